@@ -0,0 +1,104 @@
+use crate::AppState;
+use crate::merkle::AllowlistTree;
+use crate::models::escrow::{
+    AllowlistEntry, AllowlistProofResponse, AllowlistResponse, AllowlistUpload,
+};
+use axum::{
+    extract::{Extension, Json, Path},
+    http::StatusCode,
+};
+use solana_program::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use sqlx::PgPool;
+use std::collections::HashSet;
+use std::str::FromStr;
+
+/// Looks up a wallet on a stored allowlist: its cap and proof, or `None` if it is not listed
+pub async fn allowlist_claim(
+    db: &PgPool,
+    root: &str,
+    wallet: &str,
+) -> anyhow::Result<Option<(Option<u64>, Vec<Hash>)>> {
+    let row: Option<(sqlx::types::Json<Vec<AllowlistEntry>>,)> =
+        sqlx::query_as(r#"SELECT entries FROM allowlists WHERE root = $1"#)
+            .bind(root)
+            .fetch_optional(db)
+            .await?;
+    let Some((sqlx::types::Json(entries),)) = row else {
+        anyhow::bail!("allowlist {} not found", root);
+    };
+
+    let Some(index) = entries.iter().position(|entry| entry.wallet == wallet) else {
+        return Ok(None);
+    };
+    let tree = AllowlistTree::new(&entries)?;
+    Ok(Some((entries[index].cap, tree.proof(index))))
+}
+
+pub async fn create_allowlist(
+    Extension(state): Extension<AppState>,
+    Json(upload): Json<AllowlistUpload>,
+) -> Result<Json<AllowlistResponse>, (StatusCode, String)> {
+    // A wallet listed twice would have two leaves and two caps
+    let mut seen = HashSet::new();
+    if let Some(entry) = upload.entries.iter().find(|entry| !seen.insert(&entry.wallet)) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("Wallet {} is listed more than once", entry.wallet),
+        ));
+    }
+    let tree = AllowlistTree::new(&upload.entries)
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid allowlist: {}", e)))?;
+    let root = tree.root().to_string();
+
+    let res = sqlx::query(
+        r#"INSERT INTO allowlists (root, entries) VALUES ($1, $2)
+           ON CONFLICT (root) DO NOTHING"#,
+    )
+    .bind(&root)
+    .bind(sqlx::types::Json(&upload.entries))
+    .execute(&state.db)
+    .await;
+
+    match res {
+        Ok(_) => Ok(Json(AllowlistResponse {
+            root,
+            count: upload.entries.len(),
+        })),
+        Err(e) => {
+            eprintln!("❌ Failed to store allowlist {}: {:?}", root, e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to store allowlist: {}", e),
+            ))
+        }
+    }
+}
+
+pub async fn get_allowlist_proof(
+    Extension(state): Extension<AppState>,
+    Path((root, wallet)): Path<(String, String)>,
+) -> Result<Json<AllowlistProofResponse>, (StatusCode, String)> {
+    Pubkey::from_str(&wallet)
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid wallet address: {}", e)))?;
+
+    match allowlist_claim(&state.db, &root, &wallet).await {
+        Ok(Some((cap, proof))) => Ok(Json(AllowlistProofResponse {
+            root,
+            wallet,
+            cap,
+            proof: proof.iter().map(Hash::to_string).collect(),
+        })),
+        Ok(None) => Err((
+            StatusCode::NOT_FOUND,
+            format!("Wallet {} is not on allowlist {}", wallet, root),
+        )),
+        Err(e) => {
+            eprintln!("❌ Failed to build allowlist proof for {}: {:?}", wallet, e);
+            Err((
+                StatusCode::NOT_FOUND,
+                format!("Failed to build allowlist proof: {}", e),
+            ))
+        }
+    }
+}
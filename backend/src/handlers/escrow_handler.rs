@@ -1,11 +1,16 @@
-use crate::models::escrow::EscrowState;
+use crate::handlers::allowlist_handler::allowlist_claim;
+use crate::models::escrow::{CounterOfferResponse, EscrowState, ExchangeTxRequest, QuoteResponse};
 use crate::{AppState, models::escrow::UpdatedEscrow};
 use anyhow::Result;
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
 use axum::{
     extract::{Extension, Json, Path},
     http::StatusCode,
 };
 use serde_json::json;
+use solana_program::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
 
 pub async fn create_escrow(
     Extension(state): Extension<AppState>,
@@ -233,3 +238,127 @@ pub async fn delete_escrow(
     }
 }
 
+pub async fn build_exchange_transaction(
+    Extension(state): Extension<AppState>,
+    Path(address): Path<String>,
+    Json(request): Json<ExchangeTxRequest>,
+) -> Result<(StatusCode, Json<serde_json::Value>), StatusCode> {
+    println!(
+        "📩 Incoming request to build exchange tx for escrow {} (initializer {})",
+        request.escrow_pda, address
+    );
+    let existing: Result<(sqlx::types::Json<Vec<EscrowState>>,), sqlx::Error> =
+        sqlx::query_as(r#"SELECT escrows FROM users WHERE address = $1"#)
+            .bind(&address)
+            .fetch_one(&state.db)
+            .await;
+
+    let escrows = match existing {
+        Ok((sqlx::types::Json(current),)) => current,
+        Err(sqlx::Error::RowNotFound) => {
+            eprintln!("⚠️ User not found for address: {}", address);
+            return Err(StatusCode::NOT_FOUND);
+        }
+        Err(e) => {
+            eprintln!(
+                "❌ Failed to fetch existing escrows for {}: {:?}",
+                address, e
+            );
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    let Some(escrow) = escrows
+        .iter()
+        .find(|e| e.public_key == request.escrow_pda)
+    else {
+        eprintln!("❌ Escrow not found for PDA: {}", request.escrow_pda);
+        return Err(StatusCode::NOT_FOUND);
+    };
+
+    // Allowlisted escrows take the taker's Merkle proof and cap as extra `exchange` args
+    let (wallet_cap, allowlist_proof) = match escrow.account.allowlist_root {
+        Some(root) => {
+            let root = Hash::new_from_array(root).to_string();
+            match allowlist_claim(&state.db, &root, &request.taker).await {
+                Ok(Some(claim)) => claim,
+                Ok(None) => {
+                    eprintln!("⚠️ Taker {} is not on allowlist {}", request.taker, root);
+                    return Err(StatusCode::FORBIDDEN);
+                }
+                Err(e) => {
+                    eprintln!("❌ Failed to load allowlist {}: {:?}", root, e);
+                    return Err(StatusCode::NOT_FOUND);
+                }
+            }
+        }
+        None => (None, Vec::new()),
+    };
+
+    let tx = match state
+        .solana
+        .build_exchange_transaction(&escrow.account, &request, &allowlist_proof, wallet_cap)
+        .await
+    {
+        Ok(tx) => tx,
+        Err(e) => {
+            eprintln!(
+                "❌ Failed to build exchange tx for {}: {:?}",
+                request.escrow_pda, e
+            );
+            return Err(StatusCode::BAD_REQUEST);
+        }
+    };
+
+    match bincode::serialize(&tx) {
+        Ok(bytes) => Ok((
+            StatusCode::OK,
+            Json(json!({ "transaction": BASE64.encode(bytes) })),
+        )),
+        Err(e) => {
+            eprintln!("❌ Failed to serialize exchange tx: {:?}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+pub async fn get_quote(
+    Extension(state): Extension<AppState>,
+    Path(escrow_pda): Path<String>,
+) -> Result<Json<QuoteResponse>, (StatusCode, String)> {
+    let escrow = Pubkey::from_str(&escrow_pda)
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid escrow address: {}", e)))?;
+
+    match state.solana.quote(&escrow).await {
+        Ok(taker_amount) => Ok(Json(QuoteResponse {
+            escrow_pda,
+            taker_amount,
+        })),
+        Err(e) => {
+            eprintln!("❌ Failed to quote escrow {}: {:?}", escrow_pda, e);
+            Err((
+                StatusCode::BAD_REQUEST,
+                format!("Failed to quote escrow: {}", e),
+            ))
+        }
+    }
+}
+
+pub async fn get_counter_offers(
+    Extension(state): Extension<AppState>,
+    Path(escrow_pda): Path<String>,
+) -> Result<Json<Vec<CounterOfferResponse>>, (StatusCode, String)> {
+    let escrow = Pubkey::from_str(&escrow_pda)
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid escrow address: {}", e)))?;
+
+    match state.solana.counter_offers(&escrow).await {
+        Ok(offers) => Ok(Json(offers)),
+        Err(e) => {
+            eprintln!("❌ Failed to fetch counter-offers for {}: {:?}", escrow_pda, e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to fetch counter-offers: {}", e),
+            ))
+        }
+    }
+}
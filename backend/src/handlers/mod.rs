@@ -1,4 +1,6 @@
 // pub mod health_handler;
+pub mod allowlist_handler;
 pub mod escrow_handler;
+pub mod order_handler;
 pub mod stats_handler;
 pub mod user_handler;
@@ -0,0 +1,149 @@
+use crate::AppState;
+use crate::models::escrow::{SignedOrder, SignedOrderResponse, SignedOrderUpload};
+use axum::{
+    extract::{Extension, Json, Path},
+    http::StatusCode,
+};
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use std::str::FromStr;
+
+pub async fn create_signed_order(
+    Extension(state): Extension<AppState>,
+    Json(upload): Json<SignedOrderUpload>,
+) -> Result<Json<SignedOrderResponse>, (StatusCode, String)> {
+    let order = upload.order;
+    if order.maker_amount == 0 || order.taker_amount == 0 {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Order amounts must be positive".to_string(),
+        ));
+    }
+    if order.expires_at <= chrono::Utc::now().timestamp() {
+        return Err((StatusCode::BAD_REQUEST, "Order has expired".to_string()));
+    }
+
+    // Reject orders the program would refuse, so the book only holds fillable ones
+    let maker = Pubkey::from_str(&order.maker)
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid maker address: {}", e)))?;
+    let signature = Signature::from_str(&upload.signature)
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid signature: {}", e)))?;
+    let message = state
+        .solana
+        .signed_order_message(&order)
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid order: {}", e)))?;
+    if !signature.verify(maker.as_ref(), &message) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Signature does not match the order and maker".to_string(),
+        ));
+    }
+
+    let res = sqlx::query(
+        r#"INSERT INTO signed_orders (maker, nonce, order_data, signature, expires_at)
+           VALUES ($1, $2, $3, $4, $5)"#,
+    )
+    .bind(&order.maker)
+    .bind(order.nonce.to_string())
+    .bind(sqlx::types::Json(&order))
+    .bind(&upload.signature)
+    .bind(order.expires_at)
+    .execute(&state.db)
+    .await;
+
+    match res {
+        Ok(_) => Ok(Json(SignedOrderResponse {
+            order,
+            signature: upload.signature,
+            message: BASE64.encode(message),
+        })),
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => Err((
+            StatusCode::CONFLICT,
+            format!("Maker already has an order with nonce {}", order.nonce),
+        )),
+        Err(e) => {
+            eprintln!("❌ Failed to store signed order: {:?}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to store signed order: {}", e),
+            ))
+        }
+    }
+}
+
+pub async fn get_signed_orders(
+    Extension(state): Extension<AppState>,
+) -> Result<Json<Vec<SignedOrderResponse>>, (StatusCode, String)> {
+    open_signed_orders(&state, None).await.map(Json)
+}
+
+pub async fn get_maker_signed_orders(
+    Extension(state): Extension<AppState>,
+    Path(maker): Path<String>,
+) -> Result<Json<Vec<SignedOrderResponse>>, (StatusCode, String)> {
+    open_signed_orders(&state, Some(&maker)).await.map(Json)
+}
+
+/// Unexpired orders whose nonce is still unspent. Orders filled or canceled on-chain
+/// since the last read are dropped from the book here.
+async fn open_signed_orders(
+    state: &AppState,
+    maker: Option<&str>,
+) -> Result<Vec<SignedOrderResponse>, (StatusCode, String)> {
+    let rows: Vec<(sqlx::types::Json<SignedOrder>, String)> = sqlx::query_as(
+        r#"SELECT order_data, signature FROM signed_orders
+           WHERE expires_at > $1 AND ($2::TEXT IS NULL OR maker = $2)
+           ORDER BY created_at"#,
+    )
+    .bind(chrono::Utc::now().timestamp())
+    .bind(maker)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| {
+        eprintln!("❌ Failed to fetch signed orders: {:?}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to fetch signed orders: {}", e),
+        )
+    })?;
+
+    let orders: Vec<SignedOrder> = rows.iter().map(|(order, _)| order.0.clone()).collect();
+    let spent = state.solana.spent_order_nonces(&orders).await.map_err(|e| {
+        eprintln!("❌ Failed to check order nonces: {:?}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to check order nonces: {}", e),
+        )
+    })?;
+
+    let mut open = Vec::new();
+    for ((sqlx::types::Json(order), signature), spent) in rows.into_iter().zip(spent) {
+        if spent {
+            if let Err(e) =
+                sqlx::query(r#"DELETE FROM signed_orders WHERE maker = $1 AND nonce = $2"#)
+                    .bind(&order.maker)
+                    .bind(order.nonce.to_string())
+                    .execute(&state.db)
+                    .await
+            {
+                eprintln!(
+                    "⚠️ Failed to drop spent order {}/{}: {:?}",
+                    order.maker, order.nonce, e
+                );
+            }
+            continue;
+        }
+        let message = state.solana.signed_order_message(&order).map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Stored order is invalid: {}", e),
+            )
+        })?;
+        open.push(SignedOrderResponse {
+            order,
+            signature,
+            message: BASE64.encode(message),
+        });
+    }
+    Ok(open)
+}
@@ -1,7 +1,10 @@
 use crate::AppState;
 use crate::models::escrow::EscrowState;
-use crate::models::stats::{DailyCreationStats, EscrowRow, EscrowStatsResponse};
+use crate::models::stats::{DailyCreationStats, EscrowRow, EscrowStatsResponse, MintStatsResponse};
 use axum::Extension;
+use axum::extract::Path;
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
 use axum::{Json, http::StatusCode};
 use chrono::NaiveDate;
 use sqlx::types::Json as SqlxJson;
@@ -36,7 +39,7 @@ pub async fn get_escrow_stats(
         let SqlxJson(escrows_vec) = row.escrows;
 
         // 'escrows_vec' is now an owned Vec<EscrowState> and can be consumed by into_iter()
-        all_escrows.extend(escrows_vec.into_iter());
+        all_escrows.extend(escrows_vec);
     }
 
     // 2. Aggregate counts by date using Chrono
@@ -63,3 +66,27 @@ pub async fn get_escrow_stats(
 
     Ok(Json(response))
 }
+
+/// Handler for GET /api/stats/mints/{mint}
+pub async fn get_mint_stats(
+    Extension(state): Extension<AppState>,
+    Path(mint): Path<String>,
+) -> Result<Json<MintStatsResponse>, (StatusCode, String)> {
+    let mint = Pubkey::from_str(&mint)
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid mint address: {}", e)))?;
+
+    match state.solana.mint_stats(&mint).await {
+        Ok(Some(stats)) => Ok(Json(stats)),
+        Ok(None) => Err((
+            StatusCode::NOT_FOUND,
+            format!("No escrow has used mint {} yet", mint),
+        )),
+        Err(e) => {
+            eprintln!("RPC error fetching mint stats for {}: {}", mint, e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to fetch mint stats: {}", e),
+            ))
+        }
+    }
+}
@@ -4,6 +4,7 @@ use std::env;
 use tokio::net::TcpListener;
 mod errors;
 mod handlers;
+mod merkle;
 mod models;
 mod routes;
 mod state;
@@ -11,7 +12,6 @@ mod worker;
 use crate::state::AppState;
 use tower_http::cors::Any;
 use tower_http::cors::CorsLayer;
-use tracing_subscriber;
 mod solana_client;
 use crate::worker::run_keeper;
 use std::sync::Arc;
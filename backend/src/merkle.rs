@@ -0,0 +1,121 @@
+use crate::models::escrow::AllowlistEntry;
+use solana_program::hash::{Hash, hashv};
+use solana_sdk::pubkey::Pubkey;
+
+/// Leaf for a wallet, mirrors the program's `allowlist_leaf`
+pub fn allowlist_leaf(wallet: &Pubkey, cap: Option<u64>) -> [u8; 32] {
+    match cap {
+        Some(cap) => hashv(&[&[0u8], wallet.as_ref(), &cap.to_le_bytes()]).to_bytes(),
+        None => hashv(&[&[0u8], wallet.as_ref()]).to_bytes(),
+    }
+}
+
+/// Pairs are hashed in sorted order, so proofs carry no left/right flags
+fn hash_pair(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (first, second) = if a <= b { (a, b) } else { (b, a) };
+    hashv(&[&[1u8], first, second]).to_bytes()
+}
+
+/// Merkle tree over an allowlist, built bottom-up. An odd node out is promoted to the
+/// next level unchanged.
+pub struct AllowlistTree {
+    levels: Vec<Vec<[u8; 32]>>,
+}
+
+impl AllowlistTree {
+    pub fn new(entries: &[AllowlistEntry]) -> anyhow::Result<Self> {
+        anyhow::ensure!(!entries.is_empty(), "allowlist is empty");
+        let mut leaves = Vec::with_capacity(entries.len());
+        for entry in entries {
+            let wallet: Pubkey = entry.wallet.parse()?;
+            leaves.push(allowlist_leaf(&wallet, entry.cap));
+        }
+
+        let mut levels = vec![leaves];
+        while levels.last().map_or(0, Vec::len) > 1 {
+            let next = levels
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| match pair {
+                    [a, b] => hash_pair(a, b),
+                    [a] => *a,
+                    _ => unreachable!(),
+                })
+                .collect();
+            levels.push(next);
+        }
+        Ok(Self { levels })
+    }
+
+    pub fn root(&self) -> Hash {
+        Hash::new_from_array(self.levels.last().unwrap()[0])
+    }
+
+    /// Sibling hashes from the leaf at `index` up to the root
+    pub fn proof(&self, mut index: usize) -> Vec<Hash> {
+        let mut proof = Vec::new();
+        for level in &self.levels[..self.levels.len() - 1] {
+            if let Some(sibling) = level.get(index ^ 1) {
+                proof.push(Hash::new_from_array(*sibling));
+            }
+            index /= 2;
+        }
+        proof
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries(count: usize) -> Vec<AllowlistEntry> {
+        (0..count)
+            .map(|i| AllowlistEntry {
+                wallet: Pubkey::new_from_array([i as u8 + 1; 32]).to_string(),
+                cap: (i % 2 == 0).then_some(100 * (i as u64 + 1)),
+            })
+            .collect()
+    }
+
+    // Proofs built here must pass the program's own `verify_allowlist_proof`
+    #[test]
+    fn proofs_verify_on_chain() {
+        for count in [1, 2, 3, 4, 5, 7, 8, 13] {
+            let entries = entries(count);
+            let tree = AllowlistTree::new(&entries).unwrap();
+            let root = tree.root().to_bytes();
+            for (index, entry) in entries.iter().enumerate() {
+                let wallet: Pubkey = entry.wallet.parse().unwrap();
+                let leaf = escrow::allowlist_leaf(&wallet.to_bytes().into(), entry.cap);
+                assert_eq!(leaf, allowlist_leaf(&wallet, entry.cap));
+
+                let proof: Vec<[u8; 32]> =
+                    tree.proof(index).iter().map(|node| node.to_bytes()).collect();
+                assert!(
+                    escrow::verify_allowlist_proof(&proof, root, leaf),
+                    "entry {index} of {count}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn proofs_do_not_verify_for_another_cap_or_wallet() {
+        let entries = entries(5);
+        let tree = AllowlistTree::new(&entries).unwrap();
+        let root = tree.root().to_bytes();
+        let proof: Vec<[u8; 32]> = tree.proof(0).iter().map(|node| node.to_bytes()).collect();
+        let wallet: Pubkey = entries[0].wallet.parse().unwrap();
+
+        let raised_cap = escrow::allowlist_leaf(&wallet.to_bytes().into(), Some(u64::MAX));
+        assert!(!escrow::verify_allowlist_proof(&proof, root, raised_cap));
+        let outsider = escrow::allowlist_leaf(&[0xff; 32].into(), entries[0].cap);
+        assert!(!escrow::verify_allowlist_proof(&proof, root, outsider));
+    }
+
+    #[test]
+    fn empty_allowlist_is_rejected() {
+        assert!(AllowlistTree::new(&[]).is_err());
+    }
+}
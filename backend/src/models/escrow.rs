@@ -9,6 +9,15 @@ pub enum Status {
     Expired,
 }
 
+/// Asset on one leg of an escrow, as emitted by the program ("token" / "nativeSol")
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AssetKind {
+    #[default]
+    Token,
+    NativeSol,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Account {
@@ -22,6 +31,121 @@ pub struct Account {
     pub unique_seed: [u8; 8], // ← FIXED: Added unique seed to state
     pub expires_at: String,
     pub bump: u8,
+    #[serde(default)]
+    pub allowed_taker: Option<String>, // Designated taker for private escrows
+    #[serde(default)]
+    pub deposit_token_program: Option<String>, // Token program owning Token A
+    #[serde(default)]
+    pub receive_token_program: Option<String>, // Token program owning Token B
+    #[serde(default)]
+    pub deposit_asset: AssetKind, // Token A: SPL token or native SOL
+    #[serde(default)]
+    pub receive_asset: AssetKind, // Token B: SPL token or native SOL
+    #[serde(default)]
+    pub floor_price: Option<String>, // Dutch auction: the ask decays to this by expires_at
+    #[serde(default)]
+    pub price_step_seconds: Option<String>, // Dutch auction: 0 for a continuous decay
+    #[serde(default)]
+    pub collateralized: bool, // Two-sided: the taker locks Token B at accept, swapped at settle
+    #[serde(default)]
+    pub settle_at: Option<String>, // Two-sided: anyone may settle from this time on
+    #[serde(default)]
+    pub offered_nft: bool, // Token A was verified as an NFT (supply 1, 0 decimals, no mint authority)
+    #[serde(default)]
+    pub collection_bid: bool, // Token B mint is a Token-2022 collection; any member NFT fills it
+    #[serde(default)]
+    pub allowlist_root: Option<[u8; 32]>, // Merkle root of the wallets allowed to fill
+}
+/// Request body for building an unsigned `exchange` transaction for a taker
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExchangeTxRequest {
+    pub escrow_pda: String,
+    pub taker: String,
+    pub fill_amount: u64,
+    // Slippage guard: minimum Token A released and maximum Token B paid
+    pub expected_initializer_amount: u64,
+    pub max_taker_amount: u64,
+    // Mints the taker agreed to trade, checked on-chain against the escrow
+    pub expected_initializer_mint: String,
+    pub expected_taker_mint: String,
+}
+/// A wallet on an allowlist, with an optional cap on the Token B it may pay
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AllowlistEntry {
+    pub wallet: String,
+    #[serde(default)]
+    pub cap: Option<u64>,
+}
+/// Request body for uploading an allowlist
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AllowlistUpload {
+    pub entries: Vec<AllowlistEntry>,
+}
+/// Root to pass to `initialize` for an uploaded allowlist (base58)
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AllowlistResponse {
+    pub root: String,
+    pub count: usize,
+}
+/// A wallet's Merkle proof and cap, the extra `exchange` args for an allowlisted escrow
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AllowlistProofResponse {
+    pub root: String,
+    pub wallet: String,
+    pub cap: Option<u64>,
+    pub proof: Vec<String>, // Sibling hashes, leaf to root (base58)
+}
+/// Off-chain order signed by a maker, mirrors the program's `SignedOrder`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedOrder {
+    pub maker: String,
+    pub maker_mint: String,
+    pub maker_amount: u64,
+    pub taker_mint: String,
+    pub taker_amount: u64,
+    #[serde(default)]
+    pub allowed_taker: Option<String>,
+    pub expires_at: i64,
+    pub nonce: u64,
+}
+/// Request body for listing a signed order
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedOrderUpload {
+    pub order: SignedOrder,
+    pub signature: String, // Maker's Ed25519 signature over the order message (base58)
+}
+/// A stored signed order, with what a taker needs to build `fill_signed_order`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedOrderResponse {
+    pub order: SignedOrder,
+    pub signature: String,
+    pub message: String, // The signed bytes, for the Ed25519 instruction (base64)
+}
+/// Live price of an escrow, from the program's `quote` instruction
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuoteResponse {
+    pub escrow_pda: String,
+    pub taker_amount: u64, // Token B to fill the rest now, before fees
+}
+/// A taker's open counter-offer on an escrow (program `CounterOffer` account)
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CounterOfferResponse {
+    pub address: String,
+    pub escrow_pda: String,
+    pub taker: String,
+    pub taker_amount: u64,       // Token B escrowed by the taker
+    pub initializer_amount: u64, // Token A they expect, the escrow's remaining amount
+    pub expires_at: i64,
 }
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct UpdatedEscrow {
@@ -19,3 +19,15 @@ pub struct EscrowRow {
 pub struct EscrowStatsResponse {
     pub daily_creations: Vec<DailyCreationStats>,
 }
+
+/// On-chain per-mint totals, in the mint's base units
+#[derive(Debug, Serialize, Clone)]
+pub struct MintStatsResponse {
+    pub mint: String,
+    pub value_locked: u64,
+    pub value_released: u64,
+    pub value_refunded: u64,
+    pub fill_count: u64,
+    pub volume: u64,
+    pub fees_collected: u64,
+}
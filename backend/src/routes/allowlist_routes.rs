@@ -0,0 +1,14 @@
+use crate::handlers::allowlist_handler::{create_allowlist, get_allowlist_proof};
+use axum::{
+    Router,
+    routing::{get, post},
+};
+
+pub fn allowlist_routes() -> Router {
+    Router::new()
+        .route("/allowlists", post(create_allowlist))
+        .route(
+            "/allowlists/{root}/proofs/{wallet}",
+            get(get_allowlist_proof),
+        )
+}
@@ -1,12 +1,25 @@
-use crate::handlers::escrow_handler::{create_escrow, delete_escrow, get_escrows, update_escrow};
-use axum::{Router, routing::get}; // Import your shared state type
+use crate::handlers::escrow_handler::{
+    build_exchange_transaction, create_escrow, delete_escrow, get_counter_offers, get_escrows,
+    get_quote, update_escrow,
+};
+use axum::{
+    Router,
+    routing::{get, post},
+}; // Import your shared state type
 
 pub fn escrow_routes() -> Router {
-    Router::new().route(
-        "/escrows/{address}",
-        get(get_escrows)
-            .post(create_escrow)
-            .put(update_escrow)
-            .delete(delete_escrow),
-    )
+    Router::new()
+        .route(
+            "/escrows/{address}",
+            get(get_escrows)
+                .post(create_escrow)
+                .put(update_escrow)
+                .delete(delete_escrow),
+        )
+        .route(
+            "/escrows/{address}/exchange",
+            post(build_exchange_transaction),
+        )
+        .route("/quote/{escrow_pda}", get(get_quote))
+        .route("/counter-offers/{escrow_pda}", get(get_counter_offers))
 }
@@ -1,4 +1,6 @@
+pub mod allowlist_routes;
 pub mod escrow_routes;
+pub mod order_routes;
 pub mod stats_routes;
 pub mod user_routes;
 use axum::Router;
@@ -8,4 +10,6 @@ pub fn create_routes() -> Router {
         .merge(user_routes::user_routes())
         .merge(escrow_routes::escrow_routes())
         .merge(stats_routes::stats_routes())
+        .merge(allowlist_routes::allowlist_routes())
+        .merge(order_routes::order_routes())
 }
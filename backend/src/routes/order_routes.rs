@@ -0,0 +1,10 @@
+use crate::handlers::order_handler::{
+    create_signed_order, get_maker_signed_orders, get_signed_orders,
+};
+use axum::{Router, routing::get};
+
+pub fn order_routes() -> Router {
+    Router::new()
+        .route("/orders", get(get_signed_orders).post(create_signed_order))
+        .route("/orders/{maker}", get(get_maker_signed_orders))
+}
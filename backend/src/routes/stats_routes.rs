@@ -1,6 +1,8 @@
-use crate::handlers::stats_handler::get_escrow_stats;
+use crate::handlers::stats_handler::{get_escrow_stats, get_mint_stats};
 use axum::{Router, routing::get}; // Import your shared state type
 
 pub fn stats_routes() -> Router {
-    Router::new().route("/stats", get(get_escrow_stats))
+    Router::new()
+        .route("/stats", get(get_escrow_stats))
+        .route("/stats/mints/{mint}", get(get_mint_stats))
 }
@@ -1,19 +1,54 @@
-use crate::models::escrow::Account; // Assuming AppState is defined in models
+use crate::models::escrow::{
+    Account, AssetKind, CounterOfferResponse, ExchangeTxRequest, SignedOrder,
+}; // Assuming AppState is defined in models
+use crate::models::stats::MintStatsResponse;
 use anyhow::anyhow;
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
 use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcProgramAccountsConfig;
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_program::hash::Hash;
 use solana_sdk::{
     instruction::{AccountMeta, Instruction},
     pubkey::Pubkey,
     signature::{Keypair, Signer, read_keypair_file},
     transaction::Transaction,
 };
+use spl_token_2022_interface::{
+    extension::{StateWithExtensions, transfer_hook},
+    state::Mint,
+};
+use spl_transfer_hook_interface::offchain::add_extra_account_metas_for_execute;
 use std::str::FromStr;
 use tracing::{error, info};
 
-const CANCEL_IX_DISCRIMINATOR: [u8; 8] = [232, 219, 223, 41, 219, 236, 220, 190];
+const REFUND_EXPIRED_IX_DISCRIMINATOR: [u8; 8] = [118, 153, 164, 244, 40, 128, 242, 250];
+const EXCHANGE_IX_DISCRIMINATOR: [u8; 8] = [47, 3, 27, 97, 215, 236, 219, 144];
+const QUOTE_IX_DISCRIMINATOR: [u8; 8] = [149, 42, 109, 247, 134, 146, 213, 123];
+const COUNTER_OFFER_ACCOUNT_DISCRIMINATOR: [u8; 8] = [88, 123, 138, 187, 214, 100, 16, 167];
+const ASSOCIATED_TOKEN_PROGRAM_ID: &str = "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL";
+const SYSTEM_PROGRAM_ID: &str = "11111111111111111111111111111111";
+const COUNTER_OFFER_ACCOUNT_SPACE: u64 = 97;
+// Size of escrows opened before partial fills; `migrate_accounts` grows them to the current layout
+const LEGACY_ESCROW_ACCOUNT_LEN: usize = 201;
+const SIGNED_ORDER_DOMAIN: &[u8] = b"escrow-signed-order";
 
 // Assuming the Global Stats PDA seed for the client to find the key
 
+/// The parts of the program's Config account the exchange builder needs.
+struct ProtocolConfig {
+    treasury: Pubkey,
+    charge_taker_side: bool,
+    charge_initializer_side: bool,
+}
+
+/// The taker side of an accepted two-sided escrow, as recorded on-chain by `accept`.
+struct AcceptedCollateral {
+    taker: Pubkey,
+    taker_deposit_token_account: Pubkey,
+    taker_amount: u64,
+}
+
 // --- 1. SolanaClient Definition ---
 pub struct SolanaClient {
     pub rpc: RpcClient,
@@ -37,10 +72,305 @@ impl SolanaClient {
         }
     }
 
-    // --- 2. The requested function to call the Anchor 'cancel' instruction ---
-    // Creates and sends the transaction to cancel an expired escrow.
+    // Resolves the token program recorded for a mint when the escrow was created.
+    // Escrows stored before the programs were recorded fall back to the mint's owner.
+    async fn token_program(&self, recorded: &Option<String>, mint: &str) -> anyhow::Result<Pubkey> {
+        match recorded {
+            Some(program) => Ok(Pubkey::from_str(program)?),
+            None => {
+                let mint = self.rpc.get_account(&Pubkey::from_str(mint)?).await?;
+                Ok(mint.owner)
+            }
+        }
+    }
+
+    async fn deposit_token_program(&self, escrow: &Account) -> anyhow::Result<Pubkey> {
+        self.token_program(
+            &escrow.deposit_token_program,
+            &escrow.initializer_deposit_token_mint,
+        )
+        .await
+    }
+
+    async fn receive_token_program(&self, escrow: &Account) -> anyhow::Result<Pubkey> {
+        self.token_program(&escrow.receive_token_program, &escrow.taker_expected_token_mint)
+            .await
+    }
+
+    // Associated token account of `owner` for `mint` under the given token program
+    fn associated_token_address(owner: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+        let associated_token_program = Pubkey::from_str(ASSOCIATED_TOKEN_PROGRAM_ID)
+            .expect("❌ Invalid associated token program ID");
+        Pubkey::find_program_address(
+            &[owner.as_ref(), token_program.as_ref(), mint.as_ref()],
+            &associated_token_program,
+        )
+        .0
+    }
+
+    fn mint_stats_address(&self, mint: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"mint-stats", mint.as_ref()], &self.program_id).0
+    }
+
+    /// Reads a mint's on-chain `MintStats` account, `None` if no escrow has used the mint yet.
+    pub async fn mint_stats(&self, mint: &Pubkey) -> anyhow::Result<Option<MintStatsResponse>> {
+        let address = self.mint_stats_address(mint);
+        let account = self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment())
+            .await?;
+        let Some(account) = account.value else {
+            return Ok(None);
+        };
+
+        // 8 discriminator + 32 mint, then six u64 counters
+        let data = account.data;
+        if data.len() < 88 {
+            anyhow::bail!("mint stats account {} is too small", address);
+        }
+        let counter = |index: usize| {
+            let start = 40 + index * 8;
+            u64::from_le_bytes(data[start..start + 8].try_into().unwrap())
+        };
+        Ok(Some(MintStatsResponse {
+            mint: mint.to_string(),
+            value_locked: counter(0),
+            value_released: counter(1),
+            value_refunded: counter(2),
+            fill_count: counter(3),
+            volume: counter(4),
+            fees_collected: counter(5),
+        }))
+    }
+
+    /// Simulates the read-only `quote` instruction and returns the Token B a taker would pay
+    /// right now to fill the rest of the escrow (the live price for Dutch auctions).
+    pub async fn quote(&self, escrow_pda: &Pubkey) -> anyhow::Result<u64> {
+        let instruction = Instruction {
+            program_id: self.program_id,
+            data: QUOTE_IX_DISCRIMINATOR.to_vec(),
+            accounts: vec![AccountMeta::new_readonly(*escrow_pda, false)],
+        };
 
-    pub async fn cancel_if_expired(&self, pub_key: String, escrow: &Account) -> anyhow::Result<()> {
+        let latest_blockhash = self.rpc.get_latest_blockhash().await?;
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&self.payer.pubkey()),
+            &[&self.payer],
+            latest_blockhash,
+        );
+        let simulation = self.rpc.simulate_transaction(&tx).await?.value;
+        if let Some(err) = simulation.err {
+            anyhow::bail!("quote simulation failed for {}: {:?}", escrow_pda, err);
+        }
+
+        // Anchor returns the u64 as little-endian return data
+        let return_data = simulation
+            .return_data
+            .ok_or_else(|| anyhow!("quote for {} returned no data", escrow_pda))?;
+        let bytes = BASE64.decode(return_data.data.0)?;
+        let bytes: [u8; 8] = bytes
+            .as_slice()
+            .try_into()
+            .map_err(|_| anyhow!("unexpected quote return data for {}", escrow_pda))?;
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    /// Lists the open counter-offers on an escrow, read straight from the program accounts.
+    pub async fn counter_offers(&self, escrow_pda: &Pubkey) -> anyhow::Result<Vec<CounterOfferResponse>> {
+        // CounterOffer layout: 8 discriminator + 32 escrow_state + 32 taker + 8 taker_amount
+        // + 8 initializer_amount + 8 expires_at + 1 bump
+        let config = RpcProgramAccountsConfig {
+            filters: Some(vec![
+                RpcFilterType::DataSize(COUNTER_OFFER_ACCOUNT_SPACE),
+                RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+                    0,
+                    &COUNTER_OFFER_ACCOUNT_DISCRIMINATOR,
+                )),
+                RpcFilterType::Memcmp(Memcmp::new_base58_encoded(8, escrow_pda.as_ref())),
+            ]),
+            ..Default::default()
+        };
+        // The UI-account variant would only add a base64 round trip for raw account bytes
+        #[allow(deprecated)]
+        let accounts = self
+            .rpc
+            .get_program_accounts_with_config(&self.program_id, config)
+            .await?;
+
+        accounts
+            .into_iter()
+            .map(|(address, account)| {
+                let data = account.data;
+                let read_u64 = |start: usize| u64::from_le_bytes(data[start..start + 8].try_into().unwrap());
+                Ok(CounterOfferResponse {
+                    address: address.to_string(),
+                    escrow_pda: escrow_pda.to_string(),
+                    taker: Pubkey::try_from(&data[40..72])?.to_string(),
+                    taker_amount: read_u64(72),
+                    initializer_amount: read_u64(80),
+                    expires_at: read_u64(88) as i64,
+                })
+            })
+            .collect()
+    }
+
+    /// Bytes a maker signs for an order: the domain, the program id, then the order
+    /// Borsh-encoded as the program's `SignedOrder`.
+    pub fn signed_order_message(&self, order: &SignedOrder) -> anyhow::Result<Vec<u8>> {
+        let mut message = SIGNED_ORDER_DOMAIN.to_vec();
+        message.extend_from_slice(self.program_id.as_ref());
+        message.extend_from_slice(Pubkey::from_str(&order.maker)?.as_ref());
+        message.extend_from_slice(Pubkey::from_str(&order.maker_mint)?.as_ref());
+        message.extend_from_slice(&order.maker_amount.to_le_bytes());
+        message.extend_from_slice(Pubkey::from_str(&order.taker_mint)?.as_ref());
+        message.extend_from_slice(&order.taker_amount.to_le_bytes());
+        match &order.allowed_taker {
+            Some(taker) => {
+                message.push(1);
+                message.extend_from_slice(Pubkey::from_str(taker)?.as_ref());
+            }
+            None => message.push(0),
+        }
+        message.extend_from_slice(&order.expires_at.to_le_bytes());
+        message.extend_from_slice(&order.nonce.to_le_bytes());
+        Ok(message)
+    }
+
+    /// Which of the given orders have had their nonce spent on-chain, by a fill or a
+    /// cancellation; the program creates the `OrderNonce` account either way.
+    pub async fn spent_order_nonces(&self, orders: &[SignedOrder]) -> anyhow::Result<Vec<bool>> {
+        let mut nonce_addresses = Vec::with_capacity(orders.len());
+        for order in orders {
+            let maker = Pubkey::from_str(&order.maker)?;
+            let (address, _) = Pubkey::find_program_address(
+                &[b"order-nonce", maker.as_ref(), &order.nonce.to_le_bytes()],
+                &self.program_id,
+            );
+            nonce_addresses.push(address);
+        }
+
+        let mut spent = Vec::with_capacity(orders.len());
+        // getMultipleAccounts takes at most 100 keys
+        for chunk in nonce_addresses.chunks(100) {
+            let accounts = self.rpc.get_multiple_accounts(chunk).await?;
+            spent.extend(accounts.iter().map(Option::is_some));
+        }
+        Ok(spent)
+    }
+
+    /// Reads the fee settings out of the on-chain Config account.
+    async fn protocol_config(&self, config_pda: &Pubkey) -> anyhow::Result<ProtocolConfig> {
+        let data = self.rpc.get_account_data(config_pda).await?;
+        // 8 discriminator + 32 admin + 32 treasury + 2 fee_bps, then the two charge flags
+        if data.len() < 76 {
+            anyhow::bail!("config account {} is too small", config_pda);
+        }
+        Ok(ProtocolConfig {
+            treasury: Pubkey::try_from(&data[40..72])?,
+            charge_taker_side: data[74] != 0,
+            charge_initializer_side: data[75] != 0,
+        })
+    }
+
+    /// Reads the Token A still in the vault, `remaining_amount` on the on-chain `EscrowState`
+    async fn remaining_amount(&self, escrow_pda: &Pubkey) -> anyhow::Result<u64> {
+        let data = self.rpc.get_account_data(escrow_pda).await?;
+        if data.len() == LEGACY_ESCROW_ACCOUNT_LEN {
+            anyhow::bail!("escrow {} is in the legacy layout until `migrate_accounts` runs", escrow_pda);
+        }
+        // 8 discriminator + 32*4 keys + 8*2 amounts + 32 receive account + 8 unique_seed
+        // + 8 expires_at + 1 bump, then remaining_amount
+        let bytes = data
+            .get(201..209)
+            .ok_or_else(|| anyhow!("escrow {} is too small", escrow_pda))?;
+        Ok(u64::from_le_bytes(bytes.try_into()?))
+    }
+
+    /// Reads the taker side of a two-sided escrow from its on-chain `EscrowState`,
+    /// `None` unless a taker has accepted it.
+    async fn accepted_collateral(&self, escrow_pda: &Pubkey) -> anyhow::Result<Option<AcceptedCollateral>> {
+        let data = self.rpc.get_account_data(escrow_pda).await?;
+        // Legacy escrows predate two-sided escrows, so no taker can have accepted them
+        if data.len() == LEGACY_ESCROW_ACCOUNT_LEN {
+            return Ok(None);
+        }
+        // 8 discriminator + 32*4 keys + 8*2 amounts + 32 receive account + 8 unique_seed
+        // + 8 expires_at + 1 bump + 8*2 remaining/filled, then the variable-length tail
+        let mut offset = 217;
+        let skip_option = |offset: &mut usize, size: usize| -> anyhow::Result<()> {
+            let tag = *data.get(*offset).ok_or_else(|| anyhow!("escrow {} is too small", escrow_pda))?;
+            *offset += 1 + if tag == 1 { size } else { 0 };
+            Ok(())
+        };
+        skip_option(&mut offset, 32)?; // allowed_taker
+        offset += 32 * 2 + 1 + 2; // token programs, taker_pays_transfer_fee, asset kinds
+        skip_option(&mut offset, 24)?; // dutch_auction
+        // status (0 = Open, 1 = Accepted), then the collateral option
+        if data.get(offset) != Some(&1) || data.get(offset + 1) != Some(&1) {
+            return Ok(None);
+        }
+        offset += 2;
+        skip_option(&mut offset, 8)?; // settle_at
+        if data.len() < offset + 72 {
+            anyhow::bail!("escrow {} is too small", escrow_pda);
+        }
+        Ok(Some(AcceptedCollateral {
+            taker: Pubkey::try_from(&data[offset..offset + 32])?,
+            taker_deposit_token_account: Pubkey::try_from(&data[offset + 32..offset + 64])?,
+            taker_amount: u64::from_le_bytes(data[offset + 64..offset + 72].try_into().unwrap()),
+        }))
+    }
+
+    // Resolves the extra accounts a TransferHook mint needs for a transfer (extra metas,
+    // hook program and its extra-account-metas PDA). Mints without a hook need none.
+    async fn transfer_hook_accounts(
+        &self,
+        mint: &Pubkey,
+        source: &Pubkey,
+        destination: &Pubkey,
+        authority: &Pubkey,
+        amount: u64,
+    ) -> anyhow::Result<Vec<AccountMeta>> {
+        let mint_account = self.rpc.get_account(mint).await?;
+        let mint_state = StateWithExtensions::<Mint>::unpack(&mint_account.data)?;
+        let Some(hook_program_id) = transfer_hook::get_program_id(&mint_state) else {
+            return Ok(Vec::new());
+        };
+
+        // Collects the resolved metas on a scratch instruction
+        let mut hook_instruction = Instruction {
+            program_id: hook_program_id,
+            data: Vec::new(),
+            accounts: Vec::new(),
+        };
+        add_extra_account_metas_for_execute(
+            &mut hook_instruction,
+            &hook_program_id,
+            source,
+            mint,
+            destination,
+            authority,
+            amount,
+            |address| async move {
+                self.rpc
+                    .get_account(&address)
+                    .await
+                    .map(|account| Some(account.data))
+                    .map_err(Into::into)
+            },
+        )
+        .await
+        .map_err(|e| anyhow!("Failed to resolve transfer hook accounts: {}", e))?;
+
+        Ok(hook_instruction.accounts)
+    }
+
+    // --- 2. Calls the permissionless Anchor 'refund_expired' instruction ---
+    // Creates and sends the transaction returning an expired escrow to its initializer.
+    // The payer only cranks; Token A and the rent go back to the initializer.
+
+    pub async fn refund_expired(&self, pub_key: String, escrow: &Account) -> anyhow::Result<()> {
         // tracing::info!("Attempting to cancel expired escrow: {}", escrow.);
         let escrow_pda = Pubkey::from_str(&pub_key)?;
         let vault_seeds = &[
@@ -53,32 +383,73 @@ impl SolanaClient {
         let (global_stats_pda, _) =
             Pubkey::find_program_address(&[b"global-stats"], &self.program_id);
 
-        let token_program_id = self
-            .rpc
-            .get_account(&Pubkey::from_str(&escrow.initializer_deposit_token_mint)?)
-            .await?;
+        let deposit_token_program = self.deposit_token_program(escrow).await?;
+        let deposit_mint = Pubkey::from_str(&escrow.initializer_deposit_token_mint)?;
+        let initializer_deposit_token_account =
+            Pubkey::from_str(&escrow.initializer_deposit_token_account)?;
 
-        let accounts = vec![
+        let mut accounts = vec![
             AccountMeta::new(self.payer.pubkey(), true),
-            AccountMeta::new(
-                Pubkey::from_str(&escrow.initializer_deposit_token_account)?,
-                false,
-            ),
+            AccountMeta::new(Pubkey::from_str(&escrow.initializer_key)?, false),
+            AccountMeta::new(initializer_deposit_token_account, false),
             AccountMeta::new(vault_pda, false),
-            AccountMeta::new_readonly(
-                Pubkey::from_str(&escrow.initializer_deposit_token_mint)?,
-                false,
-            ),
-            AccountMeta::new(Pubkey::from_str(&pub_key)?, false),
+            AccountMeta::new_readonly(deposit_mint, false),
+            AccountMeta::new(escrow_pda, false),
             AccountMeta::new(global_stats_pda, false),
-            AccountMeta::new_readonly(token_program_id.owner, false),
+            AccountMeta::new(self.mint_stats_address(&deposit_mint), false),
+            AccountMeta::new_readonly(deposit_token_program, false),
         ];
 
+        // Vault -> initializer refund leg, forwarded by the program as remaining accounts.
+        // A native SOL vault is simply closed to the initializer, so it has no hook.
+        // Partial fills shrink the refund, so the hook sees the amount actually moved.
+        let mut hook_accounts = Vec::new();
+        if escrow.deposit_asset == AssetKind::Token {
+            hook_accounts = self
+                .transfer_hook_accounts(
+                    &deposit_mint,
+                    &vault_pda,
+                    &initializer_deposit_token_account,
+                    &escrow_pda,
+                    self.remaining_amount(&escrow_pda).await?,
+                )
+                .await?;
+        }
+
+        // Optional accounts to unwind an accepted two-sided escrow. Anchor reads them by
+        // position, so unused slots are filled with the program ID ahead of any hook accounts.
+        let mut unwind_accounts = vec![AccountMeta::new_readonly(self.program_id, false); 5];
+        if let Some(collateral) = self.accepted_collateral(&escrow_pda).await? {
+            let (taker_vault_pda, _) =
+                Pubkey::find_program_address(&[b"taker-vault", escrow_pda.as_ref()], &self.program_id);
+            let receive_mint = Pubkey::from_str(&escrow.taker_expected_token_mint)?;
+            unwind_accounts = vec![
+                AccountMeta::new(collateral.taker, false),
+                AccountMeta::new(collateral.taker_deposit_token_account, false),
+                AccountMeta::new(taker_vault_pda, false),
+                AccountMeta::new_readonly(receive_mint, false),
+                AccountMeta::new_readonly(self.receive_token_program(escrow).await?, false),
+            ];
+            // Taker vault -> taker refund leg
+            let unwind_hook_accounts = self
+                .transfer_hook_accounts(
+                    &receive_mint,
+                    &taker_vault_pda,
+                    &collateral.taker_deposit_token_account,
+                    &escrow_pda,
+                    collateral.taker_amount,
+                )
+                .await?;
+            hook_accounts.extend(unwind_hook_accounts);
+        }
+        accounts.extend(unwind_accounts);
+        accounts.extend(hook_accounts);
+
         // --- 2. Create Instruction ---
-        // Create instruction with correct discriminator (no args for cancel)
+        // Create instruction with correct discriminator (no args for refund_expired)
         let instruction = Instruction {
             program_id: self.program_id,
-            data: CANCEL_IX_DISCRIMINATOR.to_vec(),
+            data: REFUND_EXPIRED_IX_DISCRIMINATOR.to_vec(),
             accounts,
         };
 
@@ -97,15 +468,194 @@ impl SolanaClient {
         match self.rpc.send_and_confirm_transaction(&tx).await {
             Ok(sig) => {
                 info!(
-                    "✅ Successfully canceled escrow {}: Signature: {}",
+                    "✅ Successfully refunded expired escrow {}: Signature: {}",
                     pub_key, sig
                 );
                 Ok(())
             }
             Err(e) => {
-                error!("❌ Failed to cancel escrow {}: {:?}", pub_key, e);
+                error!("❌ Failed to refund expired escrow {}: {:?}", pub_key, e);
                 Err(anyhow!("Solana transaction failed: {}", e))
             }
         }
     }
+
+    // --- 3. Builds the Anchor 'exchange' instruction for a taker ---
+    // Returns an unsigned transaction (taker pays the fee) carrying the taker's slippage
+    // guard; the taker signs and submits it from their wallet.
+    pub async fn build_exchange_transaction(
+        &self,
+        escrow: &Account,
+        request: &ExchangeTxRequest,
+        allowlist_proof: &[Hash],
+        wallet_cap: Option<u64>,
+    ) -> anyhow::Result<Transaction> {
+        // Collection bids are filled with a member NFT through `fill_collection_bid`
+        if escrow.collection_bid {
+            anyhow::bail!("escrow {} is a collection bid", request.escrow_pda);
+        }
+        let escrow_pda = Pubkey::from_str(&request.escrow_pda)?;
+        let taker = Pubkey::from_str(&request.taker)?;
+        let initializer = Pubkey::from_str(&escrow.initializer_key)?;
+        let deposit_mint = Pubkey::from_str(&escrow.initializer_deposit_token_mint)?;
+        let receive_mint = Pubkey::from_str(&escrow.taker_expected_token_mint)?;
+        // Taken from the taker, not the stored escrow, so the on-chain check catches stale data
+        let expected_initializer_mint = Pubkey::from_str(&request.expected_initializer_mint)?;
+        let expected_taker_mint = Pubkey::from_str(&request.expected_taker_mint)?;
+        let initializer_deposit_token_account =
+            Pubkey::from_str(&escrow.initializer_deposit_token_account)?;
+        let initializer_receive_token_account =
+            Pubkey::from_str(&escrow.initializer_receive_token_account)?;
+
+        let (vault_pda, _) =
+            Pubkey::find_program_address(&[b"vault", escrow_pda.as_ref()], &self.program_id);
+        let (global_stats_pda, _) =
+            Pubkey::find_program_address(&[b"global-stats"], &self.program_id);
+        let (config_pda, _) = Pubkey::find_program_address(&[b"config"], &self.program_id);
+        let config = self.protocol_config(&config_pda).await?;
+
+        let deposit_token_program = self.deposit_token_program(escrow).await?;
+        let receive_token_program = self.receive_token_program(escrow).await?;
+        let taker_deposit_token_account =
+            Self::associated_token_address(&taker, &receive_mint, &receive_token_program);
+        let taker_receive_token_account =
+            Self::associated_token_address(&taker, &deposit_mint, &deposit_token_program);
+
+        // Native SOL legs omit their token accounts; the program id stands in for them
+        let receive_leg_account = |key: Pubkey| match escrow.receive_asset {
+            AssetKind::Token => AccountMeta::new(key, false),
+            AssetKind::NativeSol => AccountMeta::new_readonly(self.program_id, false),
+        };
+        let taker_receive_account = match escrow.deposit_asset {
+            AssetKind::Token => AccountMeta::new(taker_receive_token_account, false),
+            AssetKind::NativeSol => AccountMeta::new_readonly(self.program_id, false),
+        };
+        let mut accounts = vec![
+            AccountMeta::new(taker, true),
+            receive_leg_account(taker_deposit_token_account),
+            taker_receive_account,
+            receive_leg_account(initializer_receive_token_account),
+            AccountMeta::new(initializer_deposit_token_account, false),
+            AccountMeta::new(escrow_pda, false),
+            AccountMeta::new(vault_pda, false),
+            AccountMeta::new(global_stats_pda, false),
+            AccountMeta::new(self.mint_stats_address(&deposit_mint), false),
+            AccountMeta::new(self.mint_stats_address(&receive_mint), false),
+            AccountMeta::new_readonly(deposit_mint, false),
+            AccountMeta::new_readonly(receive_mint, false),
+            AccountMeta::new(initializer, false),
+            AccountMeta::new_readonly(deposit_token_program, false),
+            AccountMeta::new_readonly(receive_token_program, false),
+            AccountMeta::new_readonly(config_pda, false),
+        ];
+
+        // Treasury accounts are optional; the program id stands in for an omitted one
+        let treasury_deposit_token_account = if config.charge_initializer_side {
+            AccountMeta::new(
+                Self::associated_token_address(
+                    &config.treasury,
+                    &deposit_mint,
+                    &deposit_token_program,
+                ),
+                false,
+            )
+        } else {
+            AccountMeta::new_readonly(self.program_id, false)
+        };
+        let treasury_receive_token_account = if config.charge_taker_side {
+            AccountMeta::new(
+                Self::associated_token_address(
+                    &config.treasury,
+                    &receive_mint,
+                    &receive_token_program,
+                ),
+                false,
+            )
+        } else {
+            AccountMeta::new_readonly(self.program_id, false)
+        };
+        accounts.push(treasury_deposit_token_account);
+        accounts.push(treasury_receive_token_account);
+        accounts.push(AccountMeta::new_readonly(
+            Pubkey::from_str(SYSTEM_PROGRAM_ID)?,
+            false,
+        ));
+        // Per-wallet fill tracker, only needed for a capped allowlist entry
+        if wallet_cap.is_some() {
+            let (allowlist_fill_pda, _) = Pubkey::find_program_address(
+                &[b"allowlist-fill", escrow_pda.as_ref(), taker.as_ref()],
+                &self.program_id,
+            );
+            accounts.push(AccountMeta::new(allowlist_fill_pda, false));
+        } else {
+            accounts.push(AccountMeta::new_readonly(self.program_id, false));
+        }
+        // Temporary wSOL account a native SOL release is unwrapped through
+        if escrow.deposit_asset == AssetKind::NativeSol {
+            let (unwrap_pda, _) =
+                Pubkey::find_program_address(&[b"unwrap", escrow_pda.as_ref()], &self.program_id);
+            accounts.push(AccountMeta::new(unwrap_pda, false));
+        } else {
+            accounts.push(AccountMeta::new_readonly(self.program_id, false));
+        }
+
+        // Taker -> initializer (Token B) and vault -> taker (Token A) legs.
+        // Native SOL legs never carry a transfer hook.
+        if escrow.receive_asset == AssetKind::Token {
+            let taker_leg_hook_accounts = self
+                .transfer_hook_accounts(
+                    &receive_mint,
+                    &taker_deposit_token_account,
+                    &initializer_receive_token_account,
+                    &taker,
+                    request.max_taker_amount,
+                )
+                .await?;
+            accounts.extend(taker_leg_hook_accounts);
+        }
+        if escrow.deposit_asset == AssetKind::Token {
+            let release_leg_hook_accounts = self
+                .transfer_hook_accounts(
+                    &deposit_mint,
+                    &vault_pda,
+                    &taker_receive_token_account,
+                    &escrow_pda,
+                    request.expected_initializer_amount,
+                )
+                .await?;
+            accounts.extend(release_leg_hook_accounts);
+        }
+
+        // Discriminator + (fill_amount, expected_initializer_amount, max_taker_amount,
+        // expected_initializer_mint, expected_taker_mint, allowlist_proof, wallet_cap)
+        let mut data = EXCHANGE_IX_DISCRIMINATOR.to_vec();
+        data.extend_from_slice(&request.fill_amount.to_le_bytes());
+        data.extend_from_slice(&request.expected_initializer_amount.to_le_bytes());
+        data.extend_from_slice(&request.max_taker_amount.to_le_bytes());
+        data.extend_from_slice(expected_initializer_mint.as_ref());
+        data.extend_from_slice(expected_taker_mint.as_ref());
+        data.extend_from_slice(&(allowlist_proof.len() as u32).to_le_bytes());
+        for node in allowlist_proof {
+            data.extend_from_slice(node.as_ref());
+        }
+        match wallet_cap {
+            Some(cap) => {
+                data.push(1);
+                data.extend_from_slice(&cap.to_le_bytes());
+            }
+            None => data.push(0),
+        }
+
+        let instruction = Instruction {
+            program_id: self.program_id,
+            data,
+            accounts,
+        };
+
+        let latest_blockhash = self.rpc.get_latest_blockhash().await?;
+        let mut tx = Transaction::new_with_payer(&[instruction], Some(&taker));
+        tx.message.recent_blockhash = latest_blockhash;
+
+        Ok(tx)
+    }
 }
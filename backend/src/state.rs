@@ -22,6 +22,32 @@ impl AppState {
             .connect(database_url)
             .await
             .expect("❌ Failed to connect to DB");
+        // Uploaded allowlists, keyed by their Merkle root
+        sqlx::query(
+            r#"CREATE TABLE IF NOT EXISTS allowlists (
+                root TEXT PRIMARY KEY,
+                entries JSONB NOT NULL,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+            )"#,
+        )
+        .execute(&db)
+        .await
+        .expect("❌ Failed to create allowlists table");
+        // Signed off-chain orders awaiting a taker, one per (maker, nonce)
+        sqlx::query(
+            r#"CREATE TABLE IF NOT EXISTS signed_orders (
+                maker TEXT NOT NULL,
+                nonce TEXT NOT NULL,
+                order_data JSONB NOT NULL,
+                signature TEXT NOT NULL,
+                expires_at BIGINT NOT NULL,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                PRIMARY KEY (maker, nonce)
+            )"#,
+        )
+        .execute(&db)
+        .await
+        .expect("❌ Failed to create signed_orders table");
         let solana = SolanaClient::new(rpc_url, keypair_path, program_id).await;
 
         Self {
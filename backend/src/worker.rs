@@ -95,13 +95,18 @@ async fn scan_and_cancel(state: &AppState) -> anyhow::Result<()> {
                     "🚨 Escrow {} EXPIRED! Expiration Time ({}s) vs Current Time ({}s).",
                     escrow_pubkey, expires_at_timestamp_seconds, now_seconds_u64
                 );
+                // Native SOL vaults are unwrapped back to the initializer by the program itself
+                info!(
+                    "Refunding escrow {} ({:?} deposit).",
+                    escrow_pubkey, escrow_account_data.deposit_asset
+                );
                 match state
                     .solana
-                    .cancel_if_expired(escrow_pubkey.to_string(), &escrow_account_data) // Now passes updated initializer_key
+                    .refund_expired(escrow_pubkey.to_string(), &escrow_account_data) // Now passes updated initializer_key
                     .await
                 {
                     Ok(_) => {
-                        info!("✅ Escrow {} canceled successfully.", escrow_pubkey);
+                        info!("✅ Escrow {} refunded successfully.", escrow_pubkey);
                         // Custom SQL UPDATE query to set status to "Canceled"
                         let update_rows = sqlx::query(
                             r#"
@@ -151,7 +156,7 @@ async fn scan_and_cancel(state: &AppState) -> anyhow::Result<()> {
                     }
                     Err(e) => {
                         info!(
-                            "❌ Failed to cancel escrow {}: {}. Retrying next cycle.",
+                            "❌ Failed to refund escrow {}: {}. Retrying next cycle.",
                             escrow_pubkey, e
                         );
                         // Don't return Err—keep worker running for retries
@@ -0,0 +1,159 @@
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Arithmetic overflow occurred during calculation")]
+    Overflow,
+    #[msg("Insufficient funds in the initializer's deposit token account.")]
+    InsufficientFunds,
+    #[msg("The provided account does not belong to the correct owner.")]
+    InvalidOwner,
+    #[msg("The token mint in the provided account does not match the expected mint.")]
+    InvalidMint,
+    #[msg("The provided account key does not match the expected account in the escrow state.")]
+    InvalidAccount,
+    #[msg("The taker did not send the exact amount of expected tokens.")]
+    InvalidExchangeAmount,
+    #[msg("The token program provided is neither the Standard SPL Token Program nor the Token-2022 Program.")]
+    InvalidTokenProgram,
+    #[msg("Numerical overflow occurred.")]
+    NumericalOverflow,
+    #[msg("The escrow has not expired yet.")]
+    EscrowNotExpired,
+    #[msg("The escrow has expired and can no longer be exchanged.")]
+    EscrowExpired,
+    #[msg("The escrow duration is below the minimum allowed duration.")]
+    DurationTooShort,
+    #[msg("The escrow duration exceeds the maximum allowed duration.")]
+    DurationTooLong,
+    #[msg("Escrow amounts must be greater than zero.")]
+    InvalidAmount,
+    #[msg("The fill amount must be greater than zero and not exceed the unfilled amount.")]
+    InvalidFillAmount,
+    #[msg("The fill amount is too small to release any of the deposited tokens.")]
+    FillTooSmall,
+    #[msg("This escrow can only be filled by its designated taker.")]
+    UnauthorizedTaker,
+    #[msg("The escrow terms changed since the taker signed the exchange.")]
+    TermsChanged,
+    #[msg("The escrow would release less Token A than the taker expected.")]
+    InitializerAmountBelowExpected,
+    #[msg("The taker would pay more Token B than their maximum.")]
+    TakerAmountAboveMax,
+    #[msg("The escrow does not offer the Token A mint the taker expected.")]
+    UnexpectedInitializerMint,
+    #[msg("The escrow does not request the Token B mint the taker expected.")]
+    UnexpectedTakerMint,
+    #[msg("The protocol fee exceeds the maximum allowed fee.")]
+    FeeTooHigh,
+    #[msg("Only the admin may perform this action.")]
+    Unauthorized,
+    #[msg("The program is paused; only cancellations and refunds are allowed.")]
+    ProgramPaused,
+    #[msg("Native SOL legs must use the native mint and the SPL Token program.")]
+    InvalidNativeMint,
+    #[msg("The arbiter must be neither the buyer nor the seller.")]
+    InvalidArbiter,
+    #[msg("The escrow is under dispute and can only be settled by resolve_dispute.")]
+    EscrowDisputed,
+    #[msg("The escrow is not under dispute.")]
+    NotDisputed,
+    #[msg("Only the buyer can release before the review period ends.")]
+    ReviewPeriodActive,
+    #[msg("The review period is over; disputes can no longer be raised.")]
+    ReviewPeriodOver,
+    #[msg("A milestone escrow needs between 1 and MAX_MILESTONES milestones.")]
+    InvalidMilestoneCount,
+    #[msg("No milestone exists at this index.")]
+    InvalidMilestone,
+    #[msg("The milestone has already been approved or refunded.")]
+    MilestoneAlreadySettled,
+    #[msg("A treasury token account is required to collect the protocol fee.")]
+    MissingTreasuryAccount,
+    #[msg("Vesting needs start <= cliff <= end, start < end and an end in the future.")]
+    InvalidVestingSchedule,
+    #[msg("Nothing has vested since the last claim.")]
+    NothingToClaim,
+    #[msg("This vesting escrow cannot be revoked.")]
+    NotRevocable,
+    #[msg("The floor price must be below the start price and the step shorter than the auction.")]
+    InvalidAuctionTerms,
+    #[msg("A Dutch auction must be filled in full at the current price.")]
+    AuctionRequiresFullFill,
+    #[msg("A Dutch auction's price schedule cannot be amended.")]
+    AuctionScheduleLocked,
+    #[msg("The bid is below the reserve price or the minimum increment over the leading bid.")]
+    BidTooLow,
+    #[msg("The leading bid cannot be withdrawn before the auction is settled.")]
+    LeadingBid,
+    #[msg("The auction is still running.")]
+    AuctionActive,
+    #[msg("The auction has already been settled.")]
+    AuctionAlreadySettled,
+    #[msg("Settling an auction with a winner requires the winner's bid and token accounts.")]
+    MissingWinnerAccounts,
+    #[msg("Counter-offers are only supported when both legs are SPL tokens.")]
+    CounterOfferNativeSol,
+    #[msg("The escrow changed since the counter-offer was made.")]
+    CounterOfferStale,
+    #[msg("The counter-offer has expired.")]
+    CounterOfferExpired,
+    #[msg("The counter-offer holds less Token B than the initializer accepted.")]
+    CounterOfferBelowMinimum,
+    #[msg("Two-sided escrows settle through accept and settle, not exchange.")]
+    CollateralizedEscrow,
+    #[msg("Invalid two-sided escrow terms.")]
+    InvalidCollateralTerms,
+    #[msg("The escrow has already been accepted by a taker.")]
+    EscrowAlreadyAccepted,
+    #[msg("The escrow has not been accepted by a taker.")]
+    EscrowNotAccepted,
+    #[msg("The escrow cannot be settled yet.")]
+    CollateralNotSettleable,
+    #[msg("The escrow can be settled, so it cannot be unwound.")]
+    CollateralSettleable,
+    #[msg("Unwinding an accepted escrow requires the taker accounts.")]
+    MissingCollateralAccounts,
+    #[msg("A basket needs 1 to 5 offered and requested legs, each with its accounts.")]
+    InvalidBasketLegs,
+    #[msg("The mint is not an NFT: it needs a supply of 1, 0 decimals and no mint authority.")]
+    NotAnNft,
+    #[msg("The requested mint is not a Token-2022 collection (group) mint.")]
+    NotACollection,
+    #[msg("The NFT is not a member of the requested collection.")]
+    NotACollectionMember,
+    #[msg("Invalid NFT mode terms.")]
+    InvalidNftTerms,
+    #[msg("Collection bids are filled with a member NFT through fill_collection_bid.")]
+    CollectionBid,
+    #[msg("An allowlist cannot be combined with a designated taker, collateral or a collection bid.")]
+    InvalidAllowlist,
+    #[msg("The taker is not on the escrow's allowlist.")]
+    NotAllowlisted,
+    #[msg("Capped allowlist fills require the allowlist fill account.")]
+    MissingAllowlistFill,
+    #[msg("The fill exceeds the wallet's allowlist cap.")]
+    WalletCapExceeded,
+    #[msg("Allowlisted escrows can only be filled through exchange.")]
+    AllowlistedEscrow,
+    #[msg("The signed order has expired.")]
+    OrderExpired,
+    #[msg("The maker's Ed25519 signature instruction must directly precede the fill.")]
+    MissingOrderSignature,
+    #[msg("The Ed25519 instruction does not cover this order and maker.")]
+    InvalidOrderSignature,
+    #[msg("The signed order has already been filled.")]
+    OrderAlreadyFilled,
+    #[msg("The signed order was canceled by its maker.")]
+    OrderCanceled,
+    #[msg("Batch fills need one escrow, vault and receive account triple per fill amount, on token legs only.")]
+    InvalidBatch,
+    #[msg("The account is in neither the current nor the legacy layout.")]
+    UnknownAccountLayout,
+    #[msg("A token account required by a token leg is missing.")]
+    MissingTokenAccount,
+    #[msg("An escrow cannot trade a mint for itself.")]
+    SameMint,
+    #[msg("The minimum duration must be positive and no greater than the maximum.")]
+    InvalidDurationBounds,
+}
@@ -0,0 +1,474 @@
+use anchor_lang::prelude::*;
+use crate::state::{AssetKind, BasketLeg};
+
+#[event]
+pub struct InitializeEvent {
+    pub initializer_key: String,
+    pub initializer_deposit_token_account: String,
+    pub initializer_deposit_token_mint: String,
+    pub taker_expected_token_mint: String,
+    pub initializer_amount:String,
+    pub taker_expected_amount: String,
+    pub initializer_receive_token_account: String,
+    pub unique_seed: [u8; 8],
+    pub expires_at: i64,
+    pub bump: u8,
+    pub escrow_pda: String,
+    pub vault_account: String,
+    pub allowed_taker: Option<String>,
+    pub deposit_token_program: String,
+    pub receive_token_program: String,
+    pub deposit_transfer_fee: String,
+    pub taker_pays_transfer_fee: bool,
+    pub deposit_asset: String,
+    pub receive_asset: String,
+    pub floor_price: Option<String>,
+    pub price_step_seconds: Option<String>,
+    pub collateralized: bool,
+    pub settle_at: Option<String>,
+    pub offered_nft: bool,
+    pub collection_bid: bool,
+    pub allowlist_root: Option<[u8; 32]>,
+}
+
+#[event]
+pub struct ExchangeExecuted {
+    pub initializer: Pubkey,
+    pub taker: Pubkey,
+    pub initializer_deposit_token_mint: Pubkey,
+    pub taker_expected_token_mint: Pubkey,
+    pub initializer_amount: u64,
+    pub taker_expected_amount: u64,
+    pub taker_side_fee: u64,
+    pub initializer_side_fee: u64,
+    pub deposit_asset: AssetKind,
+    pub receive_asset: AssetKind,
+    pub unique_seed: [u8; 8],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BatchExchangeExecuted {
+    pub taker: Pubkey,
+    pub initializer_deposit_token_mint: Pubkey,
+    pub taker_expected_token_mint: Pubkey,
+    pub fill_count: u8,
+    // Sums over the batch: Token A the taker received and Token B it paid, fees included
+    pub total_initializer_amount: u64,
+    pub total_taker_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PartialFill {
+    pub initializer: Pubkey,
+    pub taker: Pubkey,
+    pub initializer_deposit_token_mint: Pubkey,
+    pub taker_expected_token_mint: Pubkey,
+    pub fill_amount: u64,
+    pub release_amount: u64,
+    pub filled_amount: u64,
+    pub remaining_amount: u64,
+    pub taker_transfer_fee: u64,
+    pub release_transfer_fee: u64,
+    pub unique_seed: [u8; 8],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EscrowCanceled {
+    pub initializer: Pubkey,
+    pub initializer_deposit_token_mint: Pubkey,
+    pub canceled_amount: u64,
+    pub deposit_asset: AssetKind,
+    pub unique_seed: [u8; 8],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EscrowExpired {
+    pub initializer: Pubkey,
+    pub cranker: Pubkey,
+    pub initializer_deposit_token_mint: Pubkey,
+    pub refunded_amount: u64,
+    pub deposit_asset: AssetKind,
+    pub unique_seed: [u8; 8],
+    pub expires_at: i64,
+    pub timestamp: i64,
+    // Token B returned to the taker when an accepted two-sided escrow is unwound
+    pub taker_refunded_amount: u64,
+}
+
+#[event]
+pub struct ConfigUpdated {
+    pub admin: Pubkey,
+    pub treasury: Pubkey,
+    pub fee_bps: u16,
+    pub charge_taker_side: bool,
+    pub charge_initializer_side: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DurationBoundsUpdated {
+    pub admin: Pubkey,
+    pub min_escrow_duration_seconds: i64,
+    pub max_escrow_duration_seconds: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct GlobalStatsInitialized {
+    pub admin: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PauseSet {
+    pub admin: Pubkey,
+    pub paused: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityProposed {
+    pub admin: Pubkey,
+    pub proposed_admin: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityAccepted {
+    pub previous_admin: Pubkey,
+    pub new_admin: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ServiceEscrowCreated {
+    pub buyer: Pubkey,
+    pub seller: Pubkey,
+    pub arbiter: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub review_deadline: i64,
+    pub unique_seed: [u8; 8],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ServiceEscrowReleased {
+    pub buyer: Pubkey,
+    pub seller: Pubkey,
+    pub released_by: Pubkey,
+    pub amount: u64,
+    // Released by someone other than the buyer after the review period
+    pub auto_release: bool,
+    pub unique_seed: [u8; 8],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DisputeRaised {
+    pub buyer: Pubkey,
+    pub seller: Pubkey,
+    pub arbiter: Pubkey,
+    pub raised_by: Pubkey,
+    pub dispute_deadline: i64,
+    pub unique_seed: [u8; 8],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DisputeResolved {
+    pub buyer: Pubkey,
+    pub seller: Pubkey,
+    pub resolved_by: Pubkey,
+    pub seller_amount: u64,
+    pub buyer_amount: u64,
+    // False when the arbiter missed the deadline and the buyer was refunded by default
+    pub by_arbiter: bool,
+    pub unique_seed: [u8; 8],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MilestoneEscrowCreated {
+    pub payer: Pubkey,
+    pub payee: Pubkey,
+    pub mint: Pubkey,
+    pub total_amount: u64,
+    pub milestone_count: u8,
+    pub unique_seed: [u8; 8],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MilestoneApproved {
+    pub payer: Pubkey,
+    pub payee: Pubkey,
+    pub index: u8,
+    pub amount: u64,
+    // True when this was the last open milestone and the escrow was closed
+    pub settled: bool,
+    pub unique_seed: [u8; 8],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MilestoneRefunded {
+    pub payer: Pubkey,
+    pub cranker: Pubkey,
+    pub index: u8,
+    pub amount: u64,
+    pub deadline: i64,
+    // True when this was the last open milestone and the escrow was closed
+    pub settled: bool,
+    pub unique_seed: [u8; 8],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VestingEscrowCreated {
+    pub initializer: Pubkey,
+    pub beneficiary: Pubkey,
+    pub mint: Pubkey,
+    pub total_amount: u64,
+    pub start_time: i64,
+    pub cliff_time: i64,
+    pub end_time: i64,
+    pub revocable: bool,
+    pub unique_seed: [u8; 8],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VestedClaimed {
+    pub initializer: Pubkey,
+    pub beneficiary: Pubkey,
+    pub amount: u64,
+    pub total_claimed: u64,
+    pub total_amount: u64,
+    // True when everything has been claimed and the escrow was closed
+    pub settled: bool,
+    pub unique_seed: [u8; 8],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VestingRevoked {
+    pub initializer: Pubkey,
+    pub beneficiary: Pubkey,
+    // Vested but unclaimed tokens, paid out to the beneficiary on revocation
+    pub vested_amount: u64,
+    pub unvested_amount: u64,
+    pub unique_seed: [u8; 8],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuctionCreated {
+    pub seller: Pubkey,
+    pub deposit_mint: Pubkey,
+    pub bid_mint: Pubkey,
+    pub amount: u64,
+    pub reserve_price: u64,
+    pub min_increment: u64,
+    pub expires_at: i64,
+    pub unique_seed: [u8; 8],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BidPlaced {
+    pub auction: Pubkey,
+    pub bidder: Pubkey,
+    pub amount: u64,
+    // The bidder who was leading before this bid, now free to withdraw
+    pub previous_bidder: Option<Pubkey>,
+    pub previous_bid: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BidWithdrawn {
+    pub auction: Pubkey,
+    pub bidder: Pubkey,
+    pub withdrawn_by: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuctionSettled {
+    pub auction: Pubkey,
+    pub seller: Pubkey,
+    // None when nobody met the reserve and the deposit went back to the seller
+    pub winner: Option<Pubkey>,
+    pub winning_bid: u64,
+    pub amount: u64,
+    pub settled_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CounterOfferMade {
+    pub escrow: Pubkey,
+    pub taker: Pubkey,
+    pub taker_amount: u64,
+    pub initializer_amount: u64,
+    pub expires_at: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CounterOfferAccepted {
+    pub escrow: Pubkey,
+    pub initializer: Pubkey,
+    pub taker: Pubkey,
+    pub taker_amount: u64,
+    pub initializer_amount: u64,
+    pub taker_side_fee: u64,
+    pub initializer_side_fee: u64,
+    pub unique_seed: [u8; 8],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CounterOfferWithdrawn {
+    pub escrow: Pubkey,
+    pub taker: Pubkey,
+    pub withdrawn_by: Pubkey,
+    pub amount: u64,
+    // True when cranked by someone other than the taker after the counter-offer expired
+    pub expired: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EscrowAccepted {
+    pub initializer: Pubkey,
+    pub taker: Pubkey,
+    pub taker_amount: u64,
+    pub settle_at: Option<i64>,
+    pub unique_seed: [u8; 8],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SettlementConfirmed {
+    pub initializer: Pubkey,
+    pub confirmed_by: Pubkey,
+    // True once both parties have confirmed
+    pub fully_confirmed: bool,
+    pub unique_seed: [u8; 8],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CollateralSettled {
+    pub initializer: Pubkey,
+    pub taker: Pubkey,
+    pub settled_by: Pubkey,
+    pub initializer_amount: u64,
+    pub taker_amount: u64,
+    pub taker_side_fee: u64,
+    pub initializer_side_fee: u64,
+    // True when both parties confirmed, false when settled at `settle_at`
+    pub by_confirmation: bool,
+    pub unique_seed: [u8; 8],
+    pub timestamp: i64,
+}
+
+/// One leg of a basket exchange, with the protocol fee charged on it.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BasketLegFill {
+    pub mint: Pubkey,
+    // Paid to the counterparty, before the protocol fee
+    pub amount: u64,
+    pub protocol_fee: u64,
+}
+
+#[event]
+pub struct BasketEscrowCreated {
+    pub initializer: Pubkey,
+    pub basket: Pubkey,
+    pub offered: Vec<BasketLeg>,
+    pub requested: Vec<BasketLeg>,
+    pub allowed_taker: Option<Pubkey>,
+    pub expires_at: i64,
+    pub unique_seed: [u8; 8],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BasketExchanged {
+    pub initializer: Pubkey,
+    pub taker: Pubkey,
+    pub basket: Pubkey,
+    // Offered legs went to the taker, requested legs to the initializer
+    pub offered: Vec<BasketLegFill>,
+    pub requested: Vec<BasketLegFill>,
+    pub unique_seed: [u8; 8],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BasketCanceled {
+    pub initializer: Pubkey,
+    pub canceled_by: Pubkey,
+    pub basket: Pubkey,
+    // Amounts actually returned from each vault
+    pub refunded: Vec<BasketLeg>,
+    // True when cranked by someone other than the initializer after expiry
+    pub expired: bool,
+    pub unique_seed: [u8; 8],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CollectionBidFilled {
+    pub initializer: Pubkey,
+    pub taker: Pubkey,
+    // The Token-2022 group mint the bid was made on
+    pub collection: Pubkey,
+    pub member_mint: Pubkey,
+    pub initializer_amount: u64,
+    pub initializer_side_fee: u64,
+    pub unique_seed: [u8; 8],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SignedOrderFilled {
+    pub maker: Pubkey,
+    pub taker: Pubkey,
+    pub maker_mint: Pubkey,
+    pub taker_mint: Pubkey,
+    pub maker_amount: u64,
+    pub taker_amount: u64,
+    pub nonce: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SignedOrderCanceled {
+    pub maker: Pubkey,
+    pub nonce: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EscrowAmended {
+    pub initializer: Pubkey,
+    pub old_initializer_amount: u64,
+    pub new_initializer_amount: u64,
+    pub old_taker_expected_amount: u64,
+    pub new_taker_expected_amount: u64,
+    pub old_expires_at: i64,
+    pub new_expires_at: i64,
+    pub top_up_amount: u64,
+    pub withdraw_amount: u64,
+    pub unique_seed: [u8; 8],
+    pub timestamp: i64,
+}
@@ -0,0 +1,555 @@
+use crate::*;
+
+// Helper function to check if the provided Pubkey is a valid token program ID
+pub(crate) fn check_token_program_id(program_id: &Pubkey) -> Result<()> {
+    if program_id.eq(&TOKEN_PROGRAM_ID) || program_id.eq(&TOKEN_2022_PROGRAM_ID) {
+        Ok(())
+    } else {
+        Err(ErrorCode::InvalidTokenProgram.into())
+    }
+}
+
+// Helper function to keep escrow lifetimes within the configured bounds
+pub(crate) fn check_duration(config: &Config, duration_in_seconds: i64) -> Result<()> {
+    require!(
+        duration_in_seconds >= config.min_escrow_duration_seconds,
+        ErrorCode::DurationTooShort
+    );
+    require!(
+        duration_in_seconds <= config.max_escrow_duration_seconds,
+        ErrorCode::DurationTooLong
+    );
+    Ok(())
+}
+
+// Protocol fee on `amount`, rounded down in favor of the payer
+pub(crate) fn protocol_fee(amount: u64, fee_bps: u16) -> Result<u64> {
+    let fee = (amount as u128)
+        .checked_mul(fee_bps as u128)
+        .ok_or(ErrorCode::Overflow)?
+        / BPS_DENOMINATOR as u128;
+    u64::try_from(fee).map_err(|_| error!(ErrorCode::Overflow))
+}
+
+// Token A released for `fill_amount` of Token B, rounded down in favor of the initializer
+pub(crate) fn pro_rata_release(fill_amount: u64, initializer_amount: u64, taker_expected_amount: u64) -> Result<u64> {
+    let released = (fill_amount as u128)
+        .checked_mul(initializer_amount as u128)
+        .ok_or(ErrorCode::Overflow)?
+        / taker_expected_amount as u128;
+    u64::try_from(released).map_err(|_| error!(ErrorCode::Overflow))
+}
+
+// Total Token B asked for the whole deposit at `now`. Fixed-price escrows always ask
+// `taker_expected_amount`; Dutch auctions decay from it to the floor price between
+// `start_time` and `expires_at`, continuously or in `step_seconds` steps. The decay is
+// rounded down, so the ask never drops below the schedule in the taker's favour.
+pub(crate) fn current_ask(escrow_state: &EscrowState, now: i64) -> Result<u64> {
+    let Some(auction) = escrow_state.dutch_auction else {
+        return Ok(escrow_state.taker_expected_amount);
+    };
+    let duration = escrow_state
+        .expires_at
+        .checked_sub(auction.start_time)
+        .ok_or(ErrorCode::Overflow)?;
+    if duration <= 0 {
+        return Ok(auction.floor_price);
+    }
+    let mut elapsed = now.clamp(auction.start_time, escrow_state.expires_at) - auction.start_time;
+    if auction.step_seconds > 0 {
+        elapsed -= elapsed % auction.step_seconds;
+    }
+    let decay_range = escrow_state
+        .taker_expected_amount
+        .checked_sub(auction.floor_price)
+        .ok_or(ErrorCode::Overflow)?;
+    let decayed = (decay_range as u128)
+        .checked_mul(elapsed as u128)
+        .ok_or(ErrorCode::Overflow)?
+        / duration as u128;
+    Ok(escrow_state.taker_expected_amount - decayed as u64)
+}
+
+// Amount of a vesting escrow unlocked at `now`: nothing before the cliff, linear from
+// `start_time` to `end_time`, rounded down so the beneficiary is never ahead of schedule
+pub(crate) fn vested_amount(vesting_escrow: &VestingEscrow, now: i64) -> Result<u64> {
+    if now < vesting_escrow.cliff_time {
+        return Ok(0);
+    }
+    if now >= vesting_escrow.end_time {
+        return Ok(vesting_escrow.total_amount);
+    }
+    let elapsed = (now - vesting_escrow.start_time) as u128;
+    let duration = (vesting_escrow.end_time - vesting_escrow.start_time) as u128;
+    let vested = (vesting_escrow.total_amount as u128)
+        .checked_mul(elapsed)
+        .ok_or(ErrorCode::Overflow)?
+        / duration;
+    u64::try_from(vested).map_err(|_| error!(ErrorCode::Overflow))
+}
+
+// Reads the TransferFee extension of a Token-2022 mint; other mints never charge a fee
+pub(crate) fn transfer_fee_config(mint: &AccountInfo) -> Result<Option<TransferFeeConfig>> {
+    if !mint.owner.eq(&TOKEN_2022_PROGRAM_ID) {
+        return Ok(None);
+    }
+    let data = mint.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<Token2022Mint>::unpack(&data)?;
+    Ok(mint_state.get_extension::<TransferFeeConfig>().ok().copied())
+}
+
+// Fee withheld by the mint when `amount` is transferred in the current epoch
+pub(crate) fn transfer_fee(mint: &AccountInfo, amount: u64) -> Result<u64> {
+    match transfer_fee_config(mint)? {
+        Some(config) => config
+            .calculate_epoch_fee(Clock::get()?.epoch, amount)
+            .ok_or_else(|| error!(ErrorCode::Overflow)),
+        None => Ok(0),
+    }
+}
+
+// Amount that must be sent so the recipient nets `net_amount` after the transfer fee
+pub(crate) fn gross_up_for_fee(mint: &AccountInfo, net_amount: u64) -> Result<u64> {
+    match transfer_fee_config(mint)? {
+        Some(config) => {
+            let fee = config
+                .calculate_inverse_epoch_fee(Clock::get()?.epoch, net_amount)
+                .ok_or(ErrorCode::Overflow)?;
+            net_amount.checked_add(fee).ok_or_else(|| error!(ErrorCode::Overflow))
+        }
+        None => Ok(net_amount),
+    }
+}
+
+// `transfer_checked` that forwards the context's remaining accounts, so mints with the
+// TransferHook extension get their hook program, extra-account-metas PDA and extra
+// accounts appended. Mints without a hook transfer exactly like a plain CPI.
+pub(crate) fn transfer_checked_with_hook<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, TransferChecked<'info>>,
+    amount: u64,
+    decimals: u8,
+) -> Result<()> {
+    spl_token_2022::onchain::invoke_transfer_checked(
+        ctx.program.key,
+        ctx.accounts.from,
+        ctx.accounts.mint,
+        ctx.accounts.to,
+        ctx.accounts.authority,
+        &ctx.remaining_accounts,
+        amount,
+        decimals,
+        ctx.signer_seeds,
+    )
+    .map_err(Into::into)
+}
+
+// Deposits `amount` into the vault the context transfers to and returns the vault's balance
+// afterwards. Token-2022 transfer fees are withheld from the deposit, so callers record what
+// the vault actually holds rather than the amount sent.
+pub(crate) fn deposit_to_vault<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, TransferChecked<'info>>,
+    amount: u64,
+    decimals: u8,
+) -> Result<u64> {
+    let vault = ctx.accounts.to.clone();
+    let token_program = ctx.program.key();
+    transfer_checked_with_hook(ctx, amount, decimals)?;
+    Ok(read_token_account(&vault, &token_program)?.amount)
+}
+
+// An accepted two-sided escrow settles once both parties confirmed or its settlement time has come
+pub(crate) fn collateral_settleable(collateral: &Collateral, now: i64) -> bool {
+    (collateral.initializer_confirmed && collateral.taker_confirmed)
+        || collateral.settle_at.is_some_and(|settle_at| now >= settle_at)
+}
+
+// Allowlist leaf for a wallet, committing to its per-wallet cap when it has one. Leaves
+// and inner nodes use distinct prefixes so an inner node can never pass as a leaf.
+pub fn allowlist_leaf(wallet: &Pubkey, wallet_cap: Option<u64>) -> [u8; 32] {
+    match wallet_cap {
+        Some(cap) => hashv(&[&[0u8], wallet.as_ref(), &cap.to_le_bytes()]).to_bytes(),
+        None => hashv(&[&[0u8], wallet.as_ref()]).to_bytes(),
+    }
+}
+
+// Walks a Merkle proof from `leaf` up to `root`. Pairs are hashed in sorted order, so
+// the proof carries no left/right flags.
+pub fn verify_allowlist_proof(proof: &[[u8; 32]], root: [u8; 32], leaf: [u8; 32]) -> bool {
+    let computed = proof.iter().fold(leaf, |node, sibling| {
+        let (first, second) = if node <= *sibling {
+            (node, *sibling)
+        } else {
+            (*sibling, node)
+        };
+        hashv(&[&[1u8][..], &first, &second]).to_bytes()
+    });
+    computed == root
+}
+
+// Bytes a maker signs for `order`: the domain, this program's id, then the Borsh-encoded order
+pub(crate) fn signed_order_message(order: &SignedOrder) -> Result<Vec<u8>> {
+    let mut message = SIGNED_ORDER_DOMAIN.to_vec();
+    message.extend_from_slice(crate::ID.as_ref());
+    order.serialize(&mut message)?;
+    Ok(message)
+}
+
+// Checks that the instruction right before this one is an Ed25519 program check of
+// `message` signed by `signer`. The precompile has already verified the signature when
+// we run; this only confirms it covered the expected key and bytes, all carried inline.
+pub(crate) fn verify_ed25519_ix(instructions: &AccountInfo, signer: &Pubkey, message: &[u8]) -> Result<()> {
+    let current_index = load_current_index_checked(instructions)?;
+    require!(current_index > 0, ErrorCode::MissingOrderSignature);
+    let ix = load_instruction_at_checked(current_index as usize - 1, instructions)?;
+    require_keys_eq!(
+        ix.program_id,
+        ed25519_program::ID,
+        ErrorCode::MissingOrderSignature
+    );
+
+    // [num_signatures: u8, padding: u8, then seven u16 offsets for the single signature]
+    let data = &ix.data;
+    require!(data.len() >= 16 && data[0] == 1, ErrorCode::InvalidOrderSignature);
+    let read_u16 = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]);
+    let signature_ix_index = read_u16(4);
+    let public_key_offset = read_u16(6) as usize;
+    let public_key_ix_index = read_u16(8);
+    let message_offset = read_u16(10) as usize;
+    let message_size = read_u16(12) as usize;
+    let message_ix_index = read_u16(14);
+    // u16::MAX points the precompile at its own data; anything else could verify other bytes
+    require!(
+        signature_ix_index == u16::MAX
+            && public_key_ix_index == u16::MAX
+            && message_ix_index == u16::MAX,
+        ErrorCode::InvalidOrderSignature
+    );
+
+    let signed_key = data
+        .get(public_key_offset..public_key_offset + 32)
+        .ok_or(ErrorCode::InvalidOrderSignature)?;
+    let signed_message = data
+        .get(message_offset..message_offset + message_size)
+        .ok_or(ErrorCode::InvalidOrderSignature)?;
+    require!(
+        signed_key == signer.as_ref() && signed_message == message,
+        ErrorCode::InvalidOrderSignature
+    );
+    Ok(())
+}
+
+// A genuine NFT: a single token, no decimals, and no authority left to mint more
+pub(crate) fn check_nft_mint(mint: &Mint) -> Result<()> {
+    require!(
+        mint.supply == 1 && mint.decimals == 0 && mint.mint_authority.is_none(),
+        ErrorCode::NotAnNft
+    );
+    Ok(())
+}
+
+// Whether a Token-2022 mint is a collection, i.e. carries the TokenGroup extension
+pub(crate) fn is_token_group(mint: &AccountInfo) -> Result<bool> {
+    if !mint.owner.eq(&TOKEN_2022_PROGRAM_ID) {
+        return Ok(false);
+    }
+    let data = mint.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<Token2022Mint>::unpack(&data)?;
+    Ok(mint_state.get_extension::<TokenGroup>().is_ok())
+}
+
+// Collection a Token-2022 mint belongs to, from its TokenGroupMember extension
+pub(crate) fn token_group_of(mint: &AccountInfo) -> Result<Option<Pubkey>> {
+    if !mint.owner.eq(&TOKEN_2022_PROGRAM_ID) {
+        return Ok(None);
+    }
+    let data = mint.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<Token2022Mint>::unpack(&data)?;
+    Ok(mint_state
+        .get_extension::<TokenGroupMember>()
+        .ok()
+        .map(|member| member.group))
+}
+
+// Reads a mint passed through remaining accounts, checking it belongs to `token_program`
+pub(crate) fn read_mint(info: &AccountInfo, token_program: &Pubkey) -> Result<Mint> {
+    require_keys_eq!(*info.owner, *token_program, ErrorCode::InvalidTokenProgram);
+    let data = info.try_borrow_data()?;
+    Mint::try_deserialize(&mut &data[..])
+}
+
+// Reads a token account passed through remaining accounts, checking it belongs to `token_program`
+pub(crate) fn read_token_account(info: &AccountInfo, token_program: &Pubkey) -> Result<TokenAccount> {
+    require_keys_eq!(*info.owner, *token_program, ErrorCode::InvalidTokenProgram);
+    let data = info.try_borrow_data()?;
+    TokenAccount::try_deserialize(&mut &data[..])
+}
+
+// Native SOL legs ride on the legacy native mint, so vaults and accounts stay token accounts
+pub(crate) fn check_asset_kind(kind: AssetKind, mint: &Pubkey, token_program: &Pubkey) -> Result<()> {
+    if kind == AssetKind::NativeSol {
+        require_keys_eq!(*mint, NATIVE_MINT, ErrorCode::InvalidNativeMint);
+        require_keys_eq!(*token_program, TOKEN_PROGRAM_ID, ErrorCode::InvalidNativeMint);
+    }
+    Ok(())
+}
+
+// Wraps SOL: moves lamports into a native-mint token account and syncs its balance
+pub(crate) fn wrap_lamports<'info>(
+    system_program: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    from: AccountInfo<'info>,
+    token_account: AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    anchor_lang::system_program::transfer(
+        CpiContext::new(
+            system_program,
+            anchor_lang::system_program::Transfer {
+                from,
+                to: token_account.clone(),
+            },
+        ),
+        amount,
+    )?;
+    token_interface::sync_native(CpiContext::new(
+        token_program,
+        SyncNative {
+            account: token_account,
+        },
+    ))
+}
+
+// Pays `amount` out of a native SOL vault as lamports. The wSOL moves into `unwrap_account`,
+// a temporary wSOL account owned by the escrow, which is then closed to `recipient` with its
+// rent; none of the recipient's own wSOL is touched.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn unwrap_from_vault<'info>(
+    token_program: AccountInfo<'info>,
+    native_mint: &InterfaceAccount<'info, Mint>,
+    vault: AccountInfo<'info>,
+    unwrap_account: AccountInfo<'info>,
+    escrow_state: AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+    recipient: AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            token_program.clone(),
+            TransferChecked {
+                from: vault,
+                mint: native_mint.to_account_info(),
+                to: unwrap_account.clone(),
+                authority: escrow_state.clone(),
+            },
+            signer_seeds,
+        ),
+        amount,
+        native_mint.decimals,
+    )?;
+    token_interface::close_account(CpiContext::new_with_signer(
+        token_program,
+        CloseAccount {
+            account: unwrap_account,
+            destination: recipient,
+            authority: escrow_state,
+        },
+        signer_seeds,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn escrow_with_auction(
+        taker_expected_amount: u64,
+        expires_at: i64,
+        dutch_auction: Option<DutchAuction>,
+    ) -> EscrowState {
+        EscrowState {
+            initializer_key: Pubkey::default(),
+            initializer_deposit_token_account: Pubkey::default(),
+            initializer_deposit_token_mint: Pubkey::default(),
+            taker_expected_token_mint: Pubkey::default(),
+            initializer_amount: 1_000,
+            taker_expected_amount,
+            initializer_receive_token_account: Pubkey::default(),
+            unique_seed: [0; 8],
+            expires_at,
+            bump: 0,
+            remaining_amount: 1_000,
+            filled_amount: 0,
+            allowed_taker: None,
+            deposit_token_program: TOKEN_PROGRAM_ID,
+            receive_token_program: TOKEN_PROGRAM_ID,
+            taker_pays_transfer_fee: false,
+            deposit_asset: AssetKind::Token,
+            receive_asset: AssetKind::Token,
+            dutch_auction,
+            status: EscrowStatus::Open,
+            collateral: None,
+            collection_bid: false,
+            allowlist_root: None,
+        }
+    }
+
+    fn vesting(total_amount: u64, start_time: i64, cliff_time: i64, end_time: i64) -> VestingEscrow {
+        VestingEscrow {
+            initializer: Pubkey::default(),
+            beneficiary: Pubkey::default(),
+            mint: Pubkey::default(),
+            token_program: TOKEN_PROGRAM_ID,
+            total_amount,
+            claimed_amount: 0,
+            start_time,
+            cliff_time,
+            end_time,
+            revocable: false,
+            unique_seed: [0; 8],
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn pro_rata_release_rounds_down() {
+        // 10 A for 3 B: each B is worth 3.33 A
+        assert_eq!(pro_rata_release(1, 10, 3).unwrap(), 3);
+        assert_eq!(pro_rata_release(2, 10, 3).unwrap(), 6);
+        assert_eq!(pro_rata_release(3, 10, 3).unwrap(), 10);
+        // Too small to release anything
+        assert_eq!(pro_rata_release(1, 2, 3).unwrap(), 0);
+    }
+
+    #[test]
+    fn pro_rata_release_leaves_dust_for_the_final_fill() {
+        let (initializer_amount, taker_expected_amount) = (10, 3);
+        let released: u64 = (0..3)
+            .map(|_| pro_rata_release(1, initializer_amount, taker_expected_amount).unwrap())
+            .sum();
+        // Three single fills release 3 each; the final fill returns the last unit as dust
+        assert_eq!(released, 9);
+        assert_eq!(initializer_amount - released, 1);
+    }
+
+    #[test]
+    fn pro_rata_release_rejects_overflow() {
+        assert!(pro_rata_release(u64::MAX, u64::MAX, 1).is_err());
+        assert_eq!(pro_rata_release(u64::MAX, u64::MAX, u64::MAX).unwrap(), u64::MAX);
+    }
+
+    // Every `Option` set is the largest an escrow can serialize to
+    #[test]
+    fn escrow_account_space_fits_the_largest_escrow() {
+        let auction = DutchAuction {
+            floor_price: 100,
+            start_time: 0,
+            step_seconds: 10,
+        };
+        let mut escrow_state = escrow_with_auction(1_000, 100, Some(auction));
+        escrow_state.allowed_taker = Some(Pubkey::default());
+        escrow_state.collateral = Some(Collateral {
+            settle_at: Some(0),
+            taker: Pubkey::default(),
+            taker_deposit_token_account: Pubkey::default(),
+            taker_amount: 0,
+            initializer_confirmed: false,
+            taker_confirmed: false,
+        });
+        escrow_state.allowlist_root = Some([0; 32]);
+
+        let mut data = Vec::new();
+        escrow_state.try_serialize(&mut data).unwrap();
+        assert_eq!(data.len(), ESCROW_ACCOUNT_SPACE);
+    }
+
+    #[test]
+    fn current_ask_is_fixed_without_an_auction() {
+        let escrow_state = escrow_with_auction(1_000, 100, None);
+        assert_eq!(current_ask(&escrow_state, 0).unwrap(), 1_000);
+        assert_eq!(current_ask(&escrow_state, 1_000).unwrap(), 1_000);
+    }
+
+    #[test]
+    fn current_ask_drops_on_step_boundaries() {
+        let auction = DutchAuction {
+            floor_price: 100,
+            start_time: 0,
+            step_seconds: 10,
+        };
+        let escrow_state = escrow_with_auction(1_000, 100, Some(auction));
+        assert_eq!(current_ask(&escrow_state, -5).unwrap(), 1_000);
+        assert_eq!(current_ask(&escrow_state, 0).unwrap(), 1_000);
+        assert_eq!(current_ask(&escrow_state, 9).unwrap(), 1_000);
+        assert_eq!(current_ask(&escrow_state, 10).unwrap(), 910);
+        assert_eq!(current_ask(&escrow_state, 19).unwrap(), 910);
+        assert_eq!(current_ask(&escrow_state, 99).unwrap(), 190);
+    }
+
+    #[test]
+    fn current_ask_stops_at_the_floor() {
+        let auction = DutchAuction {
+            floor_price: 100,
+            start_time: 0,
+            step_seconds: 0,
+        };
+        let escrow_state = escrow_with_auction(1_000, 100, Some(auction));
+        assert_eq!(current_ask(&escrow_state, 100).unwrap(), 100);
+        assert_eq!(current_ask(&escrow_state, 1_000).unwrap(), 100);
+        // Continuous decay is rounded down, so the ask stays at or above the schedule
+        let escrow_state = escrow_with_auction(1_000, 7, Some(auction));
+        assert_eq!(current_ask(&escrow_state, 1).unwrap(), 872);
+    }
+
+    #[test]
+    fn current_ask_is_the_floor_for_an_empty_schedule() {
+        let auction = DutchAuction {
+            floor_price: 100,
+            start_time: 50,
+            step_seconds: 0,
+        };
+        let escrow_state = escrow_with_auction(1_000, 50, Some(auction));
+        assert_eq!(current_ask(&escrow_state, 0).unwrap(), 100);
+    }
+
+    #[test]
+    fn vested_amount_is_zero_before_the_cliff() {
+        let vesting_escrow = vesting(1_000, 0, 25, 100);
+        assert_eq!(vested_amount(&vesting_escrow, -1).unwrap(), 0);
+        assert_eq!(vested_amount(&vesting_escrow, 24).unwrap(), 0);
+        // At the cliff the linear schedule catches up from `start_time`
+        assert_eq!(vested_amount(&vesting_escrow, 25).unwrap(), 250);
+    }
+
+    #[test]
+    fn vested_amount_is_everything_from_the_end() {
+        let vesting_escrow = vesting(1_000, 0, 25, 100);
+        assert_eq!(vested_amount(&vesting_escrow, 99).unwrap(), 990);
+        assert_eq!(vested_amount(&vesting_escrow, 100).unwrap(), 1_000);
+        assert_eq!(vested_amount(&vesting_escrow, 1_000).unwrap(), 1_000);
+    }
+
+    #[test]
+    fn vested_amount_rounds_down() {
+        let vesting_escrow = vesting(10, 0, 0, 3);
+        assert_eq!(vested_amount(&vesting_escrow, 1).unwrap(), 3);
+        assert_eq!(vested_amount(&vesting_escrow, 2).unwrap(), 6);
+        assert_eq!(vested_amount(&vesting_escrow, 3).unwrap(), 10);
+    }
+
+    #[test]
+    fn allowlist_proof_rejects_wrong_leaves() {
+        let wallet = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        let leaf = allowlist_leaf(&wallet, Some(5));
+        let sibling = allowlist_leaf(&other, None);
+        let (first, second) = if leaf <= sibling { (leaf, sibling) } else { (sibling, leaf) };
+        let root = hashv(&[&[1u8][..], &first, &second]).to_bytes();
+
+        assert!(verify_allowlist_proof(&[sibling], root, leaf));
+        // The cap is part of the leaf
+        assert!(!verify_allowlist_proof(&[sibling], root, allowlist_leaf(&wallet, Some(6))));
+        assert!(!verify_allowlist_proof(&[sibling], root, allowlist_leaf(&wallet, None)));
+        assert!(!verify_allowlist_proof(&[], root, leaf));
+    }
+}
@@ -0,0 +1,652 @@
+use crate::*;
+
+pub(crate) fn initialize_global_stats(ctx: Context<InitializeGlobalStats>) -> Result<()> {
+    let stats = &mut ctx.accounts.global_stats;
+    stats.total_escrows_created = 0;
+    stats.total_escrows_completed = 0;
+    stats.total_escrows_canceled = 0;
+    stats.total_value_locked = 0;
+    stats.total_value_released = 0;
+    stats.total_taker_side_fees = 0;
+    stats.total_initializer_side_fees = 0;
+    stats.bump = ctx.bumps.global_stats;
+
+    emit!(GlobalStatsInitialized {
+        admin: ctx.accounts.admin.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+pub(crate) fn initialize_config(
+    ctx: Context<InitializeConfig>,
+    treasury: Pubkey,
+    fee_bps: u16,
+    charge_taker_side: bool,
+    charge_initializer_side: bool,
+) -> Result<()> {
+    require!(fee_bps <= MAX_FEE_BPS, ErrorCode::FeeTooHigh);
+
+    let config = &mut ctx.accounts.config;
+    config.admin = ctx.accounts.admin.key();
+    config.treasury = treasury;
+    config.fee_bps = fee_bps;
+    config.charge_taker_side = charge_taker_side;
+    config.charge_initializer_side = charge_initializer_side;
+    config.paused = false;
+    config.pending_admin = None;
+    config.min_escrow_duration_seconds = DEFAULT_MIN_ESCROW_DURATION_SECONDS;
+    config.max_escrow_duration_seconds = DEFAULT_MAX_ESCROW_DURATION_SECONDS;
+    config.bump = ctx.bumps.config;
+
+    emit!(ConfigUpdated {
+        admin: config.admin,
+        treasury,
+        fee_bps,
+        charge_taker_side,
+        charge_initializer_side,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+pub(crate) fn update_config(
+    ctx: Context<UpdateConfig>,
+    treasury: Pubkey,
+    fee_bps: u16,
+    charge_taker_side: bool,
+    charge_initializer_side: bool,
+) -> Result<()> {
+    require!(fee_bps <= MAX_FEE_BPS, ErrorCode::FeeTooHigh);
+
+    let config = &mut ctx.accounts.config;
+    config.treasury = treasury;
+    config.fee_bps = fee_bps;
+    config.charge_taker_side = charge_taker_side;
+    config.charge_initializer_side = charge_initializer_side;
+
+    emit!(ConfigUpdated {
+        admin: config.admin,
+        treasury,
+        fee_bps,
+        charge_taker_side,
+        charge_initializer_side,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+pub(crate) fn set_paused(ctx: Context<UpdateConfig>, paused: bool) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.paused = paused;
+
+    emit!(PauseSet {
+        admin: config.admin,
+        paused,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+pub(crate) fn set_duration_bounds(
+    ctx: Context<UpdateConfig>,
+    min_escrow_duration_seconds: i64,
+    max_escrow_duration_seconds: i64,
+) -> Result<()> {
+    require!(
+        min_escrow_duration_seconds > 0
+            && min_escrow_duration_seconds <= max_escrow_duration_seconds,
+        ErrorCode::InvalidDurationBounds
+    );
+
+    let config = &mut ctx.accounts.config;
+    config.min_escrow_duration_seconds = min_escrow_duration_seconds;
+    config.max_escrow_duration_seconds = max_escrow_duration_seconds;
+
+    emit!(DurationBoundsUpdated {
+        admin: config.admin,
+        min_escrow_duration_seconds,
+        max_escrow_duration_seconds,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+pub(crate) fn propose_authority(ctx: Context<UpdateConfig>, proposed_admin: Pubkey) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.pending_admin = Some(proposed_admin);
+
+    emit!(AuthorityProposed {
+        admin: config.admin,
+        proposed_admin,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+pub(crate) fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let previous_admin = config.admin;
+    config.admin = ctx.accounts.pending_admin.key();
+    config.pending_admin = None;
+
+    emit!(AuthorityAccepted {
+        previous_admin,
+        new_admin: config.admin,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+pub(crate) fn migrate_accounts<'info>(ctx: Context<'_, '_, 'info, 'info, MigrateAccounts<'info>>) -> Result<()> {
+    let payer = ctx.accounts.admin.to_account_info();
+    let system_program = ctx.accounts.system_program.to_account_info();
+
+    let config_info = ctx.accounts.config.to_account_info();
+    if config_info.data_len() != CONFIG_ACCOUNT_SPACE {
+        let config = upgrade_legacy_config(&config_info.try_borrow_data()?)?;
+        rewrite_account(&config_info, &config, CONFIG_ACCOUNT_SPACE, &payer, &system_program)?;
+    }
+
+    let global_stats_info = ctx.accounts.global_stats.to_account_info();
+    if global_stats_info.data_len() != GLOBAL_STATS_ACCOUNT_SPACE {
+        let global_stats = upgrade_legacy_global_stats(&global_stats_info.try_borrow_data()?)?;
+        rewrite_account(
+            &global_stats_info,
+            &global_stats,
+            GLOBAL_STATS_ACCOUNT_SPACE,
+            &payer,
+            &system_program,
+        )?;
+    }
+
+    require!(ctx.remaining_accounts.len() % 3 == 0, ErrorCode::InvalidAccount);
+    for accounts in ctx.remaining_accounts.chunks(3) {
+        let [escrow_info, vault_account, taker_expected_token_mint] = accounts else {
+            unreachable!()
+        };
+        require_keys_eq!(*escrow_info.owner, crate::ID, ErrorCode::InvalidOwner);
+        if escrow_info.data_len() == ESCROW_ACCOUNT_SPACE {
+            continue;
+        }
+        let escrow_state = upgrade_legacy_escrow(
+            escrow_info.key,
+            &escrow_info.try_borrow_data()?,
+            vault_account,
+            taker_expected_token_mint,
+        )?;
+        rewrite_account(escrow_info, &escrow_state, ESCROW_ACCOUNT_SPACE, &payer, &system_program)?;
+    }
+    Ok(())
+}
+
+// `GlobalStats` as first deployed, before the fee totals
+#[derive(AnchorSerialize, AnchorDeserialize)]
+struct GlobalStatsV1 {
+    total_escrows_created: u64,
+    total_escrows_completed: u64,
+    total_escrows_canceled: u64,
+    total_value_locked: u64,
+    total_value_released: u64,
+    bump: u8,
+}
+
+// `Config` before `paused` and `pending_admin`
+#[derive(AnchorSerialize, AnchorDeserialize)]
+struct ConfigV1 {
+    admin: Pubkey,
+    treasury: Pubkey,
+    fee_bps: u16,
+    charge_taker_side: bool,
+    charge_initializer_side: bool,
+    bump: u8,
+}
+
+// `Config` before the duration bounds
+#[derive(AnchorSerialize, AnchorDeserialize)]
+struct ConfigV2 {
+    admin: Pubkey,
+    treasury: Pubkey,
+    fee_bps: u16,
+    charge_taker_side: bool,
+    charge_initializer_side: bool,
+    paused: bool,
+    pending_admin: Option<Pubkey>,
+    bump: u8,
+}
+
+// `EscrowState` as first deployed, which ended at `bump`
+#[derive(AnchorSerialize, AnchorDeserialize)]
+struct EscrowStateV1 {
+    initializer_key: Pubkey,
+    initializer_deposit_token_account: Pubkey,
+    initializer_deposit_token_mint: Pubkey,
+    taker_expected_token_mint: Pubkey,
+    initializer_amount: u64,
+    taker_expected_amount: u64,
+    initializer_receive_token_account: Pubkey,
+    unique_seed: [u8; 8],
+    expires_at: i64,
+    bump: u8,
+}
+
+// Legacy layouts are told apart by their allocated size. Their fields are decoded rather
+// than copied byte for byte, since an `Option` moves everything after it, the bump included.
+fn legacy_fields<'a>(data: &'a [u8], discriminator: &[u8]) -> Result<&'a [u8]> {
+    require!(data.starts_with(discriminator), ErrorCode::UnknownAccountLayout);
+    Ok(&data[discriminator.len()..])
+}
+
+fn upgrade_legacy_global_stats(data: &[u8]) -> Result<GlobalStats> {
+    let [v1_len] = LEGACY_GLOBAL_STATS_ACCOUNT_SPACES;
+    require!(data.len() == v1_len, ErrorCode::UnknownAccountLayout);
+    let mut fields = legacy_fields(data, GlobalStats::DISCRIMINATOR)?;
+    let legacy = GlobalStatsV1::deserialize(&mut fields)?;
+    Ok(GlobalStats {
+        total_escrows_created: legacy.total_escrows_created,
+        total_escrows_completed: legacy.total_escrows_completed,
+        total_escrows_canceled: legacy.total_escrows_canceled,
+        total_value_locked: legacy.total_value_locked,
+        total_value_released: legacy.total_value_released,
+        total_taker_side_fees: 0,
+        total_initializer_side_fees: 0,
+        bump: legacy.bump,
+    })
+}
+
+// Zeroed duration bounds would reject every escrow, so they start at the defaults
+fn upgrade_legacy_config(data: &[u8]) -> Result<Config> {
+    let [v1_len, v2_len] = LEGACY_CONFIG_ACCOUNT_SPACES;
+    let mut fields = legacy_fields(data, Config::DISCRIMINATOR)?;
+    let legacy = if data.len() == v1_len {
+        let v1 = ConfigV1::deserialize(&mut fields)?;
+        ConfigV2 {
+            admin: v1.admin,
+            treasury: v1.treasury,
+            fee_bps: v1.fee_bps,
+            charge_taker_side: v1.charge_taker_side,
+            charge_initializer_side: v1.charge_initializer_side,
+            paused: false,
+            pending_admin: None,
+            bump: v1.bump,
+        }
+    } else if data.len() == v2_len {
+        ConfigV2::deserialize(&mut fields)?
+    } else {
+        return err!(ErrorCode::UnknownAccountLayout);
+    };
+    Ok(Config {
+        admin: legacy.admin,
+        treasury: legacy.treasury,
+        fee_bps: legacy.fee_bps,
+        charge_taker_side: legacy.charge_taker_side,
+        charge_initializer_side: legacy.charge_initializer_side,
+        paused: legacy.paused,
+        pending_admin: legacy.pending_admin,
+        min_escrow_duration_seconds: DEFAULT_MIN_ESCROW_DURATION_SECONDS,
+        max_escrow_duration_seconds: DEFAULT_MAX_ESCROW_DURATION_SECONDS,
+        bump: legacy.bump,
+    })
+}
+
+// Legacy escrows were only ever filled in full, so the vault still holds the whole deposit.
+// The token programs the current layout records are read off the vault and the Token B mint.
+fn upgrade_legacy_escrow(
+    escrow_key: &Pubkey,
+    data: &[u8],
+    vault_account: &AccountInfo,
+    taker_expected_token_mint: &AccountInfo,
+) -> Result<EscrowState> {
+    let [v1_len] = LEGACY_ESCROW_ACCOUNT_SPACES;
+    require!(data.len() == v1_len, ErrorCode::UnknownAccountLayout);
+    let mut fields = legacy_fields(data, EscrowState::DISCRIMINATOR)?;
+    let legacy = EscrowStateV1::deserialize(&mut fields)?;
+
+    let (expected_vault, _) =
+        Pubkey::find_program_address(&[b"vault", escrow_key.as_ref()], &crate::ID);
+    require_keys_eq!(*vault_account.key, expected_vault, ErrorCode::InvalidAccount);
+    check_token_program_id(vault_account.owner)?;
+    let vault = read_token_account(vault_account, vault_account.owner)?;
+    require_keys_eq!(vault.mint, legacy.initializer_deposit_token_mint, ErrorCode::InvalidMint);
+
+    require_keys_eq!(
+        *taker_expected_token_mint.key,
+        legacy.taker_expected_token_mint,
+        ErrorCode::InvalidMint
+    );
+    check_token_program_id(taker_expected_token_mint.owner)?;
+
+    Ok(EscrowState {
+        initializer_key: legacy.initializer_key,
+        initializer_deposit_token_account: legacy.initializer_deposit_token_account,
+        initializer_deposit_token_mint: legacy.initializer_deposit_token_mint,
+        taker_expected_token_mint: legacy.taker_expected_token_mint,
+        // What the vault holds, as `initialize` now records it
+        initializer_amount: vault.amount,
+        taker_expected_amount: legacy.taker_expected_amount,
+        initializer_receive_token_account: legacy.initializer_receive_token_account,
+        unique_seed: legacy.unique_seed,
+        expires_at: legacy.expires_at,
+        bump: legacy.bump,
+        remaining_amount: vault.amount,
+        filled_amount: 0,
+        allowed_taker: None,
+        deposit_token_program: *vault_account.owner,
+        receive_token_program: *taker_expected_token_mint.owner,
+        taker_pays_transfer_fee: false,
+        deposit_asset: AssetKind::Token,
+        receive_asset: AssetKind::Token,
+        dutch_auction: None,
+        status: EscrowStatus::Open,
+        collateral: None,
+        collection_bid: false,
+        allowlist_root: None,
+    })
+}
+
+// Resizes `account` to `new_len`, topping up its rent from `payer`, and writes `value` over
+// it in the current layout.
+fn rewrite_account<'info, T: AccountSerialize>(
+    account: &AccountInfo<'info>,
+    value: &T,
+    new_len: usize,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    let rent_due = Rent::get()?
+        .minimum_balance(new_len)
+        .saturating_sub(account.lamports());
+    if rent_due > 0 {
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                anchor_lang::system_program::Transfer {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+            ),
+            rent_due,
+        )?;
+    }
+
+    account.resize(new_len)?;
+    let mut data = account.try_borrow_mut_data()?;
+    data.fill(0);
+    value.try_serialize(&mut &mut data[..])
+}
+
+#[derive(Accounts)]
+pub struct InitializeGlobalStats<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,  
+    /// Only the config admin may create the global stats account
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ErrorCode::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+    #[account(
+        init,
+        seeds = [b"global-stats"],
+        bump,
+        payer = admin,
+        space = GLOBAL_STATS_ACCOUNT_SPACE,
+    )]
+    pub global_stats: Account<'info, GlobalStats>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        init,
+        seeds = [CONFIG_SEED],
+        bump,
+        payer = admin,
+        space = CONFIG_ACCOUNT_SPACE,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, crate::program::Escrow>,
+    // Only the upgrade authority can claim the admin role
+    #[account(constraint = program_data.upgrade_authority_address == Some(admin.key()) @ErrorCode::Unauthorized)]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        has_one = admin @ErrorCode::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    pub pending_admin: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [CONFIG_SEED],
+        bump = config.bump,
+        constraint = config.pending_admin == Some(pending_admin.key()) @ErrorCode::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateAccounts<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    /// CHECK: may still be in a legacy layout; checked in `migrate_accounts`
+    #[account(mut, seeds = [CONFIG_SEED], bump, owner = crate::ID)]
+    pub config: UncheckedAccount<'info>,
+    /// CHECK: may still be in a legacy layout; checked in `migrate_accounts`
+    #[account(mut, seeds = [b"global-stats"], bump, owner = crate::ID)]
+    pub global_stats: UncheckedAccount<'info>,
+
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, crate::program::Escrow>,
+    #[account(constraint = program_data.upgrade_authority_address == Some(admin.key()) @ErrorCode::Unauthorized)]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Account data as a legacy layout left it: the fields behind the discriminator, padded
+    // out to the size that layout allocated
+    fn legacy_account(discriminator: &[u8], fields: impl AnchorSerialize, len: usize) -> Vec<u8> {
+        let mut data = discriminator.to_vec();
+        fields.serialize(&mut data).unwrap();
+        assert!(data.len() <= len);
+        data.resize(len, 0);
+        data
+    }
+
+    // Writes a migrated account into `len` bytes and reads it back the way Anchor loads it
+    fn reload<T: AccountSerialize + AccountDeserialize>(value: &T, len: usize) -> T {
+        let mut data = vec![0; len];
+        value.try_serialize(&mut &mut data[..]).unwrap();
+        T::try_deserialize(&mut &data[..]).unwrap()
+    }
+
+    fn config_v2(pending_admin: Option<Pubkey>) -> ConfigV2 {
+        ConfigV2 {
+            admin: Pubkey::new_from_array([1; 32]),
+            treasury: Pubkey::new_from_array([2; 32]),
+            fee_bps: 30,
+            charge_taker_side: true,
+            charge_initializer_side: false,
+            paused: true,
+            pending_admin,
+            bump: 253,
+        }
+    }
+
+    #[test]
+    fn baseline_global_stats_migrates() {
+        let legacy = GlobalStatsV1 {
+            total_escrows_created: 1,
+            total_escrows_completed: 2,
+            total_escrows_canceled: 3,
+            total_value_locked: 4,
+            total_value_released: 5,
+            bump: 254,
+        };
+        let data = legacy_account(GlobalStats::DISCRIMINATOR, legacy, 49);
+
+        let stats = reload(&upgrade_legacy_global_stats(&data).unwrap(), GLOBAL_STATS_ACCOUNT_SPACE);
+        assert_eq!(stats.total_escrows_created, 1);
+        assert_eq!(stats.total_escrows_completed, 2);
+        assert_eq!(stats.total_escrows_canceled, 3);
+        assert_eq!(stats.total_value_locked, 4);
+        assert_eq!(stats.total_value_released, 5);
+        assert_eq!(stats.total_taker_side_fees, 0);
+        assert_eq!(stats.total_initializer_side_fees, 0);
+        assert_eq!(stats.bump, 254);
+    }
+
+    #[test]
+    fn config_migrates_with_or_without_pending_admin() {
+        for pending_admin in [None, Some(Pubkey::new_from_array([3; 32]))] {
+            let data = legacy_account(Config::DISCRIMINATOR, config_v2(pending_admin), 111);
+
+            let config = reload(&upgrade_legacy_config(&data).unwrap(), CONFIG_ACCOUNT_SPACE);
+            assert_eq!(config.admin, Pubkey::new_from_array([1; 32]));
+            assert_eq!(config.treasury, Pubkey::new_from_array([2; 32]));
+            assert_eq!(config.fee_bps, 30);
+            assert!(config.charge_taker_side);
+            assert!(!config.charge_initializer_side);
+            assert!(config.paused);
+            assert_eq!(config.pending_admin, pending_admin);
+            assert_eq!(config.min_escrow_duration_seconds, DEFAULT_MIN_ESCROW_DURATION_SECONDS);
+            assert_eq!(config.max_escrow_duration_seconds, DEFAULT_MAX_ESCROW_DURATION_SECONDS);
+            assert_eq!(config.bump, 253);
+        }
+    }
+
+    #[test]
+    fn first_config_layout_migrates() {
+        let legacy = ConfigV1 {
+            admin: Pubkey::new_from_array([1; 32]),
+            treasury: Pubkey::new_from_array([2; 32]),
+            fee_bps: 30,
+            charge_taker_side: false,
+            charge_initializer_side: true,
+            bump: 252,
+        };
+        let data = legacy_account(Config::DISCRIMINATOR, legacy, 77);
+
+        let config = reload(&upgrade_legacy_config(&data).unwrap(), CONFIG_ACCOUNT_SPACE);
+        assert!(config.charge_initializer_side);
+        assert!(!config.paused);
+        assert_eq!(config.pending_admin, None);
+        assert_eq!(config.bump, 252);
+    }
+
+    fn legacy_escrow(escrow_key: &Pubkey) -> (Vec<u8>, Pubkey) {
+        let legacy = EscrowStateV1 {
+            initializer_key: Pubkey::new_from_array([1; 32]),
+            initializer_deposit_token_account: Pubkey::new_from_array([2; 32]),
+            initializer_deposit_token_mint: Pubkey::new_from_array([3; 32]),
+            taker_expected_token_mint: Pubkey::new_from_array([4; 32]),
+            initializer_amount: 1_000,
+            taker_expected_amount: 500,
+            initializer_receive_token_account: Pubkey::new_from_array([5; 32]),
+            unique_seed: [7; 8],
+            expires_at: 1_700_000_000,
+            bump: 251,
+        };
+        let vault_key = Pubkey::find_program_address(&[b"vault", escrow_key.as_ref()], &crate::ID).0;
+        (legacy_account(EscrowState::DISCRIMINATOR, legacy, 201), vault_key)
+    }
+
+    fn token_account_data(mint: Pubkey, owner: Pubkey, amount: u64) -> Vec<u8> {
+        use anchor_lang::solana_program::program_option::COption;
+        use anchor_lang::solana_program::program_pack::Pack;
+        let account = spl_token::state::Account {
+            mint,
+            owner,
+            amount,
+            delegate: COption::None,
+            state: spl_token::state::AccountState::Initialized,
+            is_native: COption::None,
+            delegated_amount: 0,
+            close_authority: COption::None,
+        };
+        let mut data = vec![0; spl_token::state::Account::LEN];
+        account.pack_into_slice(&mut data);
+        data
+    }
+
+    #[test]
+    fn baseline_escrow_migrates() {
+        let escrow_key = Pubkey::new_from_array([9; 32]);
+        let (data, vault_key) = legacy_escrow(&escrow_key);
+        let mint_key = Pubkey::new_from_array([4; 32]);
+        // The vault lost a transfer fee on the way in
+        let mut vault_data = token_account_data(Pubkey::new_from_array([3; 32]), escrow_key, 990);
+        let (mut vault_lamports, mut mint_lamports) = (0, 0);
+        let vault = AccountInfo::new(
+            &vault_key, false, false, &mut vault_lamports, &mut vault_data, &TOKEN_PROGRAM_ID, false, 0,
+        );
+        let mint = AccountInfo::new(
+            &mint_key, false, false, &mut mint_lamports, &mut [], &TOKEN_2022_PROGRAM_ID, false, 0,
+        );
+
+        let escrow_state = upgrade_legacy_escrow(&escrow_key, &data, &vault, &mint).unwrap();
+        let escrow_state = reload(&escrow_state, ESCROW_ACCOUNT_SPACE);
+        assert_eq!(escrow_state.initializer_key, Pubkey::new_from_array([1; 32]));
+        assert_eq!(escrow_state.initializer_receive_token_account, Pubkey::new_from_array([5; 32]));
+        assert_eq!(escrow_state.taker_expected_amount, 500);
+        assert_eq!(escrow_state.unique_seed, [7; 8]);
+        assert_eq!(escrow_state.expires_at, 1_700_000_000);
+        assert_eq!(escrow_state.bump, 251);
+        assert_eq!(escrow_state.initializer_amount, 990);
+        assert_eq!(escrow_state.remaining_amount, 990);
+        assert_eq!(escrow_state.filled_amount, 0);
+        assert_eq!(escrow_state.deposit_token_program, TOKEN_PROGRAM_ID);
+        assert_eq!(escrow_state.receive_token_program, TOKEN_2022_PROGRAM_ID);
+        assert_eq!(escrow_state.status, EscrowStatus::Open);
+    }
+
+    #[test]
+    fn baseline_escrow_needs_its_own_vault() {
+        let escrow_key = Pubkey::new_from_array([9; 32]);
+        let (data, _) = legacy_escrow(&escrow_key);
+        let other_vault_key = Pubkey::new_from_array([8; 32]);
+        let mint_key = Pubkey::new_from_array([4; 32]);
+        let mut vault_data = token_account_data(Pubkey::new_from_array([3; 32]), escrow_key, 1_000);
+        let (mut vault_lamports, mut mint_lamports) = (0, 0);
+        let vault = AccountInfo::new(
+            &other_vault_key, false, false, &mut vault_lamports, &mut vault_data, &TOKEN_PROGRAM_ID, false, 0,
+        );
+        let mint = AccountInfo::new(
+            &mint_key, false, false, &mut mint_lamports, &mut [], &TOKEN_PROGRAM_ID, false, 0,
+        );
+        assert!(upgrade_legacy_escrow(&escrow_key, &data, &vault, &mint).is_err());
+    }
+
+    #[test]
+    fn unknown_layouts_are_rejected() {
+        let data = legacy_account(Config::DISCRIMINATOR, config_v2(None), 120);
+        assert!(upgrade_legacy_config(&data).is_err());
+        // Right size, wrong account type
+        let data = legacy_account(GlobalStats::DISCRIMINATOR, config_v2(None), 111);
+        assert!(upgrade_legacy_config(&data).is_err());
+    }
+}
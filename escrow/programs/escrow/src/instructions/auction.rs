@@ -0,0 +1,611 @@
+use crate::*;
+
+pub(crate) fn create_auction<'info>(
+    ctx: Context<'_, '_, '_, 'info, CreateAuction<'info>>,
+    amount: u64,
+    unique_seed: [u8; 8],
+    reserve_price: u64,
+    min_increment: u64,
+    duration_in_seconds: i64,
+) -> Result<()> {
+    require!(!ctx.accounts.config.paused, ErrorCode::ProgramPaused);
+    check_token_program_id(ctx.accounts.deposit_token_program.key)?;
+    check_token_program_id(ctx.accounts.bid_token_program.key)?;
+    require!(
+        amount > 0 && reserve_price > 0 && min_increment > 0,
+        ErrorCode::InvalidAmount
+    );
+    check_duration(&ctx.accounts.config, duration_in_seconds)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let expires_at = now
+        .checked_add(duration_in_seconds)
+        .ok_or(ErrorCode::Overflow)?;
+
+    // --- CPI: Seller funds the vault ---
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.seller_deposit_token_account.to_account_info(),
+        mint: ctx.accounts.deposit_mint.to_account_info(),
+        to: ctx.accounts.vault_account.to_account_info(),
+        authority: ctx.accounts.seller.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.deposit_token_program.to_account_info();
+    let cpi_context = CpiContext::new(cpi_program, cpi_accounts)
+        .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+    let deposited_amount = deposit_to_vault(cpi_context, amount, ctx.accounts.deposit_mint.decimals)?;
+    require!(deposited_amount > 0, ErrorCode::InvalidAmount);
+
+    let auction = &mut ctx.accounts.auction;
+    auction.seller = ctx.accounts.seller.key();
+    auction.deposit_mint = ctx.accounts.deposit_mint.key();
+    auction.bid_mint = ctx.accounts.bid_mint.key();
+    auction.deposit_token_program = ctx.accounts.deposit_token_program.key();
+    auction.bid_token_program = ctx.accounts.bid_token_program.key();
+    auction.amount = deposited_amount;
+    auction.reserve_price = reserve_price;
+    auction.min_increment = min_increment;
+    auction.highest_bid = 0;
+    auction.highest_bidder = None;
+    auction.expires_at = expires_at;
+    auction.open_bids = 0;
+    auction.settled = false;
+    auction.unique_seed = unique_seed;
+    auction.bump = ctx.bumps.auction;
+
+    let mint_stats = &mut ctx.accounts.deposit_mint_stats;
+    mint_stats.init_if_needed(ctx.accounts.deposit_mint.key(), ctx.bumps.deposit_mint_stats);
+    mint_stats.record_lock(deposited_amount)?;
+
+    emit!(AuctionCreated {
+        seller: ctx.accounts.seller.key(),
+        deposit_mint: ctx.accounts.deposit_mint.key(),
+        bid_mint: ctx.accounts.bid_mint.key(),
+        amount: deposited_amount,
+        reserve_price,
+        min_increment,
+        expires_at,
+        unique_seed,
+        timestamp: now,
+    });
+    Ok(())
+}
+
+pub(crate) fn place_bid<'info>(
+    ctx: Context<'_, '_, '_, 'info, PlaceBid<'info>>,
+    amount: u64,
+) -> Result<()> {
+    require!(!ctx.accounts.config.paused, ErrorCode::ProgramPaused);
+    let now = Clock::get()?.unix_timestamp;
+    let auction = &ctx.accounts.auction;
+    require!(now <= auction.expires_at, ErrorCode::EscrowExpired);
+    require_keys_neq!(
+        ctx.accounts.bidder.key(),
+        auction.seller,
+        ErrorCode::InvalidAccount
+    );
+    let min_bid = match auction.highest_bidder {
+        Some(_) => auction
+            .highest_bid
+            .checked_add(auction.min_increment)
+            .ok_or(ErrorCode::Overflow)?,
+        None => auction.reserve_price,
+    };
+    let previous_bidder = auction.highest_bidder;
+    let previous_bid = auction.highest_bid;
+
+    let bid = &mut ctx.accounts.bid;
+    if bid.bidder == Pubkey::default() {
+        bid.auction = ctx.accounts.auction.key();
+        bid.bidder = ctx.accounts.bidder.key();
+        bid.bump = ctx.bumps.bid;
+        ctx.accounts.auction.open_bids = ctx
+            .accounts
+            .auction
+            .open_bids
+            .checked_add(1)
+            .ok_or(ErrorCode::Overflow)?;
+    }
+    let top_up = amount
+        .checked_sub(ctx.accounts.bid.amount)
+        .ok_or(ErrorCode::BidTooLow)?;
+    require!(top_up > 0, ErrorCode::BidTooLow);
+
+    // --- CPI: Bidder escrows the difference in their bid vault ---
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.bidder_token_account.to_account_info(),
+        mint: ctx.accounts.bid_mint.to_account_info(),
+        to: ctx.accounts.bid_vault.to_account_info(),
+        authority: ctx.accounts.bidder.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.bid_token_program.to_account_info();
+    let cpi_context = CpiContext::new(cpi_program, cpi_accounts)
+        .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+    // The bid is the vault's whole balance, so transfer fees count against the bidder
+    let bid_amount = deposit_to_vault(cpi_context, top_up, ctx.accounts.bid_mint.decimals)?;
+    require!(bid_amount >= min_bid, ErrorCode::BidTooLow);
+
+    ctx.accounts.bid.amount = bid_amount;
+    let auction = &mut ctx.accounts.auction;
+    auction.highest_bid = bid_amount;
+    auction.highest_bidder = Some(ctx.accounts.bidder.key());
+
+    emit!(BidPlaced {
+        auction: auction.key(),
+        bidder: ctx.accounts.bidder.key(),
+        amount: bid_amount,
+        previous_bidder,
+        previous_bid,
+        timestamp: now,
+    });
+    Ok(())
+}
+
+pub(crate) fn withdraw_bid<'info>(ctx: Context<'_, '_, '_, 'info, WithdrawBid<'info>>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let bid = &ctx.accounts.bid;
+    require!(
+        ctx.accounts.auction.highest_bidder != Some(bid.bidder),
+        ErrorCode::LeadingBid
+    );
+    let amount = ctx.accounts.bid_vault.amount;
+
+    let auction_key = ctx.accounts.auction.key();
+    let authority_seeds = &[
+        BID_SEED,
+        auction_key.as_ref(),
+        bid.bidder.as_ref(),
+        &[bid.bump],
+    ];
+    let signer_seeds = &[&authority_seeds[..]];
+
+    // --- CPI 1: Bid vault refunds the bidder ---
+    if amount > 0 {
+        let cpi_accounts_transfer = TransferChecked {
+            from: ctx.accounts.bid_vault.to_account_info(),
+            mint: ctx.accounts.bid_mint.to_account_info(),
+            to: ctx.accounts.bidder_token_account.to_account_info(),
+            authority: ctx.accounts.bid.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.bid_token_program.to_account_info();
+        let cpi_context_transfer =
+            CpiContext::new_with_signer(cpi_program, cpi_accounts_transfer, signer_seeds)
+                .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+        transfer_checked_with_hook(cpi_context_transfer, amount, ctx.accounts.bid_mint.decimals)?;
+    }
+
+    // --- CPI 2: Close the bid vault, rent goes back to the bidder ---
+    let cpi_accounts_close = CloseAccount {
+        account: ctx.accounts.bid_vault.to_account_info(),
+        destination: ctx.accounts.bidder.to_account_info(),
+        authority: ctx.accounts.bid.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.bid_token_program.to_account_info();
+    let cpi_context_close =
+        CpiContext::new_with_signer(cpi_program, cpi_accounts_close, signer_seeds);
+
+    token_interface::close_account(cpi_context_close)?;
+
+    let auction = &mut ctx.accounts.auction;
+    auction.open_bids = auction.open_bids.saturating_sub(1);
+    if auction.settled && auction.open_bids == 0 {
+        ctx.accounts
+            .auction
+            .close(ctx.accounts.seller.to_account_info())?;
+    }
+
+    emit!(BidWithdrawn {
+        auction: auction_key,
+        bidder: ctx.accounts.bid.bidder,
+        withdrawn_by: ctx.accounts.caller.key(),
+        amount,
+        timestamp: now,
+    });
+    Ok(())
+}
+
+pub(crate) fn settle_auction<'info>(ctx: Context<'_, '_, '_, 'info, SettleAuction<'info>>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let auction = &ctx.accounts.auction;
+    require!(!auction.settled, ErrorCode::AuctionAlreadySettled);
+    require!(now > auction.expires_at, ErrorCode::AuctionActive);
+
+    let amount = auction.amount;
+    let winning_bid = auction.highest_bid;
+    let winner = auction.highest_bidder;
+    let auction_key = auction.key();
+    let authority_seeds = &[
+        AUCTION_SEED,
+        auction.seller.as_ref(),
+        auction.unique_seed.as_ref(),
+        &[auction.bump],
+    ];
+    let signer_seeds = &[&authority_seeds[..]];
+
+    let deposit_destination = match winner {
+        Some(winner) => {
+            let accounts = &ctx.accounts;
+            let (Some(winner_wallet), Some(winner_bid), Some(winner_bid_vault), Some(winner_receive_token_account)) = (
+                accounts.winner.as_ref(),
+                accounts.winner_bid.as_ref(),
+                accounts.winner_bid_vault.as_ref(),
+                accounts.winner_receive_token_account.as_ref(),
+            ) else {
+                return err!(ErrorCode::MissingWinnerAccounts);
+            };
+            require_keys_eq!(winner_wallet.key(), winner, ErrorCode::InvalidAccount);
+            require_keys_eq!(winner_bid.auction, auction_key, ErrorCode::InvalidAccount);
+            require_keys_eq!(winner_bid.bidder, winner, ErrorCode::InvalidAccount);
+            require_keys_eq!(winner_bid_vault.owner, winner_bid.key(), ErrorCode::InvalidOwner);
+            require_keys_eq!(winner_receive_token_account.owner, winner, ErrorCode::InvalidOwner);
+
+            let bid_authority_seeds = &[
+                BID_SEED,
+                auction_key.as_ref(),
+                winner_bid.bidder.as_ref(),
+                &[winner_bid.bump],
+            ];
+            let bid_signer_seeds = &[&bid_authority_seeds[..]];
+
+            // --- CPI 1: Winning bid goes to the seller ---
+            let cpi_accounts_transfer = TransferChecked {
+                from: winner_bid_vault.to_account_info(),
+                mint: accounts.bid_mint.to_account_info(),
+                to: accounts.seller_receive_token_account.to_account_info(),
+                authority: winner_bid.to_account_info(),
+            };
+            let cpi_program = accounts.bid_token_program.to_account_info();
+            let cpi_context_transfer =
+                CpiContext::new_with_signer(cpi_program, cpi_accounts_transfer, bid_signer_seeds)
+                    .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+            transfer_checked_with_hook(cpi_context_transfer, winner_bid_vault.amount, accounts.bid_mint.decimals)?;
+
+            // --- CPI 2: Close the winning bid vault, rent goes back to the winner ---
+            let cpi_accounts_close = CloseAccount {
+                account: winner_bid_vault.to_account_info(),
+                destination: winner_wallet.to_account_info(),
+                authority: winner_bid.to_account_info(),
+            };
+            let cpi_program = accounts.bid_token_program.to_account_info();
+            let cpi_context_close =
+                CpiContext::new_with_signer(cpi_program, cpi_accounts_close, bid_signer_seeds);
+
+            token_interface::close_account(cpi_context_close)?;
+            winner_bid.close(winner_wallet.to_account_info())?;
+
+            winner_receive_token_account.to_account_info()
+        }
+        None => ctx.accounts.seller_deposit_token_account.to_account_info(),
+    };
+
+    // --- CPI 3: Vault pays the winner, or refunds the seller ---
+    let cpi_accounts_transfer = TransferChecked {
+        from: ctx.accounts.vault_account.to_account_info(),
+        mint: ctx.accounts.deposit_mint.to_account_info(),
+        to: deposit_destination,
+        authority: ctx.accounts.auction.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.deposit_token_program.to_account_info();
+    let cpi_context_transfer =
+        CpiContext::new_with_signer(cpi_program, cpi_accounts_transfer, signer_seeds)
+            .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+    transfer_checked_with_hook(cpi_context_transfer, amount, ctx.accounts.deposit_mint.decimals)?;
+
+    // --- CPI 4: Close the vault, rent goes back to the seller ---
+    let cpi_accounts_close = CloseAccount {
+        account: ctx.accounts.vault_account.to_account_info(),
+        destination: ctx.accounts.seller.to_account_info(),
+        authority: ctx.accounts.auction.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.deposit_token_program.to_account_info();
+    let cpi_context_close =
+        CpiContext::new_with_signer(cpi_program, cpi_accounts_close, signer_seeds);
+
+    token_interface::close_account(cpi_context_close)?;
+    {
+        let mint_stats = &mut ctx.accounts.deposit_mint_stats;
+        if winner.is_some() {
+            mint_stats.record_release(amount)?;
+            // Auctions charge no protocol fee
+            mint_stats.record_fills(1, amount, 0)?;
+        } else {
+            mint_stats.record_refund(amount)?;
+        }
+    }
+
+    // Losing bids keep the auction open until they are withdrawn
+    let auction = &mut ctx.accounts.auction;
+    auction.settled = true;
+    if winner.is_some() {
+        auction.open_bids = auction.open_bids.saturating_sub(1);
+    }
+    if auction.open_bids == 0 {
+        ctx.accounts
+            .auction
+            .close(ctx.accounts.seller.to_account_info())?;
+    }
+
+    emit!(AuctionSettled {
+        auction: auction_key,
+        seller: ctx.accounts.auction.seller,
+        winner,
+        winning_bid,
+        amount,
+        settled_by: ctx.accounts.caller.key(),
+        timestamp: now,
+    });
+    Ok(())
+}
+
+/// Accounts for the `create_auction` instruction
+#[derive(Accounts)]
+#[instruction(amount: u64, unique_seed: [u8; 8])]
+pub struct CreateAuction<'info> {
+    /// The seller putting Token A up for auction. Must sign the transaction.
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    /// The seller's Token A account funding the vault
+    #[account(
+        mut,
+        token::mint = deposit_mint,
+        token::authority = seller,
+        token::token_program = deposit_token_program
+    )]
+    pub seller_deposit_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mint::token_program = deposit_token_program)]
+    pub deposit_mint: InterfaceAccount<'info, Mint>,
+
+    /// The mint bids are paid in
+    #[account(mint::token_program = bid_token_program)]
+    pub bid_mint: InterfaceAccount<'info, Mint>,
+
+    /// The auction PDA
+    #[account(
+        init,
+        seeds = [AUCTION_SEED, seller.key().as_ref(), unique_seed.as_ref()],
+        bump,
+        payer = seller,
+        space = AUCTION_ACCOUNT_SPACE,
+    )]
+    pub auction: Account<'info, Auction>,
+
+    /// PDA-owned vault, same seeds as the swap escrow vault
+    #[account(
+        init,
+        token::mint = deposit_mint,
+        token::authority = auction,
+        token::token_program = deposit_token_program,
+        payer = seller,
+        seeds = [b"vault", auction.key().as_ref()],
+        bump
+    )]
+    pub vault_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Per-mint stats for Token A, created on first use
+    #[account(
+        init_if_needed,
+        seeds = [MINT_STATS_SEED, deposit_mint.key().as_ref()],
+        bump,
+        payer = seller,
+        space = MINT_STATS_ACCOUNT_SPACE,
+    )]
+    pub deposit_mint_stats: Account<'info, MintStats>,
+
+    /// Protocol config, checked for the pause flag
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    pub deposit_token_program: Interface<'info, TokenInterface>,
+    pub bid_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Accounts for the `place_bid` instruction
+#[derive(Accounts)]
+pub struct PlaceBid<'info> {
+    /// The bidder. Must sign the transaction.
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+
+    /// The bidder's account funding the bid
+    #[account(
+        mut,
+        token::mint = bid_mint,
+        token::authority = bidder,
+        token::token_program = bid_token_program
+    )]
+    pub bidder_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [AUCTION_SEED, auction.seller.as_ref(), auction.unique_seed.as_ref()],
+        bump = auction.bump,
+        has_one = bid_mint @ErrorCode::InvalidMint,
+        constraint = !auction.settled @ErrorCode::AuctionAlreadySettled,
+    )]
+    pub auction: Account<'info, Auction>,
+
+    /// The bidder's bid on this auction, created by their first bid
+    #[account(
+        init_if_needed,
+        seeds = [BID_SEED, auction.key().as_ref(), bidder.key().as_ref()],
+        bump,
+        payer = bidder,
+        space = BID_ACCOUNT_SPACE,
+    )]
+    pub bid: Account<'info, Bid>,
+
+    /// The bid's own vault, same derivation as the swap escrow vault
+    #[account(
+        init_if_needed,
+        token::mint = bid_mint,
+        token::authority = bid,
+        token::token_program = bid_token_program,
+        payer = bidder,
+        seeds = [b"vault", bid.key().as_ref()],
+        bump
+    )]
+    pub bid_vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub bid_mint: InterfaceAccount<'info, Mint>,
+
+    /// Protocol config, checked for the pause flag
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(address = auction.bid_token_program @ErrorCode::InvalidTokenProgram)]
+    pub bid_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Accounts for the `withdraw_bid` instruction
+#[derive(Accounts)]
+pub struct WithdrawBid<'info> {
+    /// The bidder, or anyone cranking the refund
+    pub caller: Signer<'info>,
+
+    /// CHECK: Matched against `bid.bidder`; receives the refund's rent
+    #[account(mut)]
+    pub bidder: AccountInfo<'info>,
+
+    /// CHECK: Matched against `auction.seller`; receives the auction rent on the last withdrawal
+    #[account(mut)]
+    pub seller: AccountInfo<'info>,
+
+    /// The bidder's account receiving the refund
+    #[account(
+        mut,
+        token::mint = bid_mint,
+        token::authority = bidder,
+        token::token_program = bid_token_program
+    )]
+    pub bidder_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [AUCTION_SEED, auction.seller.as_ref(), auction.unique_seed.as_ref()],
+        bump = auction.bump,
+        has_one = seller @ErrorCode::InvalidOwner,
+        has_one = bid_mint @ErrorCode::InvalidMint,
+    )]
+    pub auction: Account<'info, Auction>,
+
+    #[account(
+        mut,
+        seeds = [BID_SEED, auction.key().as_ref(), bidder.key().as_ref()],
+        bump = bid.bump,
+        has_one = auction @ErrorCode::InvalidAccount,
+        has_one = bidder @ErrorCode::InvalidOwner,
+        close = bidder,
+    )]
+    pub bid: Account<'info, Bid>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", bid.key().as_ref()],
+        bump,
+    )]
+    pub bid_vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub bid_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(address = auction.bid_token_program @ErrorCode::InvalidTokenProgram)]
+    pub bid_token_program: Interface<'info, TokenInterface>,
+}
+
+/// Accounts for the `settle_auction` instruction
+#[derive(Accounts)]
+pub struct SettleAuction<'info> {
+    /// Anyone, once the auction has ended
+    pub caller: Signer<'info>,
+
+    /// CHECK: Matched against `auction.seller`; receives the vault and auction rent
+    #[account(mut)]
+    pub seller: AccountInfo<'info>,
+
+    /// The seller's account receiving the winning bid
+    #[account(
+        mut,
+        token::mint = bid_mint,
+        token::authority = seller,
+        token::token_program = bid_token_program
+    )]
+    pub seller_receive_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// The seller's Token A account, refunded when nobody bid
+    #[account(
+        mut,
+        token::mint = deposit_mint,
+        token::authority = seller,
+        token::token_program = deposit_token_program
+    )]
+    pub seller_deposit_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [AUCTION_SEED, auction.seller.as_ref(), auction.unique_seed.as_ref()],
+        bump = auction.bump,
+        has_one = seller @ErrorCode::InvalidOwner,
+        has_one = deposit_mint @ErrorCode::InvalidMint,
+        has_one = bid_mint @ErrorCode::InvalidMint,
+    )]
+    pub auction: Account<'info, Auction>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", auction.key().as_ref()],
+        bump,
+    )]
+    pub vault_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Matched against `auction.highest_bidder`; receives the bid rent. Required with a winner.
+    #[account(mut)]
+    pub winner: Option<AccountInfo<'info>>,
+
+    /// The winning bid, required with a winner
+    #[account(mut)]
+    pub winner_bid: Option<Account<'info, Bid>>,
+
+    /// The winning bid's vault, required with a winner
+    #[account(
+        mut,
+        token::mint = bid_mint,
+        token::token_program = bid_token_program
+    )]
+    pub winner_bid_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The winner's Token A account, required with a winner
+    #[account(
+        mut,
+        token::mint = deposit_mint,
+        token::token_program = deposit_token_program
+    )]
+    pub winner_receive_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub deposit_mint: InterfaceAccount<'info, Mint>,
+    pub bid_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [MINT_STATS_SEED, deposit_mint.key().as_ref()],
+        bump = deposit_mint_stats.bump
+    )]
+    pub deposit_mint_stats: Account<'info, MintStats>,
+
+    #[account(address = auction.deposit_token_program @ErrorCode::InvalidTokenProgram)]
+    pub deposit_token_program: Interface<'info, TokenInterface>,
+    #[account(address = auction.bid_token_program @ErrorCode::InvalidTokenProgram)]
+    pub bid_token_program: Interface<'info, TokenInterface>,
+}
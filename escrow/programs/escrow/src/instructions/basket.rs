@@ -0,0 +1,572 @@
+use crate::*;
+
+pub(crate) fn create_basket_escrow<'info>(
+    ctx: Context<'_, '_, '_, 'info, CreateBasketEscrow<'info>>,
+    unique_seed: [u8; 8],
+    offered_amounts: Vec<u64>,
+    requested_amounts: Vec<u64>,
+    duration_in_seconds: i64,
+    allowed_taker: Option<Pubkey>,
+) -> Result<()> {
+    require!(!ctx.accounts.config.paused, ErrorCode::ProgramPaused);
+    check_duration(&ctx.accounts.config, duration_in_seconds)?;
+    require!(
+        (1..=MAX_BASKET_LEGS).contains(&offered_amounts.len())
+            && (1..=MAX_BASKET_LEGS).contains(&requested_amounts.len()),
+        ErrorCode::InvalidBasketLegs
+    );
+    let offered_end = offered_amounts.len() * 4;
+    let requested_end = offered_end + requested_amounts.len() * 3;
+    require!(
+        ctx.remaining_accounts.len() >= requested_end,
+        ErrorCode::InvalidBasketLegs
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let initializer_key = ctx.accounts.initializer.key();
+    let basket_key = ctx.accounts.basket_escrow.key();
+
+    let mut offered: Vec<BasketLeg> = Vec::with_capacity(offered_amounts.len());
+    let offered_groups = ctx.remaining_accounts[..offered_end].chunks(4);
+    for (group, amount) in offered_groups.zip(offered_amounts) {
+        let [initializer_token_account, mint, vault, token_program] = group else {
+            return err!(ErrorCode::InvalidBasketLegs);
+        };
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        check_token_program_id(token_program.key)?;
+        // One vault per mint, so each offered mint may only appear once
+        require!(
+            offered.iter().all(|leg| leg.mint != *mint.key),
+            ErrorCode::InvalidBasketLegs
+        );
+        let decimals = read_mint(mint, token_program.key)?.decimals;
+        let source = read_token_account(initializer_token_account, token_program.key)?;
+        require_keys_eq!(source.mint, *mint.key, ErrorCode::InvalidMint);
+        require_keys_eq!(source.owner, initializer_key, ErrorCode::InvalidOwner);
+
+        create_basket_vault(
+            ctx.accounts.initializer.to_account_info(),
+            vault.clone(),
+            mint.clone(),
+            ctx.accounts.basket_escrow.to_account_info(),
+            token_program.clone(),
+            ctx.accounts.system_program.to_account_info(),
+        )?;
+
+        // --- CPI: Initializer funds the leg's vault ---
+        let cpi_accounts = TransferChecked {
+            from: initializer_token_account.clone(),
+            mint: mint.clone(),
+            to: vault.clone(),
+            authority: ctx.accounts.initializer.to_account_info(),
+        };
+        let cpi_context = CpiContext::new(token_program.clone(), cpi_accounts)
+            .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+        let locked_amount = deposit_to_vault(cpi_context, amount, decimals)?;
+        require!(locked_amount > 0, ErrorCode::InvalidAmount);
+        offered.push(BasketLeg {
+            mint: *mint.key,
+            token_program: *token_program.key,
+            amount: locked_amount,
+            token_account: *initializer_token_account.key,
+        });
+    }
+
+    let mut requested = Vec::with_capacity(requested_amounts.len());
+    let requested_groups = ctx.remaining_accounts[offered_end..requested_end].chunks(3);
+    for (group, amount) in requested_groups.zip(requested_amounts) {
+        let [mint, initializer_receive_token_account, token_program] = group else {
+            return err!(ErrorCode::InvalidBasketLegs);
+        };
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        check_token_program_id(token_program.key)?;
+        read_mint(mint, token_program.key)?;
+        let destination = read_token_account(initializer_receive_token_account, token_program.key)?;
+        require_keys_eq!(destination.mint, *mint.key, ErrorCode::InvalidMint);
+        require_keys_eq!(destination.owner, initializer_key, ErrorCode::InvalidOwner);
+        requested.push(BasketLeg {
+            mint: *mint.key,
+            token_program: *token_program.key,
+            amount,
+            token_account: *initializer_receive_token_account.key,
+        });
+    }
+
+    let expires_at = now
+        .checked_add(duration_in_seconds)
+        .ok_or(ErrorCode::Overflow)?;
+    let basket_escrow = &mut ctx.accounts.basket_escrow;
+    basket_escrow.initializer = initializer_key;
+    basket_escrow.unique_seed = unique_seed;
+    basket_escrow.expires_at = expires_at;
+    basket_escrow.allowed_taker = allowed_taker;
+    basket_escrow.offered = offered.clone();
+    basket_escrow.requested = requested.clone();
+    basket_escrow.bump = ctx.bumps.basket_escrow;
+
+    let global_stats = &mut ctx.accounts.global_stats;
+    global_stats.total_escrows_created = global_stats
+        .total_escrows_created
+        .checked_add(1)
+        .ok_or(ErrorCode::Overflow)?;
+
+    emit!(BasketEscrowCreated {
+        initializer: initializer_key,
+        basket: basket_key,
+        offered,
+        requested,
+        allowed_taker,
+        expires_at,
+        unique_seed,
+        timestamp: now,
+    });
+    Ok(())
+}
+
+pub(crate) fn exchange_basket<'info>(
+    ctx: Context<'_, '_, '_, 'info, ExchangeBasket<'info>>,
+    expected_initializer_amounts: Vec<u64>,
+    max_taker_amounts: Vec<u64>,
+) -> Result<()> {
+    require!(!ctx.accounts.config.paused, ErrorCode::ProgramPaused);
+    let now = Clock::get()?.unix_timestamp;
+    let basket_escrow = &ctx.accounts.basket_escrow;
+    require!(now <= basket_escrow.expires_at, ErrorCode::EscrowExpired);
+    require_keys_neq!(
+        ctx.accounts.taker.key(),
+        basket_escrow.initializer,
+        ErrorCode::InvalidAccount
+    );
+    let requested_end = basket_escrow.requested.len() * 5;
+    let offered_end = requested_end + basket_escrow.offered.len() * 5;
+    require!(
+        ctx.remaining_accounts.len() >= offered_end
+            && max_taker_amounts.len() == basket_escrow.requested.len()
+            && expected_initializer_amounts.len() == basket_escrow.offered.len(),
+        ErrorCode::InvalidBasketLegs
+    );
+
+    let config = &ctx.accounts.config;
+    let taker_key = ctx.accounts.taker.key();
+    let basket_key = basket_escrow.key();
+    let authority_seeds = &[
+        BASKET_ESCROW_SEED,
+        basket_escrow.initializer.as_ref(),
+        basket_escrow.unique_seed.as_ref(),
+        &[basket_escrow.bump],
+    ];
+    let signer_seeds = &[&authority_seeds[..]];
+
+    let mut requested_fills = Vec::with_capacity(basket_escrow.requested.len());
+    let requested_groups = ctx.remaining_accounts[..requested_end].chunks(5);
+    let requested_legs = basket_escrow.requested.iter().zip(max_taker_amounts.iter());
+    for (group, (leg, &max_taker_amount)) in requested_groups.zip(requested_legs) {
+        let [taker_token_account, mint, initializer_receive_token_account, token_program, treasury_token_account] = group else {
+            return err!(ErrorCode::InvalidBasketLegs);
+        };
+        require_keys_eq!(*mint.key, leg.mint, ErrorCode::InvalidMint);
+        require_keys_eq!(*token_program.key, leg.token_program, ErrorCode::InvalidTokenProgram);
+        require_keys_eq!(
+            *initializer_receive_token_account.key,
+            leg.token_account,
+            ErrorCode::InvalidAccount
+        );
+        let decimals = read_mint(mint, token_program.key)?.decimals;
+        let taker_side_fee = if config.charge_taker_side {
+            protocol_fee(leg.amount, config.fee_bps)?
+        } else {
+            0
+        };
+        // As in `exchange`, the fee comes out of the leg rather than on top of it
+        require!(
+            leg.amount <= max_taker_amount,
+            ErrorCode::TakerAmountAboveMax
+        );
+
+        // --- CPI 1: Taker pays the Initializer (requested leg) ---
+        let cpi_accounts = TransferChecked {
+            from: taker_token_account.clone(),
+            mint: mint.clone(),
+            to: initializer_receive_token_account.clone(),
+            authority: ctx.accounts.taker.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(token_program.clone(), cpi_accounts)
+            .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+        transfer_checked_with_hook(cpi_ctx, leg.amount - taker_side_fee, decimals)?;
+
+        // --- CPI 1b: Taker-side protocol fee to the treasury ---
+        if taker_side_fee > 0 {
+            let treasury = read_token_account(treasury_token_account, token_program.key)
+                .map_err(|_| error!(ErrorCode::MissingTreasuryAccount))?;
+            require_keys_eq!(treasury.mint, leg.mint, ErrorCode::InvalidMint);
+            require_keys_eq!(treasury.owner, config.treasury, ErrorCode::InvalidOwner);
+            let cpi_accounts_fee = TransferChecked {
+                from: taker_token_account.clone(),
+                mint: mint.clone(),
+                to: treasury_token_account.clone(),
+                authority: ctx.accounts.taker.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new(token_program.clone(), cpi_accounts_fee)
+                .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+            transfer_checked_with_hook(cpi_ctx, taker_side_fee, decimals)?;
+        }
+
+        requested_fills.push(BasketLegFill {
+            mint: leg.mint,
+            amount: leg.amount,
+            protocol_fee: taker_side_fee,
+        });
+    }
+
+    let mut offered_fills = Vec::with_capacity(basket_escrow.offered.len());
+    let offered_groups = ctx.remaining_accounts[requested_end..offered_end].chunks(5);
+    let offered_legs = basket_escrow.offered.iter().zip(expected_initializer_amounts.iter());
+    for (group, (leg, &expected_initializer_amount)) in offered_groups.zip(offered_legs) {
+        let [vault, mint, taker_receive_token_account, token_program, treasury_token_account] = group else {
+            return err!(ErrorCode::InvalidBasketLegs);
+        };
+        require_keys_eq!(*mint.key, leg.mint, ErrorCode::InvalidMint);
+        require_keys_eq!(*token_program.key, leg.token_program, ErrorCode::InvalidTokenProgram);
+        let expected_vault = Pubkey::find_program_address(
+            &[b"vault", basket_key.as_ref(), leg.mint.as_ref()],
+            &crate::ID,
+        )
+        .0;
+        require_keys_eq!(*vault.key, expected_vault, ErrorCode::InvalidAccount);
+        let destination = read_token_account(taker_receive_token_account, token_program.key)?;
+        require_keys_eq!(destination.mint, leg.mint, ErrorCode::InvalidMint);
+        require_keys_eq!(destination.owner, taker_key, ErrorCode::InvalidOwner);
+        let decimals = read_mint(mint, token_program.key)?.decimals;
+        let initializer_side_fee = if config.charge_initializer_side {
+            protocol_fee(leg.amount, config.fee_bps)?
+        } else {
+            0
+        };
+        let taker_proceeds = leg.amount - initializer_side_fee;
+        require!(
+            taker_proceeds >= expected_initializer_amount,
+            ErrorCode::InitializerAmountBelowExpected
+        );
+
+        // --- CPI 2: Vault pays the Taker (offered leg) ---
+        let cpi_accounts = TransferChecked {
+            from: vault.clone(),
+            mint: mint.clone(),
+            to: taker_receive_token_account.clone(),
+            authority: ctx.accounts.basket_escrow.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(token_program.clone(), cpi_accounts, signer_seeds)
+            .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+        transfer_checked_with_hook(cpi_ctx, taker_proceeds, decimals)?;
+
+        // --- CPI 2b: Initializer-side protocol fee to the treasury ---
+        if initializer_side_fee > 0 {
+            let treasury = read_token_account(treasury_token_account, token_program.key)
+                .map_err(|_| error!(ErrorCode::MissingTreasuryAccount))?;
+            require_keys_eq!(treasury.mint, leg.mint, ErrorCode::InvalidMint);
+            require_keys_eq!(treasury.owner, config.treasury, ErrorCode::InvalidOwner);
+            let cpi_accounts_fee = TransferChecked {
+                from: vault.clone(),
+                mint: mint.clone(),
+                to: treasury_token_account.clone(),
+                authority: ctx.accounts.basket_escrow.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(token_program.clone(), cpi_accounts_fee, signer_seeds)
+                .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+            transfer_checked_with_hook(cpi_ctx, initializer_side_fee, decimals)?;
+        }
+
+        // --- CPI 3: Close the leg's vault, rent goes back to the Initializer ---
+        let cpi_accounts_close = CloseAccount {
+            account: vault.clone(),
+            destination: ctx.accounts.initializer.to_account_info(),
+            authority: ctx.accounts.basket_escrow.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(token_program.clone(), cpi_accounts_close, signer_seeds);
+        token_interface::close_account(cpi_ctx)?;
+
+        offered_fills.push(BasketLegFill {
+            mint: leg.mint,
+            amount: leg.amount,
+            protocol_fee: initializer_side_fee,
+        });
+    }
+    {
+        let global_stats = &mut ctx.accounts.global_stats;
+        global_stats.total_escrows_completed = global_stats
+            .total_escrows_completed
+            .checked_add(1)
+            .ok_or(ErrorCode::Overflow)?;
+        global_stats.total_taker_side_fees = requested_fills
+            .iter()
+            .try_fold(global_stats.total_taker_side_fees, |total, fill| {
+                total.checked_add(fill.protocol_fee)
+            })
+            .ok_or(ErrorCode::Overflow)?;
+        global_stats.total_initializer_side_fees = offered_fills
+            .iter()
+            .try_fold(global_stats.total_initializer_side_fees, |total, fill| {
+                total.checked_add(fill.protocol_fee)
+            })
+            .ok_or(ErrorCode::Overflow)?;
+    }
+
+    let basket_escrow = &ctx.accounts.basket_escrow;
+    emit!(BasketExchanged {
+        initializer: basket_escrow.initializer,
+        taker: taker_key,
+        basket: basket_key,
+        offered: offered_fills,
+        requested: requested_fills,
+        unique_seed: basket_escrow.unique_seed,
+        timestamp: now,
+    });
+    Ok(())
+}
+
+pub(crate) fn cancel_basket<'info>(ctx: Context<'_, '_, '_, 'info, CancelBasket<'info>>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let basket_escrow = &ctx.accounts.basket_escrow;
+    let expired = ctx.accounts.caller.key() != basket_escrow.initializer;
+    if expired {
+        require!(now > basket_escrow.expires_at, ErrorCode::EscrowNotExpired);
+    }
+    let offered_end = basket_escrow.offered.len() * 4;
+    require!(
+        ctx.remaining_accounts.len() >= offered_end,
+        ErrorCode::InvalidBasketLegs
+    );
+
+    let basket_key = basket_escrow.key();
+    let authority_seeds = &[
+        BASKET_ESCROW_SEED,
+        basket_escrow.initializer.as_ref(),
+        basket_escrow.unique_seed.as_ref(),
+        &[basket_escrow.bump],
+    ];
+    let signer_seeds = &[&authority_seeds[..]];
+
+    let mut refunded = Vec::with_capacity(basket_escrow.offered.len());
+    let offered_groups = ctx.remaining_accounts[..offered_end].chunks(4);
+    for (group, leg) in offered_groups.zip(basket_escrow.offered.iter()) {
+        let [vault, mint, initializer_token_account, token_program] = group else {
+            return err!(ErrorCode::InvalidBasketLegs);
+        };
+        require_keys_eq!(*mint.key, leg.mint, ErrorCode::InvalidMint);
+        require_keys_eq!(*token_program.key, leg.token_program, ErrorCode::InvalidTokenProgram);
+        require_keys_eq!(
+            *initializer_token_account.key,
+            leg.token_account,
+            ErrorCode::InvalidAccount
+        );
+        let expected_vault = Pubkey::find_program_address(
+            &[b"vault", basket_key.as_ref(), leg.mint.as_ref()],
+            &crate::ID,
+        )
+        .0;
+        require_keys_eq!(*vault.key, expected_vault, ErrorCode::InvalidAccount);
+        let decimals = read_mint(mint, token_program.key)?.decimals;
+        let amount = read_token_account(vault, token_program.key)?.amount;
+
+        // --- CPI 1: Vault refunds the Initializer ---
+        if amount > 0 {
+            let cpi_accounts = TransferChecked {
+                from: vault.clone(),
+                mint: mint.clone(),
+                to: initializer_token_account.clone(),
+                authority: ctx.accounts.basket_escrow.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(token_program.clone(), cpi_accounts, signer_seeds)
+                .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+            transfer_checked_with_hook(cpi_ctx, amount, decimals)?;
+        }
+
+        // --- CPI 2: Close the leg's vault, rent goes back to the Initializer ---
+        let cpi_accounts_close = CloseAccount {
+            account: vault.clone(),
+            destination: ctx.accounts.initializer.to_account_info(),
+            authority: ctx.accounts.basket_escrow.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(token_program.clone(), cpi_accounts_close, signer_seeds);
+        token_interface::close_account(cpi_ctx)?;
+
+        refunded.push(BasketLeg { amount, ..*leg });
+    }
+
+    let global_stats = &mut ctx.accounts.global_stats;
+    global_stats.total_escrows_canceled = global_stats
+        .total_escrows_canceled
+        .checked_add(1)
+        .ok_or(ErrorCode::Overflow)?;
+
+    let basket_escrow = &ctx.accounts.basket_escrow;
+    emit!(BasketCanceled {
+        initializer: basket_escrow.initializer,
+        canceled_by: ctx.accounts.caller.key(),
+        basket: basket_key,
+        refunded,
+        expired,
+        unique_seed: basket_escrow.unique_seed,
+        timestamp: now,
+    });
+    Ok(())
+}
+
+// Creates the `[b"vault", basket, mint]` vault of one offered basket leg, owned by the
+// basket PDA. Token-2022 vaults are sized for the account extensions their mint requires.
+fn create_basket_vault<'info>(
+    payer: AccountInfo<'info>,
+    vault: AccountInfo<'info>,
+    mint: AccountInfo<'info>,
+    basket: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+) -> Result<()> {
+    let (expected_vault, vault_bump) = Pubkey::find_program_address(
+        &[b"vault", basket.key.as_ref(), mint.key.as_ref()],
+        &crate::ID,
+    );
+    require_keys_eq!(*vault.key, expected_vault, ErrorCode::InvalidAccount);
+
+    let space = if token_program.key.eq(&TOKEN_2022_PROGRAM_ID) {
+        let data = mint.try_borrow_data()?;
+        let mint_state = StateWithExtensions::<Token2022Mint>::unpack(&data)?;
+        let account_extensions =
+            ExtensionType::get_required_init_account_extensions(&mint_state.get_extension_types()?);
+        ExtensionType::try_calculate_account_len::<Token2022Account>(&account_extensions)?
+    } else {
+        ExtensionType::try_calculate_account_len::<Token2022Account>(&[])?
+    };
+
+    let vault_seeds = &[b"vault".as_ref(), basket.key.as_ref(), mint.key.as_ref(), &[vault_bump]];
+    anchor_lang::system_program::create_account(
+        CpiContext::new_with_signer(
+            system_program,
+            anchor_lang::system_program::CreateAccount {
+                from: payer,
+                to: vault.clone(),
+            },
+            &[&vault_seeds[..]],
+        ),
+        Rent::get()?.minimum_balance(space),
+        space as u64,
+        token_program.key,
+    )?;
+    token_interface::initialize_account3(CpiContext::new(
+        token_program,
+        InitializeAccount3 {
+            account: vault,
+            mint,
+            authority: basket,
+        },
+    ))
+}
+
+/// Accounts for the `create_basket_escrow` instruction. Legs come in remaining accounts.
+#[derive(Accounts)]
+#[instruction(unique_seed: [u8; 8])]
+pub struct CreateBasketEscrow<'info> {
+    /// The initializer funding every offered leg. Must sign the transaction.
+    #[account(mut)]
+    pub initializer: Signer<'info>,
+
+    /// The basket escrow PDA, owner of every leg vault
+    #[account(
+        init,
+        seeds = [BASKET_ESCROW_SEED, initializer.key().as_ref(), unique_seed.as_ref()],
+        bump,
+        payer = initializer,
+        space = BASKET_ESCROW_ACCOUNT_SPACE,
+    )]
+    pub basket_escrow: Account<'info, BasketEscrow>,
+
+    #[account(
+        mut,
+        seeds = [b"global-stats"],
+        bump = global_stats.bump
+    )]
+    pub global_stats: Account<'info, GlobalStats>,
+
+    /// Protocol config, checked for the pause flag
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Accounts for the `exchange_basket` instruction. Legs come in remaining accounts.
+#[derive(Accounts)]
+pub struct ExchangeBasket<'info> {
+    /// The taker paying every requested leg; must match `allowed_taker` for private baskets
+    #[account(
+        constraint = basket_escrow.allowed_taker.is_none_or(|allowed| allowed == taker.key()) @ErrorCode::UnauthorizedTaker
+    )]
+    pub taker: Signer<'info>,
+
+    /// CHECK: Matched against `basket_escrow.initializer`; receives the rent of the closed accounts
+    #[account(
+        mut,
+        address = basket_escrow.initializer @ErrorCode::InvalidOwner
+    )]
+    pub initializer: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [BASKET_ESCROW_SEED, basket_escrow.initializer.as_ref(), basket_escrow.unique_seed.as_ref()],
+        bump = basket_escrow.bump,
+        close = initializer,
+    )]
+    pub basket_escrow: Account<'info, BasketEscrow>,
+
+    #[account(
+        mut,
+        seeds = [b"global-stats"],
+        bump = global_stats.bump
+    )]
+    pub global_stats: Account<'info, GlobalStats>,
+
+    /// Protocol fee settings
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Accounts for the `cancel_basket` instruction. Offered legs come in remaining accounts.
+#[derive(Accounts)]
+pub struct CancelBasket<'info> {
+    /// The initializer, or anyone once the basket has expired
+    pub caller: Signer<'info>,
+
+    /// CHECK: Matched against `basket_escrow.initializer`; receives the rent of the closed accounts
+    #[account(
+        mut,
+        address = basket_escrow.initializer @ErrorCode::InvalidOwner
+    )]
+    pub initializer: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [BASKET_ESCROW_SEED, basket_escrow.initializer.as_ref(), basket_escrow.unique_seed.as_ref()],
+        bump = basket_escrow.bump,
+        close = initializer,
+    )]
+    pub basket_escrow: Account<'info, BasketEscrow>,
+
+    #[account(
+        mut,
+        seeds = [b"global-stats"],
+        bump = global_stats.bump
+    )]
+    pub global_stats: Account<'info, GlobalStats>,
+}
@@ -0,0 +1,489 @@
+use crate::*;
+
+pub(crate) fn accept<'info>(
+    ctx: Context<'_, '_, '_, 'info, Accept<'info>>,
+    expected_initializer_amount: u64,
+    max_taker_amount: u64,
+) -> Result<()> {
+    require!(!ctx.accounts.config.paused, ErrorCode::ProgramPaused);
+    let now = Clock::get()?.unix_timestamp;
+    let escrow_state = &ctx.accounts.escrow_state;
+    require!(now <= escrow_state.expires_at, ErrorCode::EscrowExpired);
+    require!(
+        escrow_state.collateral.is_some(),
+        ErrorCode::InvalidCollateralTerms
+    );
+    require!(
+        escrow_state.status == EscrowStatus::Open,
+        ErrorCode::EscrowAlreadyAccepted
+    );
+    require_keys_neq!(
+        ctx.accounts.taker.key(),
+        escrow_state.initializer_key,
+        ErrorCode::InvalidAccount
+    );
+    require!(
+        escrow_state.remaining_amount >= expected_initializer_amount,
+        ErrorCode::InitializerAmountBelowExpected
+    );
+
+    // With `taker_pays_transfer_fee`, the taker grosses up so settlement pays out the full price
+    let taker_payment = if escrow_state.taker_pays_transfer_fee {
+        gross_up_for_fee(
+            &ctx.accounts.taker_expected_mint.to_account_info(),
+            escrow_state.taker_expected_amount,
+        )?
+    } else {
+        escrow_state.taker_expected_amount
+    };
+    require!(
+        taker_payment <= max_taker_amount,
+        ErrorCode::TakerAmountAboveMax
+    );
+
+    // --- CPI: Taker locks Token B in the taker vault ---
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.taker_deposit_token_account.to_account_info(),
+        mint: ctx.accounts.taker_expected_mint.to_account_info(),
+        to: ctx.accounts.taker_vault.to_account_info(),
+        authority: ctx.accounts.taker.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.receive_token_program.to_account_info();
+    let cpi_context = CpiContext::new(cpi_program, cpi_accounts)
+        .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+    let taker_amount = deposit_to_vault(cpi_context, taker_payment, ctx.accounts.taker_expected_mint.decimals)?;
+    require!(taker_amount > 0, ErrorCode::InvalidAmount);
+
+    let taker_key = ctx.accounts.taker.key();
+    let escrow_state = &mut ctx.accounts.escrow_state;
+    escrow_state.status = EscrowStatus::Accepted;
+    let collateral = escrow_state
+        .collateral
+        .as_mut()
+        .ok_or(ErrorCode::InvalidCollateralTerms)?;
+    collateral.taker = taker_key;
+    collateral.taker_deposit_token_account = ctx.accounts.taker_deposit_token_account.key();
+    collateral.taker_amount = taker_amount;
+    let settle_at = collateral.settle_at;
+
+    emit!(EscrowAccepted {
+        initializer: escrow_state.initializer_key,
+        taker: taker_key,
+        taker_amount,
+        settle_at,
+        unique_seed: escrow_state.unique_seed,
+        timestamp: now,
+    });
+    Ok(())
+}
+
+pub(crate) fn confirm_settlement(ctx: Context<ConfirmSettlement>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let signer = ctx.accounts.signer.key();
+    let escrow_state = &mut ctx.accounts.escrow_state;
+    require!(
+        escrow_state.status == EscrowStatus::Accepted,
+        ErrorCode::EscrowNotAccepted
+    );
+    require!(now <= escrow_state.expires_at, ErrorCode::EscrowExpired);
+    let initializer_key = escrow_state.initializer_key;
+    let collateral = escrow_state
+        .collateral
+        .as_mut()
+        .ok_or(ErrorCode::InvalidCollateralTerms)?;
+    if signer == initializer_key {
+        collateral.initializer_confirmed = true;
+    } else if signer == collateral.taker {
+        collateral.taker_confirmed = true;
+    } else {
+        return err!(ErrorCode::Unauthorized);
+    }
+    let fully_confirmed = collateral.initializer_confirmed && collateral.taker_confirmed;
+
+    emit!(SettlementConfirmed {
+        initializer: initializer_key,
+        confirmed_by: signer,
+        fully_confirmed,
+        unique_seed: escrow_state.unique_seed,
+        timestamp: now,
+    });
+    Ok(())
+}
+
+pub(crate) fn settle<'info>(ctx: Context<'_, '_, '_, 'info, Settle<'info>>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let escrow_state = &ctx.accounts.escrow_state;
+    require!(
+        escrow_state.status == EscrowStatus::Accepted,
+        ErrorCode::EscrowNotAccepted
+    );
+    let collateral = escrow_state
+        .collateral
+        .ok_or(ErrorCode::InvalidCollateralTerms)?;
+    require!(
+        collateral_settleable(&collateral, now),
+        ErrorCode::CollateralNotSettleable
+    );
+    let by_confirmation = collateral.initializer_confirmed && collateral.taker_confirmed;
+
+    let taker_amount = ctx.accounts.taker_vault.amount;
+    let release_amount = escrow_state.remaining_amount;
+    let config = &ctx.accounts.config;
+    let taker_side_fee = if config.charge_taker_side {
+        protocol_fee(taker_amount, config.fee_bps)?
+    } else {
+        0
+    };
+    let initializer_side_fee = if config.charge_initializer_side {
+        protocol_fee(release_amount, config.fee_bps)?
+    } else {
+        0
+    };
+
+    let unique_seed = escrow_state.unique_seed;
+    let authority_seeds = &[
+        ESCROW_PDA_SEED,
+        escrow_state.initializer_key.as_ref(),
+        unique_seed.as_ref(),
+        &[escrow_state.bump],
+    ];
+    let signer_seeds = &[&authority_seeds[..]];
+
+    // --- CPI 1: Taker vault pays the Initializer (Token B) ---
+    let cpi_accounts_transfer = TransferChecked {
+        from: ctx.accounts.taker_vault.to_account_info(),
+        mint: ctx.accounts.taker_expected_mint.to_account_info(),
+        to: ctx.accounts.initializer_receive_token_account.to_account_info(),
+        authority: ctx.accounts.escrow_state.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.receive_token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts_transfer, signer_seeds)
+        .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+    transfer_checked_with_hook(
+        cpi_ctx,
+        taker_amount - taker_side_fee,
+        ctx.accounts.taker_expected_mint.decimals,
+    )?;
+
+    // --- CPI 1b: Taker-side protocol fee to the treasury (Token B) ---
+    if taker_side_fee > 0 {
+        let treasury_receive_token_account = ctx
+            .accounts
+            .treasury_receive_token_account
+            .as_ref()
+            .ok_or(ErrorCode::MissingTreasuryAccount)?;
+        let cpi_accounts_fee = TransferChecked {
+            from: ctx.accounts.taker_vault.to_account_info(),
+            mint: ctx.accounts.taker_expected_mint.to_account_info(),
+            to: treasury_receive_token_account.to_account_info(),
+            authority: ctx.accounts.escrow_state.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.receive_token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts_fee, signer_seeds)
+            .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+        transfer_checked_with_hook(cpi_ctx, taker_side_fee, ctx.accounts.taker_expected_mint.decimals)?;
+    }
+
+    // --- CPI 2: Vault pays the Taker (Token A) ---
+    let cpi_accounts_transfer = TransferChecked {
+        from: ctx.accounts.vault_account.to_account_info(),
+        mint: ctx.accounts.initializer_deposit_mint.to_account_info(),
+        to: ctx.accounts.taker_receive_token_account.to_account_info(),
+        authority: ctx.accounts.escrow_state.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.deposit_token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts_transfer, signer_seeds)
+        .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+    transfer_checked_with_hook(
+        cpi_ctx,
+        release_amount - initializer_side_fee,
+        ctx.accounts.initializer_deposit_mint.decimals,
+    )?;
+
+    // --- CPI 2b: Initializer-side protocol fee to the treasury (Token A) ---
+    if initializer_side_fee > 0 {
+        let treasury_deposit_token_account = ctx
+            .accounts
+            .treasury_deposit_token_account
+            .as_ref()
+            .ok_or(ErrorCode::MissingTreasuryAccount)?;
+        let cpi_accounts_fee = TransferChecked {
+            from: ctx.accounts.vault_account.to_account_info(),
+            mint: ctx.accounts.initializer_deposit_mint.to_account_info(),
+            to: treasury_deposit_token_account.to_account_info(),
+            authority: ctx.accounts.escrow_state.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.deposit_token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts_fee, signer_seeds)
+            .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+        transfer_checked_with_hook(cpi_ctx, initializer_side_fee, ctx.accounts.initializer_deposit_mint.decimals)?;
+    }
+
+    // --- CPI 3: Close both vaults; each party gets back the rent they paid ---
+    let cpi_accounts_close = CloseAccount {
+        account: ctx.accounts.taker_vault.to_account_info(),
+        destination: ctx.accounts.taker.to_account_info(),
+        authority: ctx.accounts.escrow_state.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.receive_token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts_close, signer_seeds);
+    token_interface::close_account(cpi_ctx)?;
+
+    let cpi_accounts_close = CloseAccount {
+        account: ctx.accounts.vault_account.to_account_info(),
+        destination: ctx.accounts.initializer.to_account_info(),
+        authority: ctx.accounts.escrow_state.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.deposit_token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts_close, signer_seeds);
+    token_interface::close_account(cpi_ctx)?;
+    {
+        let global_stats = &mut ctx.accounts.global_stats;
+        global_stats.total_value_released = global_stats
+            .total_value_released
+            .checked_add(release_amount)
+            .ok_or(ErrorCode::Overflow)?;
+        global_stats.total_value_locked = global_stats
+            .total_value_locked
+            .saturating_sub(release_amount);
+        global_stats.total_taker_side_fees = global_stats
+            .total_taker_side_fees
+            .checked_add(taker_side_fee)
+            .ok_or(ErrorCode::Overflow)?;
+        global_stats.total_initializer_side_fees = global_stats
+            .total_initializer_side_fees
+            .checked_add(initializer_side_fee)
+            .ok_or(ErrorCode::Overflow)?;
+        global_stats.total_escrows_completed = global_stats
+            .total_escrows_completed
+            .checked_add(1)
+            .ok_or(ErrorCode::Overflow)?;
+
+        let deposit_mint_stats = &mut ctx.accounts.deposit_mint_stats;
+        deposit_mint_stats.record_release(release_amount)?;
+        deposit_mint_stats.record_fills(1, release_amount, initializer_side_fee)?;
+
+        let receive_mint_stats = &mut ctx.accounts.receive_mint_stats;
+        receive_mint_stats.record_fills(1, taker_amount, taker_side_fee)?;
+    }
+
+    let escrow_state = &ctx.accounts.escrow_state;
+    emit!(CollateralSettled {
+        initializer: escrow_state.initializer_key,
+        taker: collateral.taker,
+        settled_by: ctx.accounts.cranker.key(),
+        initializer_amount: release_amount,
+        taker_amount,
+        taker_side_fee,
+        initializer_side_fee,
+        by_confirmation,
+        unique_seed,
+        timestamp: now,
+    });
+    emit!(ExchangeExecuted {
+        initializer: escrow_state.initializer_key,
+        taker: collateral.taker,
+        initializer_deposit_token_mint: escrow_state.initializer_deposit_token_mint,
+        taker_expected_token_mint: escrow_state.taker_expected_token_mint,
+        initializer_amount: release_amount,
+        taker_expected_amount: taker_amount,
+        taker_side_fee,
+        initializer_side_fee,
+        deposit_asset: escrow_state.deposit_asset,
+        receive_asset: escrow_state.receive_asset,
+        unique_seed,
+        timestamp: now,
+    });
+    Ok(())
+}
+
+/// Accounts for the `accept` instruction
+#[derive(Accounts)]
+pub struct Accept<'info> {
+    /// The taker locking Token B; must match `allowed_taker` for private escrows
+    #[account(
+        mut,
+        constraint = escrow_state.allowed_taker.is_none_or(|allowed| allowed == taker.key()) @ErrorCode::UnauthorizedTaker
+    )]
+    pub taker: Signer<'info>,
+
+    /// Taker's account holding Token B, refunded if the escrow unwinds
+    #[account(
+        mut,
+        token::mint = taker_expected_mint,
+        token::authority = taker,
+        token::token_program = receive_token_program
+    )]
+    pub taker_deposit_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_PDA_SEED, escrow_state.initializer_key.as_ref(), escrow_state.unique_seed.as_ref()],
+        bump = escrow_state.bump,
+    )]
+    pub escrow_state: Account<'info, EscrowState>,
+
+    /// PDA-owned vault for the taker's Token B, signed for by the escrow
+    #[account(
+        init,
+        token::mint = taker_expected_mint,
+        token::authority = escrow_state,
+        token::token_program = receive_token_program,
+        payer = taker,
+        seeds = [TAKER_VAULT_SEED, escrow_state.key().as_ref()],
+        bump
+    )]
+    pub taker_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = escrow_state.taker_expected_token_mint @ErrorCode::InvalidMint)]
+    pub taker_expected_mint: InterfaceAccount<'info, Mint>,
+
+    /// Protocol config, checked for the pause flag
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(address = escrow_state.receive_token_program @ErrorCode::InvalidTokenProgram)]
+    pub receive_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Accounts for the `confirm_settlement` instruction
+#[derive(Accounts)]
+pub struct ConfirmSettlement<'info> {
+    /// The initializer or the taker of the escrow
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_PDA_SEED, escrow_state.initializer_key.as_ref(), escrow_state.unique_seed.as_ref()],
+        bump = escrow_state.bump,
+    )]
+    pub escrow_state: Account<'info, EscrowState>,
+}
+
+/// Accounts for the `settle` instruction
+#[derive(Accounts)]
+pub struct Settle<'info> {
+    /// Anyone may crank a settleable escrow; only pays the transaction fee
+    pub cranker: Signer<'info>,
+
+    /// CHECK: Matched against `escrow_state.initializer_key`; receives the escrow rent
+    #[account(
+        mut,
+        address = escrow_state.initializer_key @ErrorCode::InvalidOwner
+    )]
+    pub initializer: AccountInfo<'info>,
+
+    /// CHECK: Matched against the taker recorded by `accept`; receives the taker vault rent
+    #[account(
+        mut,
+        constraint = escrow_state.collateral.is_some_and(|collateral| collateral.taker == taker.key()) @ErrorCode::InvalidOwner
+    )]
+    pub taker: AccountInfo<'info>,
+
+    /// Taker's account receiving Token A
+    #[account(
+        mut,
+        token::mint = initializer_deposit_mint,
+        token::authority = taker,
+        token::token_program = deposit_token_program
+    )]
+    pub taker_receive_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Initializer's receiving account for Token B
+    #[account(
+        mut,
+        address = escrow_state.initializer_receive_token_account @ErrorCode::InvalidAccount,
+    )]
+    pub initializer_receive_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_PDA_SEED, escrow_state.initializer_key.as_ref(), escrow_state.unique_seed.as_ref()],
+        bump = escrow_state.bump,
+        close = initializer,
+    )]
+    pub escrow_state: Account<'info, EscrowState>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", escrow_state.key().as_ref()],
+        bump,
+    )]
+    pub vault_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [TAKER_VAULT_SEED, escrow_state.key().as_ref()],
+        bump,
+    )]
+    pub taker_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"global-stats"],
+        bump = global_stats.bump
+    )]
+    pub global_stats: Account<'info, GlobalStats>,
+
+    /// Per-mint stats for Token A
+    #[account(
+        mut,
+        seeds = [MINT_STATS_SEED, escrow_state.initializer_deposit_token_mint.as_ref()],
+        bump = deposit_mint_stats.bump
+    )]
+    pub deposit_mint_stats: Account<'info, MintStats>,
+
+    /// Per-mint stats for Token B
+    #[account(
+        mut,
+        seeds = [MINT_STATS_SEED, escrow_state.taker_expected_token_mint.as_ref()],
+        bump = receive_mint_stats.bump
+    )]
+    pub receive_mint_stats: Account<'info, MintStats>,
+
+    #[account(address = escrow_state.initializer_deposit_token_mint @ErrorCode::InvalidMint)]
+    pub initializer_deposit_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(address = escrow_state.taker_expected_token_mint @ErrorCode::InvalidMint)]
+    pub taker_expected_mint: InterfaceAccount<'info, Mint>,
+
+    /// Protocol fee settings
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Treasury's Token A account, required when the initializer side is charged
+    #[account(
+        mut,
+        token::mint = initializer_deposit_mint,
+        token::authority = config.treasury,
+        token::token_program = deposit_token_program
+    )]
+    pub treasury_deposit_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Treasury's Token B account, required when the taker side is charged
+    #[account(
+        mut,
+        token::mint = taker_expected_mint,
+        token::authority = config.treasury,
+        token::token_program = receive_token_program
+    )]
+    pub treasury_receive_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address = escrow_state.deposit_token_program @ErrorCode::InvalidTokenProgram)]
+    pub deposit_token_program: Interface<'info, TokenInterface>,
+
+    #[account(address = escrow_state.receive_token_program @ErrorCode::InvalidTokenProgram)]
+    pub receive_token_program: Interface<'info, TokenInterface>,
+}
@@ -0,0 +1,314 @@
+use crate::*;
+
+pub(crate) fn fill_collection_bid<'info>(
+    ctx: Context<'_, '_, '_, 'info, FillCollectionBid<'info>>,
+    expected_initializer_amount: u64,
+) -> Result<()> {
+    require!(!ctx.accounts.config.paused, ErrorCode::ProgramPaused);
+    let now = Clock::get()?.unix_timestamp;
+    let escrow_state = &ctx.accounts.escrow_state;
+    require!(now <= escrow_state.expires_at, ErrorCode::EscrowExpired);
+    require!(escrow_state.collection_bid, ErrorCode::InvalidNftTerms);
+    require_keys_neq!(
+        ctx.accounts.taker.key(),
+        escrow_state.initializer_key,
+        ErrorCode::InvalidAccount
+    );
+    check_nft_mint(&ctx.accounts.member_mint)?;
+    require!(
+        token_group_of(&ctx.accounts.member_mint.to_account_info())?
+            == Some(escrow_state.taker_expected_token_mint),
+        ErrorCode::NotACollectionMember
+    );
+
+    let release_amount = escrow_state.remaining_amount;
+    let config = &ctx.accounts.config;
+    let initializer_side_fee = if config.charge_initializer_side {
+        protocol_fee(release_amount, config.fee_bps)?
+    } else {
+        0
+    };
+    let taker_proceeds = release_amount - initializer_side_fee;
+    require!(
+        taker_proceeds >= expected_initializer_amount,
+        ErrorCode::InitializerAmountBelowExpected
+    );
+
+    let deposit_asset = escrow_state.deposit_asset;
+    let unique_seed = escrow_state.unique_seed;
+    let authority_seeds = &[
+        ESCROW_PDA_SEED,
+        escrow_state.initializer_key.as_ref(),
+        unique_seed.as_ref(),
+        &[escrow_state.bump],
+    ];
+    let signer_seeds = &[&authority_seeds[..]];
+
+    // --- CPI 1: Taker sends the member NFT to the Initializer ---
+    let cpi_accounts_transfer = TransferChecked {
+        from: ctx.accounts.taker_nft_account.to_account_info(),
+        mint: ctx.accounts.member_mint.to_account_info(),
+        to: ctx.accounts.initializer_nft_account.to_account_info(),
+        authority: ctx.accounts.taker.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.receive_token_program.to_account_info();
+    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts_transfer)
+        .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+    transfer_checked_with_hook(cpi_ctx, 1, 0)?;
+
+    // --- CPI 2: Vault pays the Taker (Token A) ---
+    if deposit_asset == AssetKind::NativeSol {
+        // Unwrapped through a temporary account, so the Taker gets lamports
+        let unwrap_account = ctx
+            .accounts
+            .unwrap_account
+            .as_ref()
+            .ok_or(ErrorCode::MissingTokenAccount)?;
+        unwrap_from_vault(
+            ctx.accounts.deposit_token_program.to_account_info(),
+            &ctx.accounts.initializer_deposit_mint,
+            ctx.accounts.vault_account.to_account_info(),
+            unwrap_account.to_account_info(),
+            ctx.accounts.escrow_state.to_account_info(),
+            signer_seeds,
+            ctx.accounts.taker.to_account_info(),
+            taker_proceeds,
+        )?;
+    } else {
+        let taker_receive_token_account = ctx
+            .accounts
+            .taker_receive_token_account
+            .as_ref()
+            .ok_or(ErrorCode::MissingTokenAccount)?;
+        let cpi_accounts_transfer = TransferChecked {
+            from: ctx.accounts.vault_account.to_account_info(),
+            mint: ctx.accounts.initializer_deposit_mint.to_account_info(),
+            to: taker_receive_token_account.to_account_info(),
+            authority: ctx.accounts.escrow_state.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.deposit_token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts_transfer, signer_seeds)
+            .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+        transfer_checked_with_hook(
+            cpi_ctx,
+            taker_proceeds,
+            ctx.accounts.initializer_deposit_mint.decimals,
+        )?;
+    }
+
+    // --- CPI 2b: Initializer-side protocol fee to the treasury (Token A) ---
+    if initializer_side_fee > 0 {
+        let treasury_deposit_token_account = ctx
+            .accounts
+            .treasury_deposit_token_account
+            .as_ref()
+            .ok_or(ErrorCode::MissingTreasuryAccount)?;
+        let cpi_accounts_fee = TransferChecked {
+            from: ctx.accounts.vault_account.to_account_info(),
+            mint: ctx.accounts.initializer_deposit_mint.to_account_info(),
+            to: treasury_deposit_token_account.to_account_info(),
+            authority: ctx.accounts.escrow_state.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.deposit_token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts_fee, signer_seeds)
+            .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+        transfer_checked_with_hook(cpi_ctx, initializer_side_fee, ctx.accounts.initializer_deposit_mint.decimals)?;
+    }
+
+    // --- CPI 3: Close the vault, rent goes back to the Initializer ---
+    let cpi_accounts_close = CloseAccount {
+        account: ctx.accounts.vault_account.to_account_info(),
+        destination: ctx.accounts.initializer.to_account_info(),
+        authority: ctx.accounts.escrow_state.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.deposit_token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts_close, signer_seeds);
+    token_interface::close_account(cpi_ctx)?;
+    {
+        let global_stats = &mut ctx.accounts.global_stats;
+        global_stats.total_value_released = global_stats
+            .total_value_released
+            .checked_add(release_amount)
+            .ok_or(ErrorCode::Overflow)?;
+        global_stats.total_value_locked = global_stats
+            .total_value_locked
+            .saturating_sub(release_amount);
+        global_stats.total_initializer_side_fees = global_stats
+            .total_initializer_side_fees
+            .checked_add(initializer_side_fee)
+            .ok_or(ErrorCode::Overflow)?;
+        global_stats.total_escrows_completed = global_stats
+            .total_escrows_completed
+            .checked_add(1)
+            .ok_or(ErrorCode::Overflow)?;
+
+        let deposit_mint_stats = &mut ctx.accounts.deposit_mint_stats;
+        deposit_mint_stats.record_release(release_amount)?;
+        deposit_mint_stats.record_fills(1, release_amount, initializer_side_fee)?;
+
+        // Collection volume is counted in NFTs
+        let receive_mint_stats = &mut ctx.accounts.receive_mint_stats;
+        receive_mint_stats.record_fills(1, 1, 0)?;
+    }
+
+    let escrow_state = &ctx.accounts.escrow_state;
+    let taker_key = ctx.accounts.taker.key();
+    let member_mint = ctx.accounts.member_mint.key();
+    emit!(CollectionBidFilled {
+        initializer: escrow_state.initializer_key,
+        taker: taker_key,
+        collection: escrow_state.taker_expected_token_mint,
+        member_mint,
+        initializer_amount: release_amount,
+        initializer_side_fee,
+        unique_seed,
+        timestamp: now,
+    });
+    emit!(ExchangeExecuted {
+        initializer: escrow_state.initializer_key,
+        taker: taker_key,
+        initializer_deposit_token_mint: escrow_state.initializer_deposit_token_mint,
+        taker_expected_token_mint: member_mint,
+        initializer_amount: release_amount,
+        taker_expected_amount: 1,
+        taker_side_fee: 0,
+        initializer_side_fee,
+        deposit_asset,
+        receive_asset: escrow_state.receive_asset,
+        unique_seed,
+        timestamp: now,
+    });
+    Ok(())
+}
+
+/// Accounts for the `fill_collection_bid` instruction
+#[derive(Accounts)]
+pub struct FillCollectionBid<'info> {
+    /// The taker selling a member NFT; must match `allowed_taker` for private escrows
+    #[account(
+        mut,
+        constraint = escrow_state.allowed_taker.is_none_or(|allowed| allowed == taker.key()) @ErrorCode::UnauthorizedTaker
+    )]
+    pub taker: Signer<'info>,
+
+    /// CHECK: Matched against `escrow_state.initializer_key`; receives the NFT and the escrow rent
+    #[account(
+        mut,
+        address = escrow_state.initializer_key @ErrorCode::InvalidOwner
+    )]
+    pub initializer: AccountInfo<'info>,
+
+    /// Taker's account holding the member NFT
+    #[account(
+        mut,
+        token::mint = member_mint,
+        token::authority = taker,
+        token::token_program = receive_token_program
+    )]
+    pub taker_nft_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Initializer's associated account for the member NFT, created by the taker if needed
+    #[account(
+        init_if_needed,
+        payer = taker,
+        associated_token::mint = member_mint,
+        associated_token::authority = initializer,
+        associated_token::token_program = receive_token_program
+    )]
+    pub initializer_nft_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Taker's account receiving Token A; omitted for a native SOL leg
+    #[account(
+        mut,
+        token::mint = initializer_deposit_mint,
+        token::authority = taker,
+        token::token_program = deposit_token_program
+    )]
+    pub taker_receive_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_PDA_SEED, escrow_state.initializer_key.as_ref(), escrow_state.unique_seed.as_ref()],
+        bump = escrow_state.bump,
+        close = initializer,
+    )]
+    pub escrow_state: Account<'info, EscrowState>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", escrow_state.key().as_ref()],
+        bump,
+    )]
+    pub vault_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// The NFT sold, checked against the collection in `escrow_state.taker_expected_token_mint`
+    #[account(mint::token_program = receive_token_program)]
+    pub member_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(address = escrow_state.initializer_deposit_token_mint @ErrorCode::InvalidMint)]
+    pub initializer_deposit_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"global-stats"],
+        bump = global_stats.bump
+    )]
+    pub global_stats: Account<'info, GlobalStats>,
+
+    /// Per-mint stats for Token A
+    #[account(
+        mut,
+        seeds = [MINT_STATS_SEED, escrow_state.initializer_deposit_token_mint.as_ref()],
+        bump = deposit_mint_stats.bump
+    )]
+    pub deposit_mint_stats: Account<'info, MintStats>,
+
+    /// Per-mint stats for the collection mint
+    #[account(
+        mut,
+        seeds = [MINT_STATS_SEED, escrow_state.taker_expected_token_mint.as_ref()],
+        bump = receive_mint_stats.bump
+    )]
+    pub receive_mint_stats: Account<'info, MintStats>,
+
+    /// Protocol fee settings and pause flag
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Treasury's Token A account, required when the initializer side is charged
+    #[account(
+        mut,
+        token::mint = initializer_deposit_mint,
+        token::authority = config.treasury,
+        token::token_program = deposit_token_program
+    )]
+    pub treasury_deposit_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address = escrow_state.deposit_token_program @ErrorCode::InvalidTokenProgram)]
+    pub deposit_token_program: Interface<'info, TokenInterface>,
+
+    #[account(address = escrow_state.receive_token_program @ErrorCode::InvalidTokenProgram)]
+    pub receive_token_program: Interface<'info, TokenInterface>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+
+    /// Temporary wSOL account the release is unwrapped through, required for a native SOL
+    /// deposit and closed to the taker within the instruction
+    #[account(
+        init,
+        seeds = [UNWRAP_SEED, escrow_state.key().as_ref()],
+        bump,
+        payer = taker,
+        token::mint = initializer_deposit_mint,
+        token::authority = escrow_state,
+        token::token_program = deposit_token_program,
+    )]
+    pub unwrap_account: Option<InterfaceAccount<'info, TokenAccount>>,
+}
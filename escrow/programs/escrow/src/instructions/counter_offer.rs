@@ -0,0 +1,557 @@
+use crate::*;
+
+pub(crate) fn make_counter_offer<'info>(
+    ctx: Context<'_, '_, '_, 'info, MakeCounterOffer<'info>>,
+    taker_amount: u64,
+    initializer_amount: u64,
+    duration_in_seconds: i64,
+) -> Result<()> {
+    require!(!ctx.accounts.config.paused, ErrorCode::ProgramPaused);
+    check_duration(&ctx.accounts.config, duration_in_seconds)?;
+    let now = Clock::get()?.unix_timestamp;
+    let escrow_state = &ctx.accounts.escrow_state;
+    require!(now <= escrow_state.expires_at, ErrorCode::EscrowExpired);
+    require!(
+        escrow_state.deposit_asset == AssetKind::Token
+            && escrow_state.receive_asset == AssetKind::Token,
+        ErrorCode::CounterOfferNativeSol
+    );
+    require!(!escrow_state.collection_bid, ErrorCode::CollectionBid);
+    require!(
+        escrow_state.allowlist_root.is_none(),
+        ErrorCode::AllowlistedEscrow
+    );
+    require_keys_neq!(
+        ctx.accounts.taker.key(),
+        escrow_state.initializer_key,
+        ErrorCode::InvalidAccount
+    );
+    require!(taker_amount > 0, ErrorCode::InvalidAmount);
+    require!(
+        initializer_amount == escrow_state.remaining_amount,
+        ErrorCode::CounterOfferStale
+    );
+
+    // --- CPI: Taker escrows the proposed Token B ---
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.taker_deposit_token_account.to_account_info(),
+        mint: ctx.accounts.taker_expected_mint.to_account_info(),
+        to: ctx.accounts.counter_vault.to_account_info(),
+        authority: ctx.accounts.taker.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.receive_token_program.to_account_info();
+    let cpi_context = CpiContext::new(cpi_program, cpi_accounts)
+        .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+    let offered_amount = deposit_to_vault(cpi_context, taker_amount, ctx.accounts.taker_expected_mint.decimals)?;
+    require!(offered_amount > 0, ErrorCode::InvalidAmount);
+
+    let expires_at = now
+        .checked_add(duration_in_seconds)
+        .ok_or(ErrorCode::Overflow)?;
+    let counter_offer = &mut ctx.accounts.counter_offer;
+    counter_offer.escrow_state = ctx.accounts.escrow_state.key();
+    counter_offer.taker = ctx.accounts.taker.key();
+    counter_offer.taker_amount = offered_amount;
+    counter_offer.initializer_amount = initializer_amount;
+    counter_offer.expires_at = expires_at;
+    counter_offer.bump = ctx.bumps.counter_offer;
+
+    emit!(CounterOfferMade {
+        escrow: ctx.accounts.escrow_state.key(),
+        taker: ctx.accounts.taker.key(),
+        taker_amount: offered_amount,
+        initializer_amount,
+        expires_at,
+        timestamp: now,
+    });
+    Ok(())
+}
+
+pub(crate) fn accept_counter_offer<'info>(
+    ctx: Context<'_, '_, '_, 'info, AcceptCounterOffer<'info>>,
+    min_taker_amount: u64,
+) -> Result<()> {
+    require!(!ctx.accounts.config.paused, ErrorCode::ProgramPaused);
+    let now = Clock::get()?.unix_timestamp;
+    let escrow_state = &ctx.accounts.escrow_state;
+    let counter_offer = &ctx.accounts.counter_offer;
+    require!(now <= escrow_state.expires_at, ErrorCode::EscrowExpired);
+    require!(now <= counter_offer.expires_at, ErrorCode::CounterOfferExpired);
+    require!(
+        escrow_state.status == EscrowStatus::Open,
+        ErrorCode::EscrowAlreadyAccepted
+    );
+    require!(
+        counter_offer.initializer_amount == escrow_state.remaining_amount,
+        ErrorCode::CounterOfferStale
+    );
+    require!(
+        counter_offer.taker_amount >= min_taker_amount,
+        ErrorCode::CounterOfferBelowMinimum
+    );
+
+    let taker_amount = counter_offer.taker_amount;
+    let release_amount = escrow_state.remaining_amount;
+    let config = &ctx.accounts.config;
+    let taker_side_fee = if config.charge_taker_side {
+        protocol_fee(taker_amount, config.fee_bps)?
+    } else {
+        0
+    };
+    let initializer_side_fee = if config.charge_initializer_side {
+        protocol_fee(release_amount, config.fee_bps)?
+    } else {
+        0
+    };
+
+    let escrow_key = escrow_state.key();
+    let taker_key = counter_offer.taker;
+    let unique_seed = escrow_state.unique_seed;
+    let authority_seeds = &[
+        ESCROW_PDA_SEED,
+        escrow_state.initializer_key.as_ref(),
+        unique_seed.as_ref(),
+        &[escrow_state.bump],
+    ];
+    let signer_seeds = &[&authority_seeds[..]];
+    let counter_authority_seeds = &[
+        COUNTER_OFFER_SEED,
+        escrow_key.as_ref(),
+        taker_key.as_ref(),
+        &[counter_offer.bump],
+    ];
+    let counter_signer_seeds = &[&counter_authority_seeds[..]];
+
+    // --- CPI 1: Counter-offer vault pays the Initializer (Token B) ---
+    let cpi_accounts_transfer = TransferChecked {
+        from: ctx.accounts.counter_vault.to_account_info(),
+        mint: ctx.accounts.taker_expected_mint.to_account_info(),
+        to: ctx.accounts.initializer_receive_token_account.to_account_info(),
+        authority: ctx.accounts.counter_offer.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.receive_token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts_transfer, counter_signer_seeds)
+        .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+    transfer_checked_with_hook(
+        cpi_ctx,
+        taker_amount - taker_side_fee,
+        ctx.accounts.taker_expected_mint.decimals,
+    )?;
+
+    // --- CPI 1b: Taker-side protocol fee to the treasury (Token B) ---
+    if taker_side_fee > 0 {
+        let treasury_receive_token_account = ctx
+            .accounts
+            .treasury_receive_token_account
+            .as_ref()
+            .ok_or(ErrorCode::MissingTreasuryAccount)?;
+        let cpi_accounts_fee = TransferChecked {
+            from: ctx.accounts.counter_vault.to_account_info(),
+            mint: ctx.accounts.taker_expected_mint.to_account_info(),
+            to: treasury_receive_token_account.to_account_info(),
+            authority: ctx.accounts.counter_offer.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.receive_token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts_fee, counter_signer_seeds)
+            .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+        transfer_checked_with_hook(cpi_ctx, taker_side_fee, ctx.accounts.taker_expected_mint.decimals)?;
+    }
+
+    // --- CPI 2: Vault pays the Taker (Token A) ---
+    let cpi_accounts_transfer = TransferChecked {
+        from: ctx.accounts.vault_account.to_account_info(),
+        mint: ctx.accounts.initializer_deposit_mint.to_account_info(),
+        to: ctx.accounts.taker_receive_token_account.to_account_info(),
+        authority: ctx.accounts.escrow_state.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.deposit_token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts_transfer, signer_seeds)
+        .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+    transfer_checked_with_hook(
+        cpi_ctx,
+        release_amount - initializer_side_fee,
+        ctx.accounts.initializer_deposit_mint.decimals,
+    )?;
+
+    // --- CPI 2b: Initializer-side protocol fee to the treasury (Token A) ---
+    if initializer_side_fee > 0 {
+        let treasury_deposit_token_account = ctx
+            .accounts
+            .treasury_deposit_token_account
+            .as_ref()
+            .ok_or(ErrorCode::MissingTreasuryAccount)?;
+        let cpi_accounts_fee = TransferChecked {
+            from: ctx.accounts.vault_account.to_account_info(),
+            mint: ctx.accounts.initializer_deposit_mint.to_account_info(),
+            to: treasury_deposit_token_account.to_account_info(),
+            authority: ctx.accounts.escrow_state.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.deposit_token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts_fee, signer_seeds)
+            .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+        transfer_checked_with_hook(cpi_ctx, initializer_side_fee, ctx.accounts.initializer_deposit_mint.decimals)?;
+    }
+
+    // --- CPI 3: Close both vaults; each party gets back the rent they paid ---
+    let cpi_accounts_close = CloseAccount {
+        account: ctx.accounts.counter_vault.to_account_info(),
+        destination: ctx.accounts.taker.to_account_info(),
+        authority: ctx.accounts.counter_offer.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.receive_token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts_close, counter_signer_seeds);
+    token_interface::close_account(cpi_ctx)?;
+
+    let cpi_accounts_close = CloseAccount {
+        account: ctx.accounts.vault_account.to_account_info(),
+        destination: ctx.accounts.initializer.to_account_info(),
+        authority: ctx.accounts.escrow_state.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.deposit_token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts_close, signer_seeds);
+    token_interface::close_account(cpi_ctx)?;
+    {
+        let global_stats = &mut ctx.accounts.global_stats;
+        global_stats.total_value_released = global_stats
+            .total_value_released
+            .checked_add(release_amount)
+            .ok_or(ErrorCode::Overflow)?;
+        global_stats.total_value_locked = global_stats
+            .total_value_locked
+            .saturating_sub(release_amount);
+        global_stats.total_taker_side_fees = global_stats
+            .total_taker_side_fees
+            .checked_add(taker_side_fee)
+            .ok_or(ErrorCode::Overflow)?;
+        global_stats.total_initializer_side_fees = global_stats
+            .total_initializer_side_fees
+            .checked_add(initializer_side_fee)
+            .ok_or(ErrorCode::Overflow)?;
+        global_stats.total_escrows_completed = global_stats
+            .total_escrows_completed
+            .checked_add(1)
+            .ok_or(ErrorCode::Overflow)?;
+
+        let deposit_mint_stats = &mut ctx.accounts.deposit_mint_stats;
+        deposit_mint_stats.record_release(release_amount)?;
+        deposit_mint_stats.record_fills(1, release_amount, initializer_side_fee)?;
+
+        let receive_mint_stats = &mut ctx.accounts.receive_mint_stats;
+        receive_mint_stats.record_fills(1, taker_amount, taker_side_fee)?;
+    }
+
+    let escrow_state = &ctx.accounts.escrow_state;
+    emit!(CounterOfferAccepted {
+        escrow: escrow_key,
+        initializer: escrow_state.initializer_key,
+        taker: taker_key,
+        taker_amount,
+        initializer_amount: release_amount,
+        taker_side_fee,
+        initializer_side_fee,
+        unique_seed,
+        timestamp: now,
+    });
+    emit!(ExchangeExecuted {
+        initializer: escrow_state.initializer_key,
+        taker: taker_key,
+        initializer_deposit_token_mint: escrow_state.initializer_deposit_token_mint,
+        taker_expected_token_mint: escrow_state.taker_expected_token_mint,
+        initializer_amount: release_amount,
+        taker_expected_amount: taker_amount,
+        taker_side_fee,
+        initializer_side_fee,
+        deposit_asset: escrow_state.deposit_asset,
+        receive_asset: escrow_state.receive_asset,
+        unique_seed,
+        timestamp: now,
+    });
+    Ok(())
+}
+
+pub(crate) fn withdraw_counter_offer<'info>(
+    ctx: Context<'_, '_, '_, 'info, WithdrawCounterOffer<'info>>,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let counter_offer = &ctx.accounts.counter_offer;
+    let expired = ctx.accounts.caller.key() != counter_offer.taker;
+    if expired {
+        require!(now > counter_offer.expires_at, ErrorCode::Unauthorized);
+    }
+    let amount = ctx.accounts.counter_vault.amount;
+
+    let authority_seeds = &[
+        COUNTER_OFFER_SEED,
+        counter_offer.escrow_state.as_ref(),
+        counter_offer.taker.as_ref(),
+        &[counter_offer.bump],
+    ];
+    let signer_seeds = &[&authority_seeds[..]];
+
+    // --- CPI 1: Counter-offer vault refunds the Taker ---
+    if amount > 0 {
+        let cpi_accounts_transfer = TransferChecked {
+            from: ctx.accounts.counter_vault.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.taker_deposit_token_account.to_account_info(),
+            authority: ctx.accounts.counter_offer.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_context_transfer =
+            CpiContext::new_with_signer(cpi_program, cpi_accounts_transfer, signer_seeds)
+                .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+        transfer_checked_with_hook(cpi_context_transfer, amount, ctx.accounts.mint.decimals)?;
+    }
+
+    // --- CPI 2: Close the vault, rent goes back to the Taker ---
+    let cpi_accounts_close = CloseAccount {
+        account: ctx.accounts.counter_vault.to_account_info(),
+        destination: ctx.accounts.taker.to_account_info(),
+        authority: ctx.accounts.counter_offer.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_context_close =
+        CpiContext::new_with_signer(cpi_program, cpi_accounts_close, signer_seeds);
+
+    token_interface::close_account(cpi_context_close)?;
+
+    let counter_offer = &ctx.accounts.counter_offer;
+    emit!(CounterOfferWithdrawn {
+        escrow: counter_offer.escrow_state,
+        taker: counter_offer.taker,
+        withdrawn_by: ctx.accounts.caller.key(),
+        amount,
+        expired,
+        timestamp: now,
+    });
+    Ok(())
+}
+
+/// Accounts for the `make_counter_offer` instruction
+#[derive(Accounts)]
+pub struct MakeCounterOffer<'info> {
+    /// The taker proposing new terms; must match `allowed_taker` for private escrows
+    #[account(
+        mut,
+        constraint = escrow_state.allowed_taker.is_none_or(|allowed| allowed == taker.key()) @ErrorCode::UnauthorizedTaker
+    )]
+    pub taker: Signer<'info>,
+
+    /// Taker's account holding Token B
+    #[account(
+        mut,
+        token::mint = taker_expected_mint,
+        token::authority = taker,
+        token::token_program = receive_token_program
+    )]
+    pub taker_deposit_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [ESCROW_PDA_SEED, escrow_state.initializer_key.as_ref(), escrow_state.unique_seed.as_ref()],
+        bump = escrow_state.bump,
+    )]
+    pub escrow_state: Account<'info, EscrowState>,
+
+    /// The counter-offer PDA, one per taker and escrow
+    #[account(
+        init,
+        seeds = [COUNTER_OFFER_SEED, escrow_state.key().as_ref(), taker.key().as_ref()],
+        bump,
+        payer = taker,
+        space = COUNTER_OFFER_ACCOUNT_SPACE,
+    )]
+    pub counter_offer: Account<'info, CounterOffer>,
+
+    /// PDA-owned vault for the offered Token B, same derivation as the swap escrow vault
+    #[account(
+        init,
+        token::mint = taker_expected_mint,
+        token::authority = counter_offer,
+        token::token_program = receive_token_program,
+        payer = taker,
+        seeds = [b"vault", counter_offer.key().as_ref()],
+        bump
+    )]
+    pub counter_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = escrow_state.taker_expected_token_mint @ErrorCode::InvalidMint)]
+    pub taker_expected_mint: InterfaceAccount<'info, Mint>,
+
+    /// Protocol config, checked for the pause flag
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(address = escrow_state.receive_token_program @ErrorCode::InvalidTokenProgram)]
+    pub receive_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Accounts for the `accept_counter_offer` instruction
+#[derive(Accounts)]
+pub struct AcceptCounterOffer<'info> {
+    /// The initializer of the escrow. Must sign; receives the escrow rent.
+    #[account(mut)]
+    pub initializer: Signer<'info>,
+
+    /// CHECK: Matched against `counter_offer.taker`; receives the counter-offer rent
+    #[account(mut)]
+    pub taker: AccountInfo<'info>,
+
+    /// Taker's account receiving Token A
+    #[account(
+        mut,
+        token::mint = initializer_deposit_mint,
+        token::authority = taker,
+        token::token_program = deposit_token_program
+    )]
+    pub taker_receive_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Initializer's receiving account for Token B
+    #[account(
+        mut,
+        address = escrow_state.initializer_receive_token_account @ErrorCode::InvalidAccount,
+    )]
+    pub initializer_receive_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_PDA_SEED, initializer.key().as_ref(), escrow_state.unique_seed.as_ref()],
+        bump = escrow_state.bump,
+        close = initializer,
+    )]
+    pub escrow_state: Account<'info, EscrowState>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", escrow_state.key().as_ref()],
+        bump,
+    )]
+    pub vault_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [COUNTER_OFFER_SEED, escrow_state.key().as_ref(), taker.key().as_ref()],
+        bump = counter_offer.bump,
+        has_one = escrow_state @ErrorCode::InvalidAccount,
+        has_one = taker @ErrorCode::InvalidOwner,
+        close = taker,
+    )]
+    pub counter_offer: Account<'info, CounterOffer>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", counter_offer.key().as_ref()],
+        bump,
+    )]
+    pub counter_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"global-stats"],
+        bump = global_stats.bump
+    )]
+    pub global_stats: Account<'info, GlobalStats>,
+
+    /// Per-mint stats for Token A
+    #[account(
+        mut,
+        seeds = [MINT_STATS_SEED, escrow_state.initializer_deposit_token_mint.as_ref()],
+        bump = deposit_mint_stats.bump
+    )]
+    pub deposit_mint_stats: Account<'info, MintStats>,
+
+    /// Per-mint stats for Token B
+    #[account(
+        mut,
+        seeds = [MINT_STATS_SEED, escrow_state.taker_expected_token_mint.as_ref()],
+        bump = receive_mint_stats.bump
+    )]
+    pub receive_mint_stats: Account<'info, MintStats>,
+
+    #[account(address = escrow_state.initializer_deposit_token_mint @ErrorCode::InvalidMint)]
+    pub initializer_deposit_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(address = escrow_state.taker_expected_token_mint @ErrorCode::InvalidMint)]
+    pub taker_expected_mint: InterfaceAccount<'info, Mint>,
+
+    /// Protocol fee settings
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Treasury's Token A account, required when the initializer side is charged
+    #[account(
+        mut,
+        token::mint = initializer_deposit_mint,
+        token::authority = config.treasury,
+        token::token_program = deposit_token_program
+    )]
+    pub treasury_deposit_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Treasury's Token B account, required when the taker side is charged
+    #[account(
+        mut,
+        token::mint = taker_expected_mint,
+        token::authority = config.treasury,
+        token::token_program = receive_token_program
+    )]
+    pub treasury_receive_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address = escrow_state.deposit_token_program @ErrorCode::InvalidTokenProgram)]
+    pub deposit_token_program: Interface<'info, TokenInterface>,
+
+    #[account(address = escrow_state.receive_token_program @ErrorCode::InvalidTokenProgram)]
+    pub receive_token_program: Interface<'info, TokenInterface>,
+}
+
+/// Accounts for the `withdraw_counter_offer` instruction
+#[derive(Accounts)]
+pub struct WithdrawCounterOffer<'info> {
+    /// The taker, or anyone once the counter-offer has expired
+    pub caller: Signer<'info>,
+
+    /// CHECK: Matched against `counter_offer.taker`; receives the refund's rent
+    #[account(mut)]
+    pub taker: AccountInfo<'info>,
+
+    /// Taker's account receiving the Token B back
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = taker,
+        token::token_program = token_program
+    )]
+    pub taker_deposit_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [COUNTER_OFFER_SEED, counter_offer.escrow_state.as_ref(), taker.key().as_ref()],
+        bump = counter_offer.bump,
+        has_one = taker @ErrorCode::InvalidOwner,
+        close = taker,
+    )]
+    pub counter_offer: Account<'info, CounterOffer>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", counter_offer.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::token_program = token_program
+    )]
+    pub counter_vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
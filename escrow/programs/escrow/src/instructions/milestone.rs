@@ -0,0 +1,386 @@
+use crate::*;
+
+pub(crate) fn create_milestone_escrow<'info>(
+    ctx: Context<'_, '_, '_, 'info, CreateMilestoneEscrow<'info>>,
+    unique_seed: [u8; 8],
+    milestones: Vec<MilestoneTerms>,
+) -> Result<()> {
+    require!(!ctx.accounts.config.paused, ErrorCode::ProgramPaused);
+    check_token_program_id(ctx.accounts.token_program.key)?;
+    require!(
+        !milestones.is_empty() && milestones.len() <= MAX_MILESTONES,
+        ErrorCode::InvalidMilestoneCount
+    );
+    require_keys_neq!(
+        ctx.accounts.payee.key(),
+        ctx.accounts.payer.key(),
+        ErrorCode::InvalidAccount
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let mut total_amount: u64 = 0;
+    for terms in milestones.iter() {
+        require!(terms.amount > 0, ErrorCode::InvalidAmount);
+        if let Some(deadline) = terms.deadline {
+            check_duration(
+                &ctx.accounts.config,
+                deadline.checked_sub(now).ok_or(ErrorCode::Overflow)?,
+            )?;
+        }
+        total_amount = total_amount
+            .checked_add(terms.amount)
+            .ok_or(ErrorCode::Overflow)?;
+    }
+
+    // --- CPI: Payer funds the vault ---
+    // Milestones pay out exact amounts, so the payer covers any Token-2022 transfer fee
+    let deposit_amount = gross_up_for_fee(&ctx.accounts.mint.to_account_info(), total_amount)?;
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.payer_token_account.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        to: ctx.accounts.vault_account.to_account_info(),
+        authority: ctx.accounts.payer.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_context = CpiContext::new(cpi_program, cpi_accounts)
+        .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+    let deposited_amount = deposit_to_vault(cpi_context, deposit_amount, ctx.accounts.mint.decimals)?;
+    require!(deposited_amount >= total_amount, ErrorCode::InsufficientFunds);
+
+    let milestone_count = milestones.len() as u8;
+    let milestone_escrow = &mut ctx.accounts.milestone_escrow;
+    milestone_escrow.payer = ctx.accounts.payer.key();
+    milestone_escrow.payee = ctx.accounts.payee.key();
+    milestone_escrow.mint = ctx.accounts.mint.key();
+    milestone_escrow.token_program = ctx.accounts.token_program.key();
+    milestone_escrow.unique_seed = unique_seed;
+    milestone_escrow.created_at = now;
+    milestone_escrow.milestones = milestones
+        .into_iter()
+        .map(|terms| Milestone {
+            amount: terms.amount,
+            deadline: terms.deadline,
+            status: MilestoneStatus::Pending,
+        })
+        .collect();
+    milestone_escrow.bump = ctx.bumps.milestone_escrow;
+
+    let mint_stats = &mut ctx.accounts.mint_stats;
+    mint_stats.init_if_needed(ctx.accounts.mint.key(), ctx.bumps.mint_stats);
+    mint_stats.record_lock(total_amount)?;
+
+    emit!(MilestoneEscrowCreated {
+        payer: ctx.accounts.payer.key(),
+        payee: ctx.accounts.payee.key(),
+        mint: ctx.accounts.mint.key(),
+        total_amount,
+        milestone_count,
+        unique_seed,
+        timestamp: now,
+    });
+    Ok(())
+}
+
+pub(crate) fn approve_milestone<'info>(
+    ctx: Context<'_, '_, '_, 'info, SettleMilestone<'info>>,
+    index: u8,
+) -> Result<()> {
+    require_keys_eq!(
+        ctx.accounts.caller.key(),
+        ctx.accounts.milestone_escrow.payer,
+        ErrorCode::Unauthorized
+    );
+    let now = Clock::get()?.unix_timestamp;
+    let milestone = ctx
+        .accounts
+        .milestone_escrow
+        .milestones
+        .get(index as usize)
+        .ok_or(ErrorCode::InvalidMilestone)?;
+    require!(
+        milestone.status == MilestoneStatus::Pending,
+        ErrorCode::MilestoneAlreadySettled
+    );
+    let amount = milestone.amount;
+
+    let milestone_escrow = &ctx.accounts.milestone_escrow;
+    let authority_seeds = &[
+        MILESTONE_ESCROW_SEED,
+        milestone_escrow.payer.as_ref(),
+        milestone_escrow.unique_seed.as_ref(),
+        &[milestone_escrow.bump],
+    ];
+    let signer_seeds = &[&authority_seeds[..]];
+
+    // --- CPI: Vault pays the milestone to the payee ---
+    let cpi_accounts_transfer = TransferChecked {
+        from: ctx.accounts.vault_account.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        to: ctx.accounts.payee_token_account.to_account_info(),
+        authority: ctx.accounts.milestone_escrow.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_context_transfer =
+        CpiContext::new_with_signer(cpi_program, cpi_accounts_transfer, signer_seeds)
+            .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+    transfer_checked_with_hook(cpi_context_transfer, amount, ctx.accounts.mint.decimals)?;
+
+    ctx.accounts.milestone_escrow.milestones[index as usize].status = MilestoneStatus::Approved;
+    ctx.accounts.mint_stats.record_release(amount)?;
+    let settled = close_settled_milestone_escrow(ctx.accounts, ctx.remaining_accounts)?;
+
+    let milestone_escrow = &ctx.accounts.milestone_escrow;
+    emit!(MilestoneApproved {
+        payer: milestone_escrow.payer,
+        payee: milestone_escrow.payee,
+        index,
+        amount,
+        settled,
+        unique_seed: milestone_escrow.unique_seed,
+        timestamp: now,
+    });
+    Ok(())
+}
+
+pub(crate) fn refund_milestone<'info>(
+    ctx: Context<'_, '_, '_, 'info, SettleMilestone<'info>>,
+    index: u8,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let milestone = ctx
+        .accounts
+        .milestone_escrow
+        .milestones
+        .get(index as usize)
+        .ok_or(ErrorCode::InvalidMilestone)?;
+    require!(
+        milestone.status == MilestoneStatus::Pending,
+        ErrorCode::MilestoneAlreadySettled
+    );
+    let deadline = milestone.deadline.ok_or(ErrorCode::EscrowNotExpired)?;
+    require!(now > deadline, ErrorCode::EscrowNotExpired);
+    let amount = milestone.amount;
+
+    let milestone_escrow = &ctx.accounts.milestone_escrow;
+    let authority_seeds = &[
+        MILESTONE_ESCROW_SEED,
+        milestone_escrow.payer.as_ref(),
+        milestone_escrow.unique_seed.as_ref(),
+        &[milestone_escrow.bump],
+    ];
+    let signer_seeds = &[&authority_seeds[..]];
+
+    // --- CPI: Vault refunds the milestone to the payer ---
+    let cpi_accounts_transfer = TransferChecked {
+        from: ctx.accounts.vault_account.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        to: ctx.accounts.payer_token_account.to_account_info(),
+        authority: ctx.accounts.milestone_escrow.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_context_transfer =
+        CpiContext::new_with_signer(cpi_program, cpi_accounts_transfer, signer_seeds)
+            .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+    transfer_checked_with_hook(cpi_context_transfer, amount, ctx.accounts.mint.decimals)?;
+
+    ctx.accounts.milestone_escrow.milestones[index as usize].status = MilestoneStatus::Refunded;
+    ctx.accounts.mint_stats.record_refund(amount)?;
+    let settled = close_settled_milestone_escrow(ctx.accounts, ctx.remaining_accounts)?;
+
+    let milestone_escrow = &ctx.accounts.milestone_escrow;
+    emit!(MilestoneRefunded {
+        payer: milestone_escrow.payer,
+        cranker: ctx.accounts.caller.key(),
+        index,
+        amount,
+        deadline,
+        settled,
+        unique_seed: milestone_escrow.unique_seed,
+        timestamp: now,
+    });
+    Ok(())
+}
+
+// Once no milestone is pending, returns any leftover dust to the payer and closes the
+// vault and the milestone escrow. Returns whether the escrow was closed.
+fn close_settled_milestone_escrow<'info>(
+    accounts: &mut SettleMilestone<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+) -> Result<bool> {
+    let milestone_escrow = &accounts.milestone_escrow;
+    if milestone_escrow
+        .milestones
+        .iter()
+        .any(|milestone| milestone.status == MilestoneStatus::Pending)
+    {
+        return Ok(false);
+    }
+
+    let authority_seeds = &[
+        MILESTONE_ESCROW_SEED,
+        milestone_escrow.payer.as_ref(),
+        milestone_escrow.unique_seed.as_ref(),
+        &[milestone_escrow.bump],
+    ];
+    let signer_seeds = &[&authority_seeds[..]];
+
+    accounts.vault_account.reload()?;
+    let dust = accounts.vault_account.amount;
+    if dust > 0 {
+        let cpi_accounts_dust = TransferChecked {
+            from: accounts.vault_account.to_account_info(),
+            mint: accounts.mint.to_account_info(),
+            to: accounts.payer_token_account.to_account_info(),
+            authority: accounts.milestone_escrow.to_account_info(),
+        };
+        let cpi_program = accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts_dust, signer_seeds)
+            .with_remaining_accounts(remaining_accounts.to_vec());
+
+        transfer_checked_with_hook(cpi_ctx, dust, accounts.mint.decimals)?;
+    }
+
+    let cpi_accounts_close = CloseAccount {
+        account: accounts.vault_account.to_account_info(),
+        destination: accounts.payer.to_account_info(),
+        authority: accounts.milestone_escrow.to_account_info(),
+    };
+    let cpi_program = accounts.token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts_close, signer_seeds);
+    token_interface::close_account(cpi_ctx)?;
+
+    accounts
+        .milestone_escrow
+        .close(accounts.payer.to_account_info())?;
+    Ok(true)
+}
+
+/// Accounts for the `create_milestone_escrow` instruction
+#[derive(Accounts)]
+#[instruction(unique_seed: [u8; 8])]
+pub struct CreateMilestoneEscrow<'info> {
+    /// The payer funding every milestone. Must sign the transaction.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Any wallet; paid as milestones are approved
+    pub payee: AccountInfo<'info>,
+
+    /// The payer's token account funding the vault
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = payer,
+        token::token_program = token_program
+    )]
+    pub payer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// The milestone escrow PDA
+    #[account(
+        init,
+        seeds = [MILESTONE_ESCROW_SEED, payer.key().as_ref(), unique_seed.as_ref()],
+        bump,
+        payer = payer,
+        space = MILESTONE_ESCROW_ACCOUNT_SPACE,
+    )]
+    pub milestone_escrow: Account<'info, MilestoneEscrow>,
+
+    /// PDA-owned vault, same seeds as the swap escrow vault
+    #[account(
+        init,
+        token::mint = mint,
+        token::authority = milestone_escrow,
+        token::token_program = token_program,
+        payer = payer,
+        seeds = [b"vault", milestone_escrow.key().as_ref()],
+        bump
+    )]
+    pub vault_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Per-mint stats, created on first use
+    #[account(
+        init_if_needed,
+        seeds = [MINT_STATS_SEED, mint.key().as_ref()],
+        bump,
+        payer = payer,
+        space = MINT_STATS_ACCOUNT_SPACE,
+    )]
+    pub mint_stats: Account<'info, MintStats>,
+
+    /// Protocol config, checked for the pause flag
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Accounts for the `approve_milestone` and `refund_milestone` instructions
+#[derive(Accounts)]
+pub struct SettleMilestone<'info> {
+    /// The payer for approvals; anyone for refunds past a deadline
+    pub caller: Signer<'info>,
+
+    /// CHECK: Matched against `milestone_escrow.payer`; receives refunds' rent on close
+    #[account(mut)]
+    pub payer: AccountInfo<'info>,
+
+    /// CHECK: Matched against `milestone_escrow.payee`
+    pub payee: AccountInfo<'info>,
+
+    /// The payer's token account receiving refunds and dust
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = payer,
+        token::token_program = token_program
+    )]
+    pub payer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// The payee's token account receiving approved milestones
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = payee,
+        token::token_program = token_program
+    )]
+    pub payee_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [MILESTONE_ESCROW_SEED, milestone_escrow.payer.as_ref(), milestone_escrow.unique_seed.as_ref()],
+        bump = milestone_escrow.bump,
+        has_one = payer @ErrorCode::InvalidOwner,
+        has_one = payee @ErrorCode::InvalidAccount,
+        has_one = mint @ErrorCode::InvalidMint,
+    )]
+    pub milestone_escrow: Account<'info, MilestoneEscrow>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", milestone_escrow.key().as_ref()],
+        bump,
+    )]
+    pub vault_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [MINT_STATS_SEED, mint.key().as_ref()],
+        bump = mint_stats.bump
+    )]
+    pub mint_stats: Account<'info, MintStats>,
+
+    #[account(address = milestone_escrow.token_program @ErrorCode::InvalidTokenProgram)]
+    pub token_program: Interface<'info, TokenInterface>,
+}
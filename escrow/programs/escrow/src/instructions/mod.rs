@@ -0,0 +1,23 @@
+pub mod admin;
+pub mod swap;
+pub mod service;
+pub mod milestone;
+pub mod vesting;
+pub mod auction;
+pub mod counter_offer;
+pub mod collateral;
+pub mod basket;
+pub mod collection_bid;
+pub mod signed_order;
+
+pub use admin::*;
+pub use swap::*;
+pub use service::*;
+pub use milestone::*;
+pub use vesting::*;
+pub use auction::*;
+pub use counter_offer::*;
+pub use collateral::*;
+pub use basket::*;
+pub use collection_bid::*;
+pub use signed_order::*;
@@ -0,0 +1,463 @@
+use crate::*;
+
+pub(crate) fn create_service_escrow<'info>(
+    ctx: Context<'_, '_, '_, 'info, CreateServiceEscrow<'info>>,
+    amount: u64,
+    unique_seed: [u8; 8],
+    review_period_seconds: i64,
+    dispute_timeout_seconds: i64,
+) -> Result<()> {
+    require!(!ctx.accounts.config.paused, ErrorCode::ProgramPaused);
+    check_token_program_id(ctx.accounts.token_program.key)?;
+    require!(amount > 0, ErrorCode::InvalidAmount);
+    check_duration(&ctx.accounts.config, review_period_seconds)?;
+    check_duration(&ctx.accounts.config, dispute_timeout_seconds)?;
+
+    let buyer = ctx.accounts.buyer.key();
+    let seller = ctx.accounts.seller.key();
+    let arbiter = ctx.accounts.arbiter.key();
+    require_keys_neq!(seller, buyer, ErrorCode::InvalidAccount);
+    require!(arbiter != buyer && arbiter != seller, ErrorCode::InvalidArbiter);
+
+    // --- CPI: Buyer funds the vault ---
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.buyer_token_account.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        to: ctx.accounts.vault_account.to_account_info(),
+        authority: ctx.accounts.buyer.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_context = CpiContext::new(cpi_program, cpi_accounts)
+        .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+    let deposited_amount = deposit_to_vault(cpi_context, amount, ctx.accounts.mint.decimals)?;
+    require!(deposited_amount > 0, ErrorCode::InvalidAmount);
+
+    let now = Clock::get()?.unix_timestamp;
+    let review_deadline = now
+        .checked_add(review_period_seconds)
+        .ok_or(ErrorCode::Overflow)?;
+
+    let service_escrow = &mut ctx.accounts.service_escrow;
+    service_escrow.buyer = buyer;
+    service_escrow.seller = seller;
+    service_escrow.arbiter = arbiter;
+    service_escrow.mint = ctx.accounts.mint.key();
+    service_escrow.token_program = ctx.accounts.token_program.key();
+    service_escrow.amount = deposited_amount;
+    service_escrow.unique_seed = unique_seed;
+    service_escrow.status = ServiceStatus::Funded;
+    service_escrow.created_at = now;
+    service_escrow.review_deadline = review_deadline;
+    service_escrow.dispute_timeout_seconds = dispute_timeout_seconds;
+    service_escrow.dispute_deadline = 0;
+    service_escrow.bump = ctx.bumps.service_escrow;
+
+    let mint_stats = &mut ctx.accounts.mint_stats;
+    mint_stats.init_if_needed(ctx.accounts.mint.key(), ctx.bumps.mint_stats);
+    mint_stats.record_lock(deposited_amount)?;
+
+    emit!(ServiceEscrowCreated {
+        buyer,
+        seller,
+        arbiter,
+        mint: ctx.accounts.mint.key(),
+        amount: deposited_amount,
+        review_deadline,
+        unique_seed,
+        timestamp: now,
+    });
+    Ok(())
+}
+
+pub(crate) fn release<'info>(ctx: Context<'_, '_, '_, 'info, Release<'info>>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let service_escrow = &ctx.accounts.service_escrow;
+    require!(
+        service_escrow.status == ServiceStatus::Funded,
+        ErrorCode::EscrowDisputed
+    );
+    let auto_release = ctx.accounts.caller.key() != service_escrow.buyer;
+    if auto_release {
+        require!(
+            now > service_escrow.review_deadline,
+            ErrorCode::ReviewPeriodActive
+        );
+    }
+
+    let authority_seeds = &[
+        SERVICE_ESCROW_SEED,
+        service_escrow.buyer.as_ref(),
+        service_escrow.unique_seed.as_ref(),
+        &[service_escrow.bump],
+    ];
+    let signer_seeds = &[&authority_seeds[..]];
+    let amount = service_escrow.amount;
+
+    // --- CPI 1: Vault pays the seller ---
+    let cpi_accounts_transfer = TransferChecked {
+        from: ctx.accounts.vault_account.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        to: ctx.accounts.seller_token_account.to_account_info(),
+        authority: ctx.accounts.service_escrow.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_context_transfer =
+        CpiContext::new_with_signer(cpi_program, cpi_accounts_transfer, signer_seeds)
+            .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+    transfer_checked_with_hook(cpi_context_transfer, amount, ctx.accounts.mint.decimals)?;
+
+    // --- CPI 2: Close the vault, rent goes back to the buyer ---
+    let cpi_accounts_close = CloseAccount {
+        account: ctx.accounts.vault_account.to_account_info(),
+        destination: ctx.accounts.buyer.to_account_info(),
+        authority: ctx.accounts.service_escrow.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_context_close =
+        CpiContext::new_with_signer(cpi_program, cpi_accounts_close, signer_seeds);
+
+    token_interface::close_account(cpi_context_close)?;
+    ctx.accounts.mint_stats.record_release(amount)?;
+
+    let service_escrow = &ctx.accounts.service_escrow;
+    emit!(ServiceEscrowReleased {
+        buyer: service_escrow.buyer,
+        seller: service_escrow.seller,
+        released_by: ctx.accounts.caller.key(),
+        amount,
+        auto_release,
+        unique_seed: service_escrow.unique_seed,
+        timestamp: now,
+    });
+    Ok(())
+}
+
+pub(crate) fn raise_dispute(ctx: Context<RaiseDispute>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let service_escrow = &mut ctx.accounts.service_escrow;
+    require!(
+        service_escrow.status == ServiceStatus::Funded,
+        ErrorCode::EscrowDisputed
+    );
+    require!(
+        now <= service_escrow.review_deadline,
+        ErrorCode::ReviewPeriodOver
+    );
+
+    service_escrow.status = ServiceStatus::Disputed;
+    service_escrow.dispute_deadline = now
+        .checked_add(service_escrow.dispute_timeout_seconds)
+        .ok_or(ErrorCode::Overflow)?;
+
+    emit!(DisputeRaised {
+        buyer: service_escrow.buyer,
+        seller: service_escrow.seller,
+        arbiter: service_escrow.arbiter,
+        raised_by: ctx.accounts.caller.key(),
+        dispute_deadline: service_escrow.dispute_deadline,
+        unique_seed: service_escrow.unique_seed,
+        timestamp: now,
+    });
+    Ok(())
+}
+
+pub(crate) fn resolve_dispute<'info>(
+    ctx: Context<'_, '_, '_, 'info, ResolveDispute<'info>>,
+    seller_amount: u64,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let service_escrow = &ctx.accounts.service_escrow;
+    require!(
+        service_escrow.status == ServiceStatus::Disputed,
+        ErrorCode::NotDisputed
+    );
+
+    let amount = service_escrow.amount;
+    let by_arbiter = now <= service_escrow.dispute_deadline;
+    let seller_amount = if by_arbiter {
+        require_keys_eq!(
+            ctx.accounts.resolver.key(),
+            service_escrow.arbiter,
+            ErrorCode::Unauthorized
+        );
+        require!(seller_amount <= amount, ErrorCode::InvalidAmount);
+        seller_amount
+    } else {
+        0
+    };
+    let buyer_amount = amount - seller_amount;
+
+    let authority_seeds = &[
+        SERVICE_ESCROW_SEED,
+        service_escrow.buyer.as_ref(),
+        service_escrow.unique_seed.as_ref(),
+        &[service_escrow.bump],
+    ];
+    let signer_seeds = &[&authority_seeds[..]];
+
+    // --- CPI 1: Seller's share ---
+    if seller_amount > 0 {
+        let cpi_accounts_transfer = TransferChecked {
+            from: ctx.accounts.vault_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.seller_token_account.to_account_info(),
+            authority: ctx.accounts.service_escrow.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_context_transfer =
+            CpiContext::new_with_signer(cpi_program, cpi_accounts_transfer, signer_seeds)
+                .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+        transfer_checked_with_hook(cpi_context_transfer, seller_amount, ctx.accounts.mint.decimals)?;
+    }
+
+    // --- CPI 2: Buyer's share ---
+    if buyer_amount > 0 {
+        let cpi_accounts_transfer = TransferChecked {
+            from: ctx.accounts.vault_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.buyer_token_account.to_account_info(),
+            authority: ctx.accounts.service_escrow.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_context_transfer =
+            CpiContext::new_with_signer(cpi_program, cpi_accounts_transfer, signer_seeds)
+                .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+        transfer_checked_with_hook(cpi_context_transfer, buyer_amount, ctx.accounts.mint.decimals)?;
+    }
+
+    // --- CPI 3: Close the vault, rent goes back to the buyer ---
+    let cpi_accounts_close = CloseAccount {
+        account: ctx.accounts.vault_account.to_account_info(),
+        destination: ctx.accounts.buyer.to_account_info(),
+        authority: ctx.accounts.service_escrow.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_context_close =
+        CpiContext::new_with_signer(cpi_program, cpi_accounts_close, signer_seeds);
+
+    token_interface::close_account(cpi_context_close)?;
+    {
+        let mint_stats = &mut ctx.accounts.mint_stats;
+        mint_stats.record_release(seller_amount)?;
+        mint_stats.record_refund(buyer_amount)?;
+    }
+
+    let service_escrow = &ctx.accounts.service_escrow;
+    emit!(DisputeResolved {
+        buyer: service_escrow.buyer,
+        seller: service_escrow.seller,
+        resolved_by: ctx.accounts.resolver.key(),
+        seller_amount,
+        buyer_amount,
+        by_arbiter,
+        unique_seed: service_escrow.unique_seed,
+        timestamp: now,
+    });
+    Ok(())
+}
+
+/// Accounts for the `create_service_escrow` instruction
+#[derive(Accounts)]
+#[instruction(amount: u64, unique_seed: [u8; 8])]
+pub struct CreateServiceEscrow<'info> {
+    /// The buyer locking funds. Must sign the transaction.
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    /// CHECK: Any wallet; paid when the escrow is released
+    pub seller: AccountInfo<'info>,
+
+    /// CHECK: Any wallet other than the buyer and seller; rules on disputes
+    pub arbiter: AccountInfo<'info>,
+
+    /// The buyer's token account funding the vault
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = buyer,
+        token::token_program = token_program
+    )]
+    pub buyer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// The service escrow PDA
+    #[account(
+        init,
+        seeds = [SERVICE_ESCROW_SEED, buyer.key().as_ref(), unique_seed.as_ref()],
+        bump,
+        payer = buyer,
+        space = SERVICE_ESCROW_ACCOUNT_SPACE,
+    )]
+    pub service_escrow: Account<'info, ServiceEscrow>,
+
+    /// PDA-owned vault, same seeds as the swap escrow vault
+    #[account(
+        init,
+        token::mint = mint,
+        token::authority = service_escrow,
+        token::token_program = token_program,
+        payer = buyer,
+        seeds = [b"vault", service_escrow.key().as_ref()],
+        bump
+    )]
+    pub vault_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Per-mint stats, created on first use
+    #[account(
+        init_if_needed,
+        seeds = [MINT_STATS_SEED, mint.key().as_ref()],
+        bump,
+        payer = buyer,
+        space = MINT_STATS_ACCOUNT_SPACE,
+    )]
+    pub mint_stats: Account<'info, MintStats>,
+
+    /// Protocol config, checked for the pause flag
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Accounts for the `release` instruction
+#[derive(Accounts)]
+pub struct Release<'info> {
+    /// The buyer, or anyone once the review period is over
+    pub caller: Signer<'info>,
+
+    /// CHECK: Matched against `service_escrow.buyer`; receives the rent
+    #[account(mut)]
+    pub buyer: AccountInfo<'info>,
+
+    /// CHECK: Matched against `service_escrow.seller`
+    pub seller: AccountInfo<'info>,
+
+    /// The seller's token account receiving the vault
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = seller,
+        token::token_program = token_program
+    )]
+    pub seller_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [SERVICE_ESCROW_SEED, service_escrow.buyer.as_ref(), service_escrow.unique_seed.as_ref()],
+        bump = service_escrow.bump,
+        has_one = buyer @ErrorCode::InvalidOwner,
+        has_one = seller @ErrorCode::InvalidAccount,
+        has_one = mint @ErrorCode::InvalidMint,
+        close = buyer,
+    )]
+    pub service_escrow: Account<'info, ServiceEscrow>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", service_escrow.key().as_ref()],
+        bump,
+    )]
+    pub vault_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [MINT_STATS_SEED, mint.key().as_ref()],
+        bump = mint_stats.bump
+    )]
+    pub mint_stats: Account<'info, MintStats>,
+
+    #[account(address = service_escrow.token_program @ErrorCode::InvalidTokenProgram)]
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Accounts for the `raise_dispute` instruction
+#[derive(Accounts)]
+pub struct RaiseDispute<'info> {
+    /// The buyer or the seller
+    #[account(
+        constraint = caller.key() == service_escrow.buyer
+            || caller.key() == service_escrow.seller @ErrorCode::Unauthorized
+    )]
+    pub caller: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [SERVICE_ESCROW_SEED, service_escrow.buyer.as_ref(), service_escrow.unique_seed.as_ref()],
+        bump = service_escrow.bump,
+    )]
+    pub service_escrow: Account<'info, ServiceEscrow>,
+}
+
+/// Accounts for the `resolve_dispute` instruction
+#[derive(Accounts)]
+pub struct ResolveDispute<'info> {
+    /// The arbiter, or anyone once the dispute deadline has passed
+    pub resolver: Signer<'info>,
+
+    /// CHECK: Matched against `service_escrow.buyer`; receives the rent
+    #[account(mut)]
+    pub buyer: AccountInfo<'info>,
+
+    /// CHECK: Matched against `service_escrow.seller`
+    pub seller: AccountInfo<'info>,
+
+    /// The buyer's token account receiving the refunded share
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = buyer,
+        token::token_program = token_program
+    )]
+    pub buyer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// The seller's token account receiving the awarded share
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = seller,
+        token::token_program = token_program
+    )]
+    pub seller_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [SERVICE_ESCROW_SEED, service_escrow.buyer.as_ref(), service_escrow.unique_seed.as_ref()],
+        bump = service_escrow.bump,
+        has_one = buyer @ErrorCode::InvalidOwner,
+        has_one = seller @ErrorCode::InvalidAccount,
+        has_one = mint @ErrorCode::InvalidMint,
+        close = buyer,
+    )]
+    pub service_escrow: Account<'info, ServiceEscrow>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", service_escrow.key().as_ref()],
+        bump,
+    )]
+    pub vault_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [MINT_STATS_SEED, mint.key().as_ref()],
+        bump = mint_stats.bump
+    )]
+    pub mint_stats: Account<'info, MintStats>,
+
+    #[account(address = service_escrow.token_program @ErrorCode::InvalidTokenProgram)]
+    pub token_program: Interface<'info, TokenInterface>,
+}
@@ -0,0 +1,324 @@
+use crate::*;
+
+pub(crate) fn fill_signed_order<'info>(
+    ctx: Context<'_, '_, '_, 'info, FillSignedOrder<'info>>,
+    order: SignedOrder,
+    expected_maker_amount: u64,
+) -> Result<()> {
+    require!(!ctx.accounts.config.paused, ErrorCode::ProgramPaused);
+    check_token_program_id(&ctx.accounts.deposit_token_program.key())?;
+    check_token_program_id(&ctx.accounts.receive_token_program.key())?;
+
+    let now = Clock::get()?.unix_timestamp;
+    require!(now <= order.expires_at, ErrorCode::OrderExpired);
+    require!(
+        order.maker_amount > 0 && order.taker_amount > 0,
+        ErrorCode::InvalidAmount
+    );
+    let taker_key = ctx.accounts.taker.key();
+    require_keys_neq!(taker_key, order.maker, ErrorCode::InvalidAccount);
+    require!(
+        order.allowed_taker.is_none_or(|allowed| allowed == taker_key),
+        ErrorCode::UnauthorizedTaker
+    );
+
+    verify_ed25519_ix(
+        &ctx.accounts.instructions.to_account_info(),
+        &order.maker,
+        &signed_order_message(&order)?,
+    )?;
+
+    // A nonce is spent once, by a fill or a cancellation
+    let order_nonce = &mut ctx.accounts.order_nonce;
+    require!(!order_nonce.canceled, ErrorCode::OrderCanceled);
+    require!(!order_nonce.filled, ErrorCode::OrderAlreadyFilled);
+    order_nonce.maker = order.maker;
+    order_nonce.nonce = order.nonce;
+    order_nonce.filled = true;
+    order_nonce.bump = ctx.bumps.order_nonce;
+
+    // Protocol fees skimmed from each leg into the treasury, per `Config`
+    let config = &ctx.accounts.config;
+    let taker_side_fee = if config.charge_taker_side {
+        protocol_fee(order.taker_amount, config.fee_bps)?
+    } else {
+        0
+    };
+    let maker_side_fee = if config.charge_initializer_side {
+        protocol_fee(order.maker_amount, config.fee_bps)?
+    } else {
+        0
+    };
+    let maker_proceeds = order.taker_amount - taker_side_fee;
+    let taker_proceeds = order.maker_amount - maker_side_fee;
+    require!(
+        taker_proceeds >= expected_maker_amount,
+        ErrorCode::InitializerAmountBelowExpected
+    );
+
+    let maker_key = order.maker;
+    let delegate_bump = ctx.bumps.order_delegate;
+    let delegate_seeds = &[ORDER_DELEGATE_SEED, maker_key.as_ref(), &[delegate_bump]];
+    let signer_seeds = &[&delegate_seeds[..]];
+
+    // --- CPI 1: Taker sends Token B to the Maker ---
+    let cpi_accounts_taker_transfer = TransferChecked {
+        from: ctx.accounts.taker_deposit_token_account.to_account_info(),
+        mint: ctx.accounts.taker_mint.to_account_info(),
+        to: ctx.accounts.maker_receive_token_account.to_account_info(),
+        authority: ctx.accounts.taker.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.receive_token_program.to_account_info();
+    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts_taker_transfer)
+        .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+    transfer_checked_with_hook(cpi_ctx, maker_proceeds, ctx.accounts.taker_mint.decimals)?;
+
+    // --- CPI 1b: Taker-side protocol fee to the treasury (Token B) ---
+    if taker_side_fee > 0 {
+        let treasury_receive_token_account = ctx
+            .accounts
+            .treasury_receive_token_account
+            .as_ref()
+            .ok_or(ErrorCode::MissingTreasuryAccount)?;
+        let cpi_accounts_fee = TransferChecked {
+            from: ctx.accounts.taker_deposit_token_account.to_account_info(),
+            mint: ctx.accounts.taker_mint.to_account_info(),
+            to: treasury_receive_token_account.to_account_info(),
+            authority: ctx.accounts.taker.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.receive_token_program.to_account_info();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts_fee)
+            .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+        transfer_checked_with_hook(cpi_ctx, taker_side_fee, ctx.accounts.taker_mint.decimals)?;
+    }
+
+    // --- CPI 2: Delegate PDA moves the Maker's Token A to the Taker ---
+    let cpi_accounts_maker_transfer = TransferChecked {
+        from: ctx.accounts.maker_deposit_token_account.to_account_info(),
+        mint: ctx.accounts.maker_mint.to_account_info(),
+        to: ctx.accounts.taker_receive_token_account.to_account_info(),
+        authority: ctx.accounts.order_delegate.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.deposit_token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts_maker_transfer, signer_seeds)
+        .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+    transfer_checked_with_hook(cpi_ctx, taker_proceeds, ctx.accounts.maker_mint.decimals)?;
+
+    // --- CPI 2b: Maker-side protocol fee to the treasury (Token A) ---
+    if maker_side_fee > 0 {
+        let treasury_deposit_token_account = ctx
+            .accounts
+            .treasury_deposit_token_account
+            .as_ref()
+            .ok_or(ErrorCode::MissingTreasuryAccount)?;
+        let cpi_accounts_fee = TransferChecked {
+            from: ctx.accounts.maker_deposit_token_account.to_account_info(),
+            mint: ctx.accounts.maker_mint.to_account_info(),
+            to: treasury_deposit_token_account.to_account_info(),
+            authority: ctx.accounts.order_delegate.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.deposit_token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts_fee, signer_seeds)
+            .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+        transfer_checked_with_hook(cpi_ctx, maker_side_fee, ctx.accounts.maker_mint.decimals)?;
+    }
+    {
+        // Nothing is ever locked, so only volume and fees are recorded
+        let global_stats = &mut ctx.accounts.global_stats;
+        global_stats.total_value_released = global_stats
+            .total_value_released
+            .checked_add(order.maker_amount)
+            .ok_or(ErrorCode::Overflow)?;
+        global_stats.total_taker_side_fees = global_stats
+            .total_taker_side_fees
+            .checked_add(taker_side_fee)
+            .ok_or(ErrorCode::Overflow)?;
+        global_stats.total_initializer_side_fees = global_stats
+            .total_initializer_side_fees
+            .checked_add(maker_side_fee)
+            .ok_or(ErrorCode::Overflow)?;
+    }
+
+    emit!(SignedOrderFilled {
+        maker: maker_key,
+        taker: taker_key,
+        maker_mint: order.maker_mint,
+        taker_mint: order.taker_mint,
+        maker_amount: order.maker_amount,
+        taker_amount: order.taker_amount,
+        nonce: order.nonce,
+        timestamp: now,
+    });
+    emit!(ExchangeExecuted {
+        initializer: maker_key,
+        taker: taker_key,
+        initializer_deposit_token_mint: order.maker_mint,
+        taker_expected_token_mint: order.taker_mint,
+        initializer_amount: order.maker_amount,
+        taker_expected_amount: order.taker_amount,
+        taker_side_fee,
+        initializer_side_fee: maker_side_fee,
+        deposit_asset: AssetKind::Token,
+        receive_asset: AssetKind::Token,
+        unique_seed: order.nonce.to_le_bytes(),
+        timestamp: now,
+    });
+    Ok(())
+}
+
+pub(crate) fn cancel_signed_order(ctx: Context<CancelSignedOrder>, nonce: u64) -> Result<()> {
+    let order_nonce = &mut ctx.accounts.order_nonce;
+    require!(!order_nonce.filled, ErrorCode::OrderAlreadyFilled);
+    order_nonce.maker = ctx.accounts.maker.key();
+    order_nonce.nonce = nonce;
+    order_nonce.canceled = true;
+    order_nonce.bump = ctx.bumps.order_nonce;
+
+    emit!(SignedOrderCanceled {
+        maker: order_nonce.maker,
+        nonce,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
+
+/// Accounts for the `fill_signed_order` instruction
+#[derive(Accounts)]
+#[instruction(order: SignedOrder)]
+pub struct FillSignedOrder<'info> {
+    /// Pays for the nonce account
+    #[account(mut)]
+    pub taker: Signer<'info>,
+
+    /// Maker's Token A account, with `order_delegate` approved for the order amount
+    #[account(
+        mut,
+        token::mint = maker_mint,
+        token::authority = order.maker,
+        token::token_program = deposit_token_program
+    )]
+    pub maker_deposit_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Maker's account receiving Token B
+    #[account(
+        mut,
+        token::mint = taker_mint,
+        token::authority = order.maker,
+        token::token_program = receive_token_program
+    )]
+    pub maker_receive_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Taker's account holding Token B
+    #[account(
+        mut,
+        token::mint = taker_mint,
+        token::authority = taker,
+        token::token_program = receive_token_program
+    )]
+    pub taker_deposit_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Taker's account receiving Token A
+    #[account(
+        mut,
+        token::mint = maker_mint,
+        token::authority = taker,
+        token::token_program = deposit_token_program
+    )]
+    pub taker_receive_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: PDA the maker approved as delegate; signs for Token A, holds no data
+    #[account(
+        seeds = [ORDER_DELEGATE_SEED, order.maker.as_ref()],
+        bump
+    )]
+    pub order_delegate: UncheckedAccount<'info>,
+
+    /// Spent-nonce record for this order, created on its fill
+    #[account(
+        init_if_needed,
+        payer = taker,
+        space = ORDER_NONCE_ACCOUNT_SPACE,
+        seeds = [ORDER_NONCE_SEED, order.maker.as_ref(), order.nonce.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub order_nonce: Account<'info, OrderNonce>,
+
+    #[account(
+        address = order.maker_mint @ErrorCode::InvalidMint,
+        mint::token_program = deposit_token_program
+    )]
+    pub maker_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        address = order.taker_mint @ErrorCode::InvalidMint,
+        mint::token_program = receive_token_program
+    )]
+    pub taker_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"global-stats"],
+        bump = global_stats.bump
+    )]
+    pub global_stats: Account<'info, GlobalStats>,
+
+    /// Protocol fee settings and pause flag
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Treasury's Token A account, required when the maker side is charged
+    #[account(
+        mut,
+        token::mint = maker_mint,
+        token::authority = config.treasury,
+        token::token_program = deposit_token_program
+    )]
+    pub treasury_deposit_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Treasury's Token B account, required when the taker side is charged
+    #[account(
+        mut,
+        token::mint = taker_mint,
+        token::authority = config.treasury,
+        token::token_program = receive_token_program
+    )]
+    pub treasury_receive_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token program owning Token A
+    pub deposit_token_program: Interface<'info, TokenInterface>,
+
+    /// Token program owning Token B
+    pub receive_token_program: Interface<'info, TokenInterface>,
+
+    /// CHECK: The instructions sysvar, read for the maker's Ed25519 signature check
+    #[account(address = INSTRUCTIONS_SYSVAR_ID)]
+    pub instructions: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Accounts for the `cancel_signed_order` instruction
+#[derive(Accounts)]
+#[instruction(nonce: u64)]
+pub struct CancelSignedOrder<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = maker,
+        space = ORDER_NONCE_ACCOUNT_SPACE,
+        seeds = [ORDER_NONCE_SEED, maker.key().as_ref(), nonce.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub order_nonce: Account<'info, OrderNonce>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,2099 @@
+use crate::*;
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn initialize<'info>(
+    ctx: Context<'_, '_, '_, 'info, Initialize<'info>>,
+    initializer_amount: u64,
+    taker_expected_amount: u64,
+    duration_in_seconds: i64,
+    unique_seed: [u8; 8],
+    allowed_taker: Option<Pubkey>,
+    taker_pays_transfer_fee: bool,
+    deposit_asset: AssetKind,
+    receive_asset: AssetKind,
+    dutch_auction: Option<DutchAuctionTerms>,
+    collateral: Option<CollateralTerms>,
+    nft: Option<NftTerms>,
+    allowlist_root: Option<[u8; 32]>,
+) -> Result<()> {
+    require!(!ctx.accounts.config.paused, ErrorCode::ProgramPaused);
+    check_token_program_id(ctx.accounts.deposit_token_program.key)?;
+    check_token_program_id(ctx.accounts.receive_token_program.key)?;
+    check_asset_kind(
+        deposit_asset,
+        &ctx.accounts.initializer_deposit_token_mint.key(),
+        ctx.accounts.deposit_token_program.key,
+    )?;
+    check_asset_kind(
+        receive_asset,
+        &ctx.accounts.taker_expected_token_mint.key(),
+        ctx.accounts.receive_token_program.key,
+    )?;
+    require!(
+        initializer_amount > 0 && taker_expected_amount > 0,
+        ErrorCode::InvalidAmount
+    );
+    // 1. Set the Escrow State data
+    let escrow_account = &mut ctx.accounts.escrow_state;
+    escrow_account.initializer_key = *ctx.accounts.initializer.key;
+   let clock = Clock::get()?.unix_timestamp;
+
+    check_duration(&ctx.accounts.config, duration_in_seconds)?;
+    let expires_at = clock
+        .checked_add(duration_in_seconds) 
+        .ok_or(ErrorCode::Overflow)?;
+    escrow_account.expires_at = expires_at; 
+
+    // The ask starts at `taker_expected_amount` and decays to the floor by `expires_at`
+    if let Some(terms) = dutch_auction {
+        require!(
+            terms.floor_price > 0 && terms.floor_price < taker_expected_amount,
+            ErrorCode::InvalidAuctionTerms
+        );
+        require!(
+            terms.step_seconds >= 0 && terms.step_seconds < duration_in_seconds,
+            ErrorCode::InvalidAuctionTerms
+        );
+    }
+    escrow_account.dutch_auction = dutch_auction.map(|terms| DutchAuction {
+        floor_price: terms.floor_price,
+        start_time: clock,
+        step_seconds: terms.step_seconds,
+    });
+
+    // Two-sided escrows lock Token B at `accept` and swap later, so both legs must be
+    // plain tokens at a fixed price; an optional settlement time must fall before expiry
+    if let Some(terms) = collateral {
+        require!(
+            deposit_asset == AssetKind::Token
+                && receive_asset == AssetKind::Token
+                && dutch_auction.is_none(),
+            ErrorCode::InvalidCollateralTerms
+        );
+        if let Some(settle_at) = terms.settle_at {
+            require!(
+                settle_at > clock && settle_at <= expires_at,
+                ErrorCode::InvalidCollateralTerms
+            );
+        }
+    }
+    // NFT mode: the offered mint must be a genuine NFT, and a collection bid asks for
+    // one NFT from the Token-2022 group stored as `taker_expected_token_mint`
+    let nft = nft.unwrap_or_default();
+    if nft.offered_nft {
+        require!(
+            deposit_asset == AssetKind::Token && initializer_amount == 1,
+            ErrorCode::InvalidNftTerms
+        );
+        check_nft_mint(&ctx.accounts.initializer_deposit_token_mint)?;
+    }
+    if nft.requested_collection {
+        require!(
+            receive_asset == AssetKind::Token
+                && taker_expected_amount == 1
+                && dutch_auction.is_none()
+                && collateral.is_none(),
+            ErrorCode::InvalidNftTerms
+        );
+        require!(
+            is_token_group(&ctx.accounts.taker_expected_token_mint.to_account_info())?,
+            ErrorCode::NotACollection
+        );
+    }
+    escrow_account.collection_bid = nft.requested_collection;
+
+    // Allowlisted escrows are gated in `exchange` only, so they cannot also be private,
+    // two-sided or collection bids
+    if allowlist_root.is_some() {
+        require!(
+            allowed_taker.is_none() && collateral.is_none() && !nft.requested_collection,
+            ErrorCode::InvalidAllowlist
+        );
+    }
+    escrow_account.allowlist_root = allowlist_root;
+    escrow_account.status = EscrowStatus::Open;
+    escrow_account.collateral = collateral.map(|terms| Collateral {
+        settle_at: terms.settle_at,
+        taker: Pubkey::default(),
+        taker_deposit_token_account: Pubkey::default(),
+        taker_amount: 0,
+        initializer_confirmed: false,
+        taker_confirmed: false,
+    });
+    escrow_account.initializer_deposit_token_mint = ctx
+        .accounts
+        .initializer_deposit_token_mint
+        .key();
+    // Native SOL legs move lamports through the Initializer's wallet, so their token
+    // accounts are omitted and recorded as the default key
+    let initializer_deposit_token_account = match deposit_asset {
+        AssetKind::Token => ctx
+            .accounts
+            .initializer_deposit_token_account
+            .as_ref()
+            .ok_or(ErrorCode::MissingTokenAccount)?
+            .key(),
+        AssetKind::NativeSol => Pubkey::default(),
+    };
+    let initializer_receive_token_account = match receive_asset {
+        AssetKind::Token => ctx
+            .accounts
+            .initializer_receive_token_account
+            .as_ref()
+            .ok_or(ErrorCode::MissingTokenAccount)?
+            .key(),
+        AssetKind::NativeSol => Pubkey::default(),
+    };
+    escrow_account.initializer_deposit_token_account = initializer_deposit_token_account;
+    escrow_account.taker_expected_token_mint =
+        ctx.accounts.taker_expected_token_mint.key();
+    escrow_account.taker_expected_amount = taker_expected_amount;
+    escrow_account.filled_amount = 0;
+    escrow_account.initializer_receive_token_account = initializer_receive_token_account;
+    escrow_account.unique_seed = unique_seed;
+    escrow_account.allowed_taker = allowed_taker;
+    escrow_account.deposit_token_program = ctx.accounts.deposit_token_program.key();
+    escrow_account.receive_token_program = ctx.accounts.receive_token_program.key();
+    escrow_account.taker_pays_transfer_fee = taker_pays_transfer_fee;
+    escrow_account.deposit_asset = deposit_asset;
+    escrow_account.receive_asset = receive_asset;
+    escrow_account.bump = ctx.bumps.escrow_state;
+
+    let deposited_amount = if deposit_asset == AssetKind::NativeSol {
+        // SOL is wrapped straight into the vault, no wSOL account needed up front
+        wrap_lamports(
+            ctx.accounts.system_program.to_account_info(),
+            ctx.accounts.deposit_token_program.to_account_info(),
+            ctx.accounts.initializer.to_account_info(),
+            ctx.accounts.vault_account.to_account_info(),
+            initializer_amount,
+        )?;
+        initializer_amount
+    } else {
+        let initializer_deposit_token_account = ctx
+            .accounts
+            .initializer_deposit_token_account
+            .as_ref()
+            .ok_or(ErrorCode::MissingTokenAccount)?;
+        let cpi_accounts = TransferChecked {
+            from: initializer_deposit_token_account.to_account_info(),
+            mint: ctx.accounts.initializer_deposit_token_mint.to_account_info(),
+            to: ctx.accounts.vault_account.to_account_info(),
+            authority: ctx.accounts.initializer.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.deposit_token_program.to_account_info();
+        let cpi_context = CpiContext::new(cpi_program, cpi_accounts)
+            .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+        deposit_to_vault(
+            cpi_context,
+            initializer_amount,
+            ctx.accounts.initializer_deposit_token_mint.decimals,
+        )?
+    };
+    let deposit_transfer_fee = initializer_amount
+        .checked_sub(deposited_amount)
+        .ok_or(ErrorCode::Overflow)?;
+    require!(deposited_amount > 0, ErrorCode::InvalidAmount);
+    let escrow_account = &mut ctx.accounts.escrow_state;
+    escrow_account.initializer_amount = deposited_amount;
+    escrow_account.remaining_amount = deposited_amount;
+
+    let global_stats = &mut ctx.accounts.global_stats;
+        global_stats.total_escrows_created = global_stats.total_escrows_created.checked_add(1).unwrap();
+        global_stats.total_value_locked = global_stats
+            .total_value_locked
+            .checked_add(deposited_amount)
+            .unwrap();
+
+    let deposit_mint_stats = &mut ctx.accounts.deposit_mint_stats;
+    deposit_mint_stats.init_if_needed(
+        ctx.accounts.initializer_deposit_token_mint.key(),
+        ctx.bumps.deposit_mint_stats,
+    );
+    deposit_mint_stats.record_lock(deposited_amount)?;
+
+    let receive_mint_stats = &mut ctx.accounts.receive_mint_stats;
+    receive_mint_stats.init_if_needed(
+        ctx.accounts.taker_expected_token_mint.key(),
+        ctx.bumps.receive_mint_stats,
+    );
+
+// Optional: Track unique active users
+// global_stats.last_active_user = ctx.accounts.initializer.key();
+   emit!(InitializeEvent {
+    initializer_key: ctx.accounts.initializer.key().to_string(),
+    initializer_deposit_token_account: initializer_deposit_token_account.to_string(),
+    initializer_deposit_token_mint: ctx.accounts.initializer_deposit_token_mint.key().to_string(),
+    taker_expected_token_mint: ctx.accounts.taker_expected_token_mint.key().to_string(),
+    initializer_amount: deposited_amount.to_string(),
+    taker_expected_amount: taker_expected_amount.to_string(),
+    initializer_receive_token_account: initializer_receive_token_account.to_string(),
+    unique_seed,
+    expires_at,
+    bump: ctx.bumps.escrow_state,
+    escrow_pda: ctx.accounts.escrow_state.key().to_string(),
+    vault_account: ctx.accounts.vault_account.key().to_string(),
+    allowed_taker: allowed_taker.map(|taker| taker.to_string()),
+    deposit_token_program: ctx.accounts.deposit_token_program.key().to_string(),
+    receive_token_program: ctx.accounts.receive_token_program.key().to_string(),
+    deposit_transfer_fee: deposit_transfer_fee.to_string(),
+    taker_pays_transfer_fee,
+    deposit_asset: deposit_asset.as_str().to_string(),
+    receive_asset: receive_asset.as_str().to_string(),
+    floor_price: dutch_auction.map(|terms| terms.floor_price.to_string()),
+    price_step_seconds: dutch_auction.map(|terms| terms.step_seconds.to_string()),
+    collateralized: collateral.is_some(),
+    settle_at: collateral
+        .and_then(|terms| terms.settle_at)
+        .map(|settle_at| settle_at.to_string()),
+    offered_nft: nft.offered_nft,
+    collection_bid: nft.requested_collection,
+    allowlist_root,
+});
+
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn exchange<'info>(
+    ctx: Context<'_, '_, '_, 'info, Exchange<'info>>,
+    fill_amount: u64,
+    expected_initializer_amount: u64,
+    max_taker_amount: u64,
+    expected_initializer_mint: Pubkey,
+    expected_taker_mint: Pubkey,
+    allowlist_proof: Vec<[u8; 32]>,
+    wallet_cap: Option<u64>,
+) -> Result<()> {
+    require!(!ctx.accounts.config.paused, ErrorCode::ProgramPaused);
+    check_token_program_id(&ctx.accounts.deposit_token_program.key())?;
+    check_token_program_id(&ctx.accounts.receive_token_program.key())?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let escrow_state = &ctx.accounts.escrow_state;
+    require!(now <= escrow_state.expires_at, ErrorCode::EscrowExpired);
+    require!(
+        escrow_state.collateral.is_none(),
+        ErrorCode::CollateralizedEscrow
+    );
+    require!(!escrow_state.collection_bid, ErrorCode::CollectionBid);
+    require_keys_eq!(
+        escrow_state.initializer_deposit_token_mint,
+        expected_initializer_mint,
+        ErrorCode::UnexpectedInitializerMint
+    );
+    require_keys_eq!(
+        escrow_state.taker_expected_token_mint,
+        expected_taker_mint,
+        ErrorCode::UnexpectedTakerMint
+    );
+
+    let taker_expected_amount = current_ask(escrow_state, now)?;
+    let taker_remaining = taker_expected_amount
+        .checked_sub(escrow_state.filled_amount)
+        .ok_or(ErrorCode::Overflow)?;
+    require!(
+        fill_amount > 0 && fill_amount <= taker_remaining,
+        ErrorCode::InvalidFillAmount
+    );
+    // The ask moves between blocks, so an auction has no stable price for partial fills
+    if escrow_state.dutch_auction.is_some() {
+        require!(fill_amount == taker_remaining, ErrorCode::AuctionRequiresFullFill);
+    }
+
+    if let Some(root) = escrow_state.allowlist_root {
+        let taker_key = ctx.accounts.taker.key();
+        require!(
+            verify_allowlist_proof(
+                &allowlist_proof,
+                root,
+                allowlist_leaf(&taker_key, wallet_cap)
+            ),
+            ErrorCode::NotAllowlisted
+        );
+        if let Some(cap) = wallet_cap {
+            let escrow_key = ctx.accounts.escrow_state.key();
+            let allowlist_fill = ctx
+                .accounts
+                .allowlist_fill
+                .as_mut()
+                .ok_or(ErrorCode::MissingAllowlistFill)?;
+            if allowlist_fill.escrow_state == Pubkey::default() {
+                allowlist_fill.escrow_state = escrow_key;
+                allowlist_fill.taker = taker_key;
+                allowlist_fill.bump = ctx
+                    .bumps
+                    .allowlist_fill
+                    .ok_or(ErrorCode::MissingAllowlistFill)?;
+            }
+            allowlist_fill.filled_amount = allowlist_fill
+                .filled_amount
+                .checked_add(fill_amount)
+                .ok_or(ErrorCode::Overflow)?;
+            require!(
+                allowlist_fill.filled_amount <= cap,
+                ErrorCode::WalletCapExceeded
+            );
+        }
+    }
+
+    // Rounded down, so the taker never receives more than their share
+    let release_amount = pro_rata_release(
+        fill_amount,
+        escrow_state.initializer_amount,
+        taker_expected_amount,
+    )?;
+    require!(release_amount > 0, ErrorCode::FillTooSmall);
+    require!(
+        release_amount <= escrow_state.remaining_amount,
+        ErrorCode::InsufficientFunds
+    );
+
+    // Protocol fees skimmed from each leg into the treasury, per `Config`
+    let config = &ctx.accounts.config;
+    let taker_side_fee = if config.charge_taker_side {
+        protocol_fee(fill_amount, config.fee_bps)?
+    } else {
+        0
+    };
+    let initializer_side_fee = if config.charge_initializer_side {
+        protocol_fee(release_amount, config.fee_bps)?
+    } else {
+        0
+    };
+    let initializer_proceeds = fill_amount - taker_side_fee;
+    let taker_proceeds = release_amount - initializer_side_fee;
+
+    // With `taker_pays_transfer_fee`, the taker grosses up so the initializer nets its proceeds
+    let taker_expected_mint_info = ctx.accounts.taker_expected_mint.to_account_info();
+    let taker_payment = if escrow_state.taker_pays_transfer_fee {
+        gross_up_for_fee(&taker_expected_mint_info, initializer_proceeds)?
+    } else {
+        initializer_proceeds
+    };
+    let taker_transfer_fee = transfer_fee(&taker_expected_mint_info, taker_payment)?;
+    let taker_total_payment = taker_payment
+        .checked_add(taker_side_fee)
+        .ok_or(ErrorCode::Overflow)?;
+    require!(
+        taker_proceeds >= expected_initializer_amount,
+        ErrorCode::InitializerAmountBelowExpected
+    );
+    require!(
+        taker_total_payment <= max_taker_amount,
+        ErrorCode::TakerAmountAboveMax
+    );
+    let release_transfer_fee = transfer_fee(
+        &ctx.accounts.initializer_deposit_mint.to_account_info(),
+        taker_proceeds,
+    )?;
+
+    let is_final_fill = fill_amount == taker_remaining;
+    let deposit_asset = escrow_state.deposit_asset;
+    let receive_asset = escrow_state.receive_asset;
+    let initializer_key = escrow_state.initializer_key;
+    let unique_seed = escrow_state.unique_seed;
+    let bump = escrow_state.bump;
+    let authority_seeds = &[
+        ESCROW_PDA_SEED,
+        initializer_key.as_ref(),
+        unique_seed.as_ref(),
+        &[bump],
+    ];
+    let signer_seeds = &[&authority_seeds[..]];
+
+    // --- CPI 1: Taker sends Token B to Initializer ---
+    if receive_asset == AssetKind::NativeSol {
+        // Native SOL goes straight to the Initializer's wallet
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: ctx.accounts.taker.to_account_info(),
+                    to: ctx.accounts.initializer_key.to_account_info(),
+                },
+            ),
+            taker_payment,
+        )?;
+    } else {
+        let taker_deposit_token_account = ctx
+            .accounts
+            .taker_deposit_token_account
+            .as_ref()
+            .ok_or(ErrorCode::MissingTokenAccount)?;
+        let initializer_receive_token_account = ctx
+            .accounts
+            .initializer_receive_token_account
+            .as_ref()
+            .ok_or(ErrorCode::MissingTokenAccount)?;
+        let cpi_accounts_taker_transfer = TransferChecked {
+            from: taker_deposit_token_account.to_account_info(),
+            mint: ctx.accounts.taker_expected_mint.to_account_info(),
+            to: initializer_receive_token_account.to_account_info(),
+            authority: ctx.accounts.taker.to_account_info(),
+        };
+
+        let cpi_program = ctx.accounts.receive_token_program.to_account_info();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts_taker_transfer)
+            .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+        transfer_checked_with_hook(
+            cpi_ctx,
+            taker_payment,
+            ctx.accounts.taker_expected_mint.decimals,
+        )?;
+    }
+
+    // --- CPI 1b: Taker-side protocol fee to the treasury (Token B) ---
+    if taker_side_fee > 0 {
+        let treasury_receive_token_account = ctx
+            .accounts
+            .treasury_receive_token_account
+            .as_ref()
+            .ok_or(ErrorCode::MissingTreasuryAccount)?;
+        if receive_asset == AssetKind::NativeSol {
+            // The treasury keeps protocol fees as wSOL
+            wrap_lamports(
+                ctx.accounts.system_program.to_account_info(),
+                ctx.accounts.receive_token_program.to_account_info(),
+                ctx.accounts.taker.to_account_info(),
+                treasury_receive_token_account.to_account_info(),
+                taker_side_fee,
+            )?;
+        } else {
+            let taker_deposit_token_account = ctx
+                .accounts
+                .taker_deposit_token_account
+                .as_ref()
+                .ok_or(ErrorCode::MissingTokenAccount)?;
+            let cpi_accounts_fee = TransferChecked {
+                from: taker_deposit_token_account.to_account_info(),
+                mint: ctx.accounts.taker_expected_mint.to_account_info(),
+                to: treasury_receive_token_account.to_account_info(),
+                authority: ctx.accounts.taker.to_account_info(),
+            };
+
+            let cpi_program = ctx.accounts.receive_token_program.to_account_info();
+            let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts_fee)
+                .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+            transfer_checked_with_hook(
+                cpi_ctx,
+                taker_side_fee,
+                ctx.accounts.taker_expected_mint.decimals,
+            )?;
+        }
+    }
+
+    // --- CPI 2: Escrow PDA sends the released slice of Token A to Taker ---
+    if deposit_asset == AssetKind::NativeSol {
+        // Unwrapped through a temporary account, so the Taker gets lamports
+        let unwrap_account = ctx
+            .accounts
+            .unwrap_account
+            .as_ref()
+            .ok_or(ErrorCode::MissingTokenAccount)?;
+        unwrap_from_vault(
+            ctx.accounts.deposit_token_program.to_account_info(),
+            &ctx.accounts.initializer_deposit_mint,
+            ctx.accounts.vault_account.to_account_info(),
+            unwrap_account.to_account_info(),
+            ctx.accounts.escrow_state.to_account_info(),
+            signer_seeds,
+            ctx.accounts.taker.to_account_info(),
+            taker_proceeds,
+        )?;
+    } else {
+        let taker_receive_token_account = ctx
+            .accounts
+            .taker_receive_token_account
+            .as_ref()
+            .ok_or(ErrorCode::MissingTokenAccount)?;
+        let cpi_accounts_initializer_transfer = TransferChecked {
+            from: ctx.accounts.vault_account.to_account_info(),
+            mint: ctx.accounts.initializer_deposit_mint.to_account_info(),
+            to: taker_receive_token_account.to_account_info(),
+            authority: ctx.accounts.escrow_state.to_account_info(),
+        };
+
+        let cpi_program = ctx.accounts.deposit_token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts_initializer_transfer, signer_seeds)
+            .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+        transfer_checked_with_hook(
+            cpi_ctx,
+            taker_proceeds,
+            ctx.accounts.initializer_deposit_mint.decimals,
+        )?;
+    }
+
+    // --- CPI 2b: Initializer-side protocol fee to the treasury (Token A) ---
+    if initializer_side_fee > 0 {
+        let treasury_deposit_token_account = ctx
+            .accounts
+            .treasury_deposit_token_account
+            .as_ref()
+            .ok_or(ErrorCode::MissingTreasuryAccount)?;
+        let cpi_accounts_fee = TransferChecked {
+            from: ctx.accounts.vault_account.to_account_info(),
+            mint: ctx.accounts.initializer_deposit_mint.to_account_info(),
+            to: treasury_deposit_token_account.to_account_info(),
+            authority: ctx.accounts.escrow_state.to_account_info(),
+        };
+
+        let cpi_program = ctx.accounts.deposit_token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts_fee, signer_seeds)
+            .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+        transfer_checked_with_hook(
+            cpi_ctx,
+            initializer_side_fee,
+            ctx.accounts.initializer_deposit_mint.decimals,
+        )?;
+    }
+
+    let escrow_state = &mut ctx.accounts.escrow_state;
+    escrow_state.filled_amount = escrow_state
+        .filled_amount
+        .checked_add(fill_amount)
+        .ok_or(ErrorCode::Overflow)?;
+    escrow_state.remaining_amount -= release_amount;
+    let remaining_amount = escrow_state.remaining_amount;
+    let filled_amount = escrow_state.filled_amount;
+
+    if is_final_fill {
+        // --- CPI 3: Return rounding dust to the Initializer ---
+        // Native SOL dust leaves with the vault's lamports when it is closed below
+        if remaining_amount > 0 && deposit_asset == AssetKind::Token {
+            let initializer_deposit_token_account = ctx
+                .accounts
+                .initializer_deposit_token_account
+                .as_ref()
+                .ok_or(ErrorCode::MissingTokenAccount)?;
+            let cpi_accounts_dust = TransferChecked {
+                from: ctx.accounts.vault_account.to_account_info(),
+                mint: ctx.accounts.initializer_deposit_mint.to_account_info(),
+                to: initializer_deposit_token_account.to_account_info(),
+                authority: ctx.accounts.escrow_state.to_account_info(),
+            };
+
+            let cpi_program = ctx.accounts.deposit_token_program.to_account_info();
+            let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts_dust, signer_seeds)
+                .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+            transfer_checked_with_hook(
+                cpi_ctx,
+                remaining_amount,
+                ctx.accounts.initializer_deposit_mint.decimals,
+            )?;
+        }
+
+        // --- CPI 4: Close Vault ---
+        // A native vault still holds the dust as lamports, which belong to the Initializer
+        let vault_rent_destination = if deposit_asset == AssetKind::NativeSol {
+            ctx.accounts.initializer_key.to_account_info()
+        } else {
+            ctx.accounts.taker.to_account_info()
+        };
+        let cpi_accounts_close = CloseAccount {
+            account: ctx.accounts.vault_account.to_account_info(),
+            destination: vault_rent_destination,
+            authority: ctx.accounts.escrow_state.to_account_info(),
+        };
+
+        let cpi_program = ctx.accounts.deposit_token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts_close, signer_seeds);
+
+        token_interface::close_account(cpi_ctx)?;
+
+        ctx.accounts
+            .escrow_state
+            .close(ctx.accounts.taker.to_account_info())?;
+    }
+    {
+        let global_stats = &mut ctx.accounts.global_stats;
+
+        // Add to total value released
+        global_stats.total_value_released = global_stats
+            .total_value_released
+            .checked_add(release_amount)
+            .unwrap();
+
+        // Subtract from TVL (since escrow funds are released)
+        global_stats.total_value_locked = global_stats
+            .total_value_locked
+            .saturating_sub(release_amount);
+
+        global_stats.total_taker_side_fees = global_stats
+            .total_taker_side_fees
+            .checked_add(taker_side_fee)
+            .unwrap();
+        global_stats.total_initializer_side_fees = global_stats
+            .total_initializer_side_fees
+            .checked_add(initializer_side_fee)
+            .unwrap();
+
+        if is_final_fill {
+            // Increment total completed escrows
+            global_stats.total_escrows_completed = global_stats
+                .total_escrows_completed
+                .checked_add(1)
+                .unwrap();
+
+            // Dust returned to the Initializer also leaves TVL
+            global_stats.total_value_locked = global_stats
+                .total_value_locked
+                .saturating_sub(remaining_amount);
+        }
+    }
+    {
+        let deposit_mint_stats = &mut ctx.accounts.deposit_mint_stats;
+        deposit_mint_stats.record_release(release_amount)?;
+        deposit_mint_stats.record_fills(1, release_amount, initializer_side_fee)?;
+        if is_final_fill {
+            // Rounding dust goes back to the Initializer
+            deposit_mint_stats.record_refund(remaining_amount)?;
+        }
+
+        let receive_mint_stats = &mut ctx.accounts.receive_mint_stats;
+        receive_mint_stats.record_fills(1, fill_amount, taker_side_fee)?;
+    }
+    // --- Emit Events ---
+    let escrow_state = &ctx.accounts.escrow_state;
+    emit!(PartialFill {
+        initializer: initializer_key,
+        taker: ctx.accounts.taker.key(),
+        initializer_deposit_token_mint: escrow_state.initializer_deposit_token_mint,
+        taker_expected_token_mint: escrow_state.taker_expected_token_mint,
+        fill_amount,
+        release_amount,
+        filled_amount,
+        remaining_amount: if is_final_fill { 0 } else { remaining_amount },
+        taker_transfer_fee,
+        release_transfer_fee,
+        unique_seed,
+        timestamp: now,
+    });
+
+    // One ExchangeExecuted per fill, with the amounts and protocol fees of that fill
+    emit!(ExchangeExecuted {
+        initializer: initializer_key,
+        taker: ctx.accounts.taker.key(),
+        initializer_deposit_token_mint: escrow_state.initializer_deposit_token_mint,
+        taker_expected_token_mint: escrow_state.taker_expected_token_mint,
+        initializer_amount: release_amount,
+        taker_expected_amount: fill_amount,
+        taker_side_fee,
+        initializer_side_fee,
+        deposit_asset,
+        receive_asset,
+        unique_seed,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn exchange_many<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ExchangeMany<'info>>,
+    fill_amounts: Vec<u64>,
+    expected_total_initializer_amount: u64,
+    max_total_taker_amount: u64,
+) -> Result<()> {
+    require!(!ctx.accounts.config.paused, ErrorCode::ProgramPaused);
+    check_token_program_id(&ctx.accounts.deposit_token_program.key())?;
+    check_token_program_id(&ctx.accounts.receive_token_program.key())?;
+    let triples_end = fill_amounts.len() * 3;
+    require!(
+        !fill_amounts.is_empty()
+            && fill_amounts.len() <= u8::MAX as usize
+            && ctx.remaining_accounts.len() >= triples_end,
+        ErrorCode::InvalidBatch
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let taker_key = ctx.accounts.taker.key();
+    let deposit_mint_key = ctx.accounts.initializer_deposit_mint.key();
+    let receive_mint_key = ctx.accounts.taker_expected_mint.key();
+    let deposit_decimals = ctx.accounts.initializer_deposit_mint.decimals;
+    let receive_decimals = ctx.accounts.taker_expected_mint.decimals;
+    let deposit_mint_info = ctx.accounts.initializer_deposit_mint.to_account_info();
+    let receive_mint_info = ctx.accounts.taker_expected_mint.to_account_info();
+    let config = &ctx.accounts.config;
+
+    let mut total_initializer_amount: u64 = 0;
+    let mut total_taker_amount: u64 = 0;
+    let mut total_released: u64 = 0;
+    let mut total_filled: u64 = 0;
+    let mut total_dust: u64 = 0;
+    let mut total_taker_side_fees: u64 = 0;
+    let mut total_initializer_side_fees: u64 = 0;
+    let mut completed: u64 = 0;
+
+    let triples = ctx.remaining_accounts[..triples_end].chunks(3);
+    for (triple, &fill_amount) in triples.zip(fill_amounts.iter()) {
+        let [escrow_info, vault, initializer_receive_token_account] = triple else {
+            return err!(ErrorCode::InvalidBatch);
+        };
+        let mut escrow_state = Account::<EscrowState>::try_from(escrow_info)?;
+
+        // The same checks `Exchange` makes through its account constraints
+        let initializer_key = escrow_state.initializer_key;
+        let unique_seed = escrow_state.unique_seed;
+        let bump = escrow_state.bump;
+        let authority_seeds = &[
+            ESCROW_PDA_SEED,
+            initializer_key.as_ref(),
+            unique_seed.as_ref(),
+            &[bump],
+        ];
+        let signer_seeds = &[&authority_seeds[..]];
+        let escrow_key = Pubkey::create_program_address(authority_seeds, &crate::ID)
+            .map_err(|_| error!(ErrorCode::InvalidAccount))?;
+        require_keys_eq!(escrow_key, escrow_info.key(), ErrorCode::InvalidAccount);
+        require!(
+            escrow_state.allowed_taker.is_none_or(|allowed| allowed == taker_key),
+            ErrorCode::UnauthorizedTaker
+        );
+        require_keys_eq!(
+            initializer_receive_token_account.key(),
+            escrow_state.initializer_receive_token_account,
+            ErrorCode::InvalidAccount
+        );
+        require_keys_eq!(
+            escrow_state.initializer_deposit_token_mint,
+            deposit_mint_key,
+            ErrorCode::UnexpectedInitializerMint
+        );
+        require_keys_eq!(
+            escrow_state.taker_expected_token_mint,
+            receive_mint_key,
+            ErrorCode::UnexpectedTakerMint
+        );
+        require_keys_eq!(
+            escrow_state.deposit_token_program,
+            ctx.accounts.deposit_token_program.key(),
+            ErrorCode::InvalidTokenProgram
+        );
+        require_keys_eq!(
+            escrow_state.receive_token_program,
+            ctx.accounts.receive_token_program.key(),
+            ErrorCode::InvalidTokenProgram
+        );
+        let (vault_key, _) =
+            Pubkey::find_program_address(&[b"vault", escrow_key.as_ref()], &crate::ID);
+        require_keys_eq!(vault.key(), vault_key, ErrorCode::InvalidAccount);
+        let vault_account = read_token_account(vault, &escrow_state.deposit_token_program)?;
+        require_keys_eq!(vault_account.owner, escrow_key, ErrorCode::InvalidOwner);
+
+        // ...and the ones its handler makes
+        require!(now <= escrow_state.expires_at, ErrorCode::EscrowExpired);
+        require!(
+            escrow_state.collateral.is_none(),
+            ErrorCode::CollateralizedEscrow
+        );
+        require!(!escrow_state.collection_bid, ErrorCode::CollectionBid);
+        require!(
+            escrow_state.allowlist_root.is_none(),
+            ErrorCode::AllowlistedEscrow
+        );
+        require!(
+            escrow_state.deposit_asset == AssetKind::Token
+                && escrow_state.receive_asset == AssetKind::Token,
+            ErrorCode::InvalidBatch
+        );
+
+        let taker_expected_amount = current_ask(&escrow_state, now)?;
+        let taker_remaining = taker_expected_amount
+            .checked_sub(escrow_state.filled_amount)
+            .ok_or(ErrorCode::Overflow)?;
+        require!(
+            fill_amount > 0 && fill_amount <= taker_remaining,
+            ErrorCode::InvalidFillAmount
+        );
+        if escrow_state.dutch_auction.is_some() {
+            require!(fill_amount == taker_remaining, ErrorCode::AuctionRequiresFullFill);
+        }
+
+        let release_amount = pro_rata_release(
+            fill_amount,
+            escrow_state.initializer_amount,
+            taker_expected_amount,
+        )?;
+        require!(release_amount > 0, ErrorCode::FillTooSmall);
+        require!(
+            release_amount <= escrow_state.remaining_amount,
+            ErrorCode::InsufficientFunds
+        );
+
+        // Protocol fees skimmed from each leg into the treasury, per `Config`
+        let taker_side_fee = if config.charge_taker_side {
+            protocol_fee(fill_amount, config.fee_bps)?
+        } else {
+            0
+        };
+        let initializer_side_fee = if config.charge_initializer_side {
+            protocol_fee(release_amount, config.fee_bps)?
+        } else {
+            0
+        };
+        let initializer_proceeds = fill_amount - taker_side_fee;
+        let taker_proceeds = release_amount - initializer_side_fee;
+        let taker_payment = if escrow_state.taker_pays_transfer_fee {
+            gross_up_for_fee(&receive_mint_info, initializer_proceeds)?
+        } else {
+            initializer_proceeds
+        };
+        let taker_transfer_fee = transfer_fee(&receive_mint_info, taker_payment)?;
+        let release_transfer_fee = transfer_fee(&deposit_mint_info, taker_proceeds)?;
+
+        // --- CPI 1: Taker sends Token B to Initializer ---
+        let cpi_accounts_taker_transfer = TransferChecked {
+            from: ctx.accounts.taker_deposit_token_account.to_account_info(),
+            mint: receive_mint_info.clone(),
+            to: initializer_receive_token_account.clone(),
+            authority: ctx.accounts.taker.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.receive_token_program.to_account_info();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts_taker_transfer)
+            .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+        transfer_checked_with_hook(cpi_ctx, taker_payment, receive_decimals)?;
+
+        // --- CPI 1b: Taker-side protocol fee to the treasury (Token B) ---
+        if taker_side_fee > 0 {
+            let treasury_receive_token_account = ctx
+                .accounts
+                .treasury_receive_token_account
+                .as_ref()
+                .ok_or(ErrorCode::MissingTreasuryAccount)?;
+            let cpi_accounts_fee = TransferChecked {
+                from: ctx.accounts.taker_deposit_token_account.to_account_info(),
+                mint: receive_mint_info.clone(),
+                to: treasury_receive_token_account.to_account_info(),
+                authority: ctx.accounts.taker.to_account_info(),
+            };
+            let cpi_program = ctx.accounts.receive_token_program.to_account_info();
+            let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts_fee)
+                .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+            transfer_checked_with_hook(cpi_ctx, taker_side_fee, receive_decimals)?;
+        }
+
+        // --- CPI 2: Escrow PDA sends the released slice of Token A to Taker ---
+        let cpi_accounts_release = TransferChecked {
+            from: vault.clone(),
+            mint: deposit_mint_info.clone(),
+            to: ctx.accounts.taker_receive_token_account.to_account_info(),
+            authority: escrow_info.clone(),
+        };
+        let cpi_program = ctx.accounts.deposit_token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts_release, signer_seeds)
+            .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+        transfer_checked_with_hook(cpi_ctx, taker_proceeds, deposit_decimals)?;
+
+        // --- CPI 2b: Initializer-side protocol fee to the treasury (Token A) ---
+        if initializer_side_fee > 0 {
+            let treasury_deposit_token_account = ctx
+                .accounts
+                .treasury_deposit_token_account
+                .as_ref()
+                .ok_or(ErrorCode::MissingTreasuryAccount)?;
+            let cpi_accounts_fee = TransferChecked {
+                from: vault.clone(),
+                mint: deposit_mint_info.clone(),
+                to: treasury_deposit_token_account.to_account_info(),
+                authority: escrow_info.clone(),
+            };
+            let cpi_program = ctx.accounts.deposit_token_program.to_account_info();
+            let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts_fee, signer_seeds)
+                .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+            transfer_checked_with_hook(cpi_ctx, initializer_side_fee, deposit_decimals)?;
+        }
+
+        let is_final_fill = fill_amount == taker_remaining;
+        escrow_state.filled_amount = escrow_state
+            .filled_amount
+            .checked_add(fill_amount)
+            .ok_or(ErrorCode::Overflow)?;
+        escrow_state.remaining_amount -= release_amount;
+        let remaining_amount = escrow_state.remaining_amount;
+        let filled_amount = escrow_state.filled_amount;
+
+        if is_final_fill {
+            // --- CPI 3: Return rounding dust to the Initializer ---
+            if remaining_amount > 0 {
+                let dust_destination = escrow_state.initializer_deposit_token_account;
+                let initializer_deposit_token_account = ctx.remaining_accounts[triples_end..]
+                    .iter()
+                    .find(|account| account.key() == dust_destination)
+                    .ok_or(ErrorCode::InvalidBatch)?;
+                let cpi_accounts_dust = TransferChecked {
+                    from: vault.clone(),
+                    mint: deposit_mint_info.clone(),
+                    to: initializer_deposit_token_account.clone(),
+                    authority: escrow_info.clone(),
+                };
+                let cpi_program = ctx.accounts.deposit_token_program.to_account_info();
+                let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts_dust, signer_seeds)
+                    .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+                transfer_checked_with_hook(cpi_ctx, remaining_amount, deposit_decimals)?;
+            }
+
+            // --- CPI 4: Close Vault ---
+            let cpi_accounts_close = CloseAccount {
+                account: vault.clone(),
+                destination: ctx.accounts.taker.to_account_info(),
+                authority: escrow_info.clone(),
+            };
+            let cpi_program = ctx.accounts.deposit_token_program.to_account_info();
+            let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts_close, signer_seeds);
+
+            token_interface::close_account(cpi_ctx)?;
+
+            escrow_state.close(ctx.accounts.taker.to_account_info())?;
+            completed += 1;
+            total_dust = total_dust
+                .checked_add(remaining_amount)
+                .ok_or(ErrorCode::Overflow)?;
+        } else {
+            escrow_state.exit(&crate::ID)?;
+        }
+
+        total_initializer_amount = total_initializer_amount
+            .checked_add(taker_proceeds)
+            .ok_or(ErrorCode::Overflow)?;
+        total_taker_amount = total_taker_amount
+            .checked_add(taker_payment)
+            .and_then(|total| total.checked_add(taker_side_fee))
+            .ok_or(ErrorCode::Overflow)?;
+        total_released = total_released
+            .checked_add(release_amount)
+            .ok_or(ErrorCode::Overflow)?;
+        total_filled = total_filled
+            .checked_add(fill_amount)
+            .ok_or(ErrorCode::Overflow)?;
+        total_taker_side_fees = total_taker_side_fees
+            .checked_add(taker_side_fee)
+            .ok_or(ErrorCode::Overflow)?;
+        total_initializer_side_fees = total_initializer_side_fees
+            .checked_add(initializer_side_fee)
+            .ok_or(ErrorCode::Overflow)?;
+
+        emit!(PartialFill {
+            initializer: initializer_key,
+            taker: taker_key,
+            initializer_deposit_token_mint: deposit_mint_key,
+            taker_expected_token_mint: receive_mint_key,
+            fill_amount,
+            release_amount,
+            filled_amount,
+            remaining_amount: if is_final_fill { 0 } else { remaining_amount },
+            taker_transfer_fee,
+            release_transfer_fee,
+            unique_seed,
+            timestamp: now,
+        });
+        emit!(ExchangeExecuted {
+            initializer: initializer_key,
+            taker: taker_key,
+            initializer_deposit_token_mint: deposit_mint_key,
+            taker_expected_token_mint: receive_mint_key,
+            initializer_amount: release_amount,
+            taker_expected_amount: fill_amount,
+            taker_side_fee,
+            initializer_side_fee,
+            deposit_asset: AssetKind::Token,
+            receive_asset: AssetKind::Token,
+            unique_seed,
+            timestamp: now,
+        });
+    }
+
+    // Slippage guard over the whole batch
+    require!(
+        total_initializer_amount >= expected_total_initializer_amount,
+        ErrorCode::InitializerAmountBelowExpected
+    );
+    require!(
+        total_taker_amount <= max_total_taker_amount,
+        ErrorCode::TakerAmountAboveMax
+    );
+    {
+        let global_stats = &mut ctx.accounts.global_stats;
+        global_stats.total_value_released = global_stats
+            .total_value_released
+            .checked_add(total_released)
+            .ok_or(ErrorCode::Overflow)?;
+        // Released Token A and dust returned on final fills both leave TVL
+        global_stats.total_value_locked = global_stats
+            .total_value_locked
+            .saturating_sub(total_released)
+            .saturating_sub(total_dust);
+        global_stats.total_taker_side_fees = global_stats
+            .total_taker_side_fees
+            .checked_add(total_taker_side_fees)
+            .ok_or(ErrorCode::Overflow)?;
+        global_stats.total_initializer_side_fees = global_stats
+            .total_initializer_side_fees
+            .checked_add(total_initializer_side_fees)
+            .ok_or(ErrorCode::Overflow)?;
+        global_stats.total_escrows_completed = global_stats
+            .total_escrows_completed
+            .checked_add(completed)
+            .ok_or(ErrorCode::Overflow)?;
+
+        let fill_count = fill_amounts.len() as u64;
+        let deposit_mint_stats = &mut ctx.accounts.deposit_mint_stats;
+        deposit_mint_stats.record_release(total_released)?;
+        deposit_mint_stats.record_refund(total_dust)?;
+        deposit_mint_stats.record_fills(fill_count, total_released, total_initializer_side_fees)?;
+
+        let receive_mint_stats = &mut ctx.accounts.receive_mint_stats;
+        receive_mint_stats.record_fills(fill_count, total_filled, total_taker_side_fees)?;
+    }
+
+    emit!(BatchExchangeExecuted {
+        taker: taker_key,
+        initializer_deposit_token_mint: deposit_mint_key,
+        taker_expected_token_mint: receive_mint_key,
+        fill_count: fill_amounts.len() as u8,
+        total_initializer_amount,
+        total_taker_amount,
+        timestamp: now,
+    });
+    Ok(())
+}
+
+pub(crate) fn cancel<'info>(ctx: Context<'_, '_, '_, 'info, Cancel<'info>>) -> Result<()> {
+    check_token_program_id(&ctx.accounts.deposit_token_program.key())?;
+
+    let escrow_state = &ctx.accounts.escrow_state;
+    // Once a taker has locked collateral, only settlement or an unwind can close the escrow
+    require!(
+        escrow_state.status == EscrowStatus::Open,
+        ErrorCode::EscrowAlreadyAccepted
+    );
+
+    // --- CPI 1: Return Token A from the vault to the Initializer ---
+    let authority_seeds = &[
+        ESCROW_PDA_SEED,
+        escrow_state.initializer_key.as_ref(),
+        escrow_state.unique_seed.as_ref(),
+        &[escrow_state.bump],
+    ];
+    let signer_seeds = &[&authority_seeds[..]];
+
+    // A native vault pays its SOL out to the Initializer when it is closed below
+    if escrow_state.deposit_asset == AssetKind::Token {
+        let initializer_deposit_token_account = ctx
+            .accounts
+            .initializer_deposit_token_account
+            .as_ref()
+            .ok_or(ErrorCode::MissingTokenAccount)?;
+        let cpi_accounts_transfer = TransferChecked {
+            from: ctx.accounts.vault_account.to_account_info(),
+            mint: ctx.accounts.initializer_deposit_mint.to_account_info(),
+            to: initializer_deposit_token_account.to_account_info(),
+            authority: ctx.accounts.escrow_state.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.deposit_token_program.to_account_info();
+        let cpi_context_transfer =
+            CpiContext::new_with_signer(cpi_program, cpi_accounts_transfer, signer_seeds)
+                .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+        transfer_checked_with_hook(
+            cpi_context_transfer,
+            escrow_state.remaining_amount,
+            ctx.accounts.initializer_deposit_mint.decimals,
+        )?;
+    }
+
+    // --- CPI 2: Close the PDA-owned Vault Account ---
+    let cpi_accounts_close = CloseAccount {
+        account: ctx.accounts.vault_account.to_account_info(),
+        destination: ctx.accounts.initializer.to_account_info(), // Refund rent to Initializer
+        authority: ctx.accounts.escrow_state.to_account_info(),  // PDA signs the close
+    };
+    let cpi_program = ctx.accounts.deposit_token_program.to_account_info();
+    let cpi_context_close =
+        CpiContext::new_with_signer(cpi_program, cpi_accounts_close, signer_seeds);
+
+    token_interface::close_account(cpi_context_close)?;
+    {
+        let global_stats = &mut ctx.accounts.global_stats;
+
+        // Use checked arithmetic with manual error handling
+        global_stats.total_escrows_canceled = global_stats
+            .total_escrows_canceled
+            .checked_add(1)
+            .ok_or_else(|| error!(ErrorCode::NumericalOverflow))?;
+
+        // Refunds leave TVL without counting as released
+        global_stats.total_value_locked = global_stats
+            .total_value_locked
+            .saturating_sub(escrow_state.remaining_amount);
+
+        let deposit_mint_stats = &mut ctx.accounts.deposit_mint_stats;
+        deposit_mint_stats.record_refund(escrow_state.remaining_amount)?;
+    }
+
+    // --- Emit Event ---
+    emit!(EscrowCanceled {
+        initializer: escrow_state.initializer_key,
+        initializer_deposit_token_mint: escrow_state.initializer_deposit_token_mint,
+        canceled_amount: escrow_state.remaining_amount,
+        deposit_asset: escrow_state.deposit_asset,
+        unique_seed: escrow_state.unique_seed,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn refund_expired<'info>(ctx: Context<'_, '_, '_, 'info, RefundExpired<'info>>) -> Result<()> {
+    check_token_program_id(&ctx.accounts.deposit_token_program.key())?;
+
+    let escrow_state = &ctx.accounts.escrow_state;
+    let now = Clock::get()?.unix_timestamp;
+    require!(now > escrow_state.expires_at, ErrorCode::EscrowNotExpired);
+
+    let authority_seeds = &[
+        ESCROW_PDA_SEED,
+        escrow_state.initializer_key.as_ref(),
+        escrow_state.unique_seed.as_ref(),
+        &[escrow_state.bump],
+    ];
+    let signer_seeds = &[&authority_seeds[..]];
+
+    // --- CPI 0: Unwind an accepted two-sided escrow, returning the taker's collateral ---
+    // Only possible when it never became settleable, i.e. one side never confirmed
+    let mut taker_refunded_amount = 0;
+    if escrow_state.status == EscrowStatus::Accepted {
+        let collateral = escrow_state
+            .collateral
+            .ok_or(ErrorCode::InvalidCollateralTerms)?;
+        require!(
+            !collateral_settleable(&collateral, now),
+            ErrorCode::CollateralSettleable
+        );
+        let accounts = &ctx.accounts;
+        let taker = accounts
+            .taker
+            .as_ref()
+            .ok_or(ErrorCode::MissingCollateralAccounts)?;
+        let taker_deposit_token_account = accounts
+            .taker_deposit_token_account
+            .as_ref()
+            .ok_or(ErrorCode::MissingCollateralAccounts)?;
+        let taker_vault = accounts
+            .taker_vault
+            .as_ref()
+            .ok_or(ErrorCode::MissingCollateralAccounts)?;
+        let taker_expected_mint = accounts
+            .taker_expected_mint
+            .as_ref()
+            .ok_or(ErrorCode::MissingCollateralAccounts)?;
+        let receive_token_program = accounts
+            .receive_token_program
+            .as_ref()
+            .ok_or(ErrorCode::MissingCollateralAccounts)?;
+        require_keys_eq!(taker.key(), collateral.taker, ErrorCode::InvalidAccount);
+        require_keys_eq!(
+            taker_deposit_token_account.key(),
+            collateral.taker_deposit_token_account,
+            ErrorCode::InvalidAccount
+        );
+
+        taker_refunded_amount = taker_vault.amount;
+        let cpi_accounts_transfer = TransferChecked {
+            from: taker_vault.to_account_info(),
+            mint: taker_expected_mint.to_account_info(),
+            to: taker_deposit_token_account.to_account_info(),
+            authority: accounts.escrow_state.to_account_info(),
+        };
+        let cpi_context_transfer = CpiContext::new_with_signer(
+            receive_token_program.to_account_info(),
+            cpi_accounts_transfer,
+            signer_seeds,
+        )
+        .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+        transfer_checked_with_hook(
+            cpi_context_transfer,
+            taker_refunded_amount,
+            taker_expected_mint.decimals,
+        )?;
+
+        let cpi_accounts_close = CloseAccount {
+            account: taker_vault.to_account_info(),
+            destination: taker.to_account_info(),
+            authority: accounts.escrow_state.to_account_info(),
+        };
+        let cpi_context_close = CpiContext::new_with_signer(
+            receive_token_program.to_account_info(),
+            cpi_accounts_close,
+            signer_seeds,
+        );
+        token_interface::close_account(cpi_context_close)?;
+    }
+
+    // --- CPI 1: Return Token A from the vault to the Initializer ---
+    // A native vault pays its SOL out to the Initializer when it is closed below
+    if escrow_state.deposit_asset == AssetKind::Token {
+        let initializer_deposit_token_account = ctx
+            .accounts
+            .initializer_deposit_token_account
+            .as_ref()
+            .ok_or(ErrorCode::MissingTokenAccount)?;
+        let cpi_accounts_transfer = TransferChecked {
+            from: ctx.accounts.vault_account.to_account_info(),
+            mint: ctx.accounts.initializer_deposit_mint.to_account_info(),
+            to: initializer_deposit_token_account.to_account_info(),
+            authority: ctx.accounts.escrow_state.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.deposit_token_program.to_account_info();
+        let cpi_context_transfer =
+            CpiContext::new_with_signer(cpi_program, cpi_accounts_transfer, signer_seeds)
+                .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+        transfer_checked_with_hook(
+            cpi_context_transfer,
+            escrow_state.remaining_amount,
+            ctx.accounts.initializer_deposit_mint.decimals,
+        )?;
+    }
+
+    // --- CPI 2: Close the vault, rent goes to the Initializer ---
+    let cpi_accounts_close = CloseAccount {
+        account: ctx.accounts.vault_account.to_account_info(),
+        destination: ctx.accounts.initializer.to_account_info(),
+        authority: ctx.accounts.escrow_state.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.deposit_token_program.to_account_info();
+    let cpi_context_close =
+        CpiContext::new_with_signer(cpi_program, cpi_accounts_close, signer_seeds);
+
+    token_interface::close_account(cpi_context_close)?;
+    {
+        let global_stats = &mut ctx.accounts.global_stats;
+
+        global_stats.total_escrows_canceled = global_stats
+            .total_escrows_canceled
+            .checked_add(1)
+            .ok_or_else(|| error!(ErrorCode::NumericalOverflow))?;
+
+        // Funds go back to the seller, so they leave TVL without counting as released
+        global_stats.total_value_locked = global_stats
+            .total_value_locked
+            .saturating_sub(escrow_state.remaining_amount);
+
+        let deposit_mint_stats = &mut ctx.accounts.deposit_mint_stats;
+        deposit_mint_stats.record_refund(escrow_state.remaining_amount)?;
+    }
+
+    // --- Emit Event ---
+    emit!(EscrowExpired {
+        initializer: escrow_state.initializer_key,
+        cranker: ctx.accounts.cranker.key(),
+        initializer_deposit_token_mint: escrow_state.initializer_deposit_token_mint,
+        refunded_amount: escrow_state.remaining_amount,
+        deposit_asset: escrow_state.deposit_asset,
+        unique_seed: escrow_state.unique_seed,
+        expires_at: escrow_state.expires_at,
+        timestamp: now,
+        taker_refunded_amount,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn amend_escrow<'info>(
+    ctx: Context<'_, '_, '_, 'info, AmendEscrow<'info>>,
+    new_taker_expected_amount: Option<u64>,
+    new_expires_at: Option<i64>,
+    top_up_amount: u64,
+    withdraw_amount: u64,
+) -> Result<()> {
+    check_token_program_id(&ctx.accounts.deposit_token_program.key())?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let escrow_state = &ctx.accounts.escrow_state;
+    require!(now <= escrow_state.expires_at, ErrorCode::EscrowExpired);
+    require!(
+        top_up_amount == 0 || withdraw_amount == 0,
+        ErrorCode::InvalidAmount
+    );
+    require!(
+        escrow_state.status == EscrowStatus::Open,
+        ErrorCode::EscrowAlreadyAccepted
+    );
+    // The price curve of a Dutch auction is fixed; cancel and relist to change it
+    require!(
+        escrow_state.dutch_auction.is_none()
+            || (new_taker_expected_amount.is_none() && new_expires_at.is_none()),
+        ErrorCode::AuctionScheduleLocked
+    );
+    // A collection bid always asks for exactly one NFT
+    require!(
+        !escrow_state.collection_bid || new_taker_expected_amount.is_none_or(|amount| amount == 1),
+        ErrorCode::InvalidNftTerms
+    );
+    // Withdrawing everything is what `cancel` is for
+    require!(
+        withdraw_amount < escrow_state.remaining_amount,
+        ErrorCode::InsufficientFunds
+    );
+
+    let old_initializer_amount = escrow_state.initializer_amount;
+    let old_taker_expected_amount = escrow_state.taker_expected_amount;
+    let old_expires_at = escrow_state.expires_at;
+    let taker_remaining = old_taker_expected_amount
+        .checked_sub(escrow_state.filled_amount)
+        .ok_or(ErrorCode::Overflow)?;
+    let new_taker_expected_amount = new_taker_expected_amount.unwrap_or(taker_remaining);
+    require!(new_taker_expected_amount > 0, ErrorCode::InvalidAmount);
+
+    let new_expires_at = match new_expires_at {
+        Some(expires_at) => {
+            check_duration(
+                &ctx.accounts.config,
+                expires_at.checked_sub(now).ok_or(ErrorCode::Overflow)?,
+            )?;
+            expires_at
+        }
+        None => old_expires_at,
+    };
+
+    let authority_seeds = &[
+        ESCROW_PDA_SEED,
+        escrow_state.initializer_key.as_ref(),
+        escrow_state.unique_seed.as_ref(),
+        &[escrow_state.bump],
+    ];
+    let signer_seeds = &[&authority_seeds[..]];
+    let vault_before = ctx.accounts.vault_account.amount;
+
+    // --- CPI: Top up the vault from the Initializer ---
+    if top_up_amount > 0 {
+        if escrow_state.deposit_asset == AssetKind::NativeSol {
+            wrap_lamports(
+                ctx.accounts.system_program.to_account_info(),
+                ctx.accounts.deposit_token_program.to_account_info(),
+                ctx.accounts.initializer.to_account_info(),
+                ctx.accounts.vault_account.to_account_info(),
+                top_up_amount,
+            )?;
+        } else {
+            let initializer_deposit_token_account = ctx
+                .accounts
+                .initializer_deposit_token_account
+                .as_ref()
+                .ok_or(ErrorCode::MissingTokenAccount)?;
+            let cpi_accounts = TransferChecked {
+                from: initializer_deposit_token_account.to_account_info(),
+                mint: ctx.accounts.initializer_deposit_mint.to_account_info(),
+                to: ctx.accounts.vault_account.to_account_info(),
+                authority: ctx.accounts.initializer.to_account_info(),
+            };
+            let cpi_program = ctx.accounts.deposit_token_program.to_account_info();
+            let cpi_context = CpiContext::new(cpi_program, cpi_accounts)
+                .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+            transfer_checked_with_hook(
+                cpi_context,
+                top_up_amount,
+                ctx.accounts.initializer_deposit_mint.decimals,
+            )?;
+        }
+    }
+
+    // --- CPI: Withdraw part of the vault back to the Initializer ---
+    if withdraw_amount > 0 {
+        if escrow_state.deposit_asset == AssetKind::NativeSol {
+            // Unwrapped through a temporary account, so the Initializer gets lamports
+            let unwrap_account = ctx
+                .accounts
+                .unwrap_account
+                .as_ref()
+                .ok_or(ErrorCode::MissingTokenAccount)?;
+            unwrap_from_vault(
+                ctx.accounts.deposit_token_program.to_account_info(),
+                &ctx.accounts.initializer_deposit_mint,
+                ctx.accounts.vault_account.to_account_info(),
+                unwrap_account.to_account_info(),
+                ctx.accounts.escrow_state.to_account_info(),
+                signer_seeds,
+                ctx.accounts.initializer.to_account_info(),
+                withdraw_amount,
+            )?;
+        } else {
+            let initializer_deposit_token_account = ctx
+                .accounts
+                .initializer_deposit_token_account
+                .as_ref()
+                .ok_or(ErrorCode::MissingTokenAccount)?;
+            let cpi_accounts = TransferChecked {
+                from: ctx.accounts.vault_account.to_account_info(),
+                mint: ctx.accounts.initializer_deposit_mint.to_account_info(),
+                to: initializer_deposit_token_account.to_account_info(),
+                authority: ctx.accounts.escrow_state.to_account_info(),
+            };
+            let cpi_program = ctx.accounts.deposit_token_program.to_account_info();
+            let cpi_context = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds)
+                .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+            transfer_checked_with_hook(
+                cpi_context,
+                withdraw_amount,
+                ctx.accounts.initializer_deposit_mint.decimals,
+            )?;
+        }
+    }
+
+    // The escrow is re-based on whatever the vault holds after the top-up or withdrawal
+    ctx.accounts.vault_account.reload()?;
+    let vault_after = ctx.accounts.vault_account.amount;
+
+    let escrow_state = &mut ctx.accounts.escrow_state;
+    escrow_state.initializer_amount = vault_after;
+    escrow_state.remaining_amount = vault_after;
+    escrow_state.taker_expected_amount = new_taker_expected_amount;
+    escrow_state.filled_amount = 0;
+    escrow_state.expires_at = new_expires_at;
+    {
+        let global_stats = &mut ctx.accounts.global_stats;
+        global_stats.total_value_locked = global_stats
+            .total_value_locked
+            .saturating_sub(vault_before)
+            .checked_add(vault_after)
+            .ok_or_else(|| error!(ErrorCode::NumericalOverflow))?;
+
+        let deposit_mint_stats = &mut ctx.accounts.deposit_mint_stats;
+        deposit_mint_stats.record_refund(withdraw_amount)?;
+        // Whatever the vault gained beyond the withdrawal is the top-up it actually received
+        deposit_mint_stats.record_lock((vault_after + withdraw_amount).saturating_sub(vault_before))?;
+    }
+
+    // --- Emit Event ---
+    let escrow_state = &ctx.accounts.escrow_state;
+    emit!(EscrowAmended {
+        initializer: escrow_state.initializer_key,
+        old_initializer_amount,
+        new_initializer_amount: vault_after,
+        old_taker_expected_amount,
+        new_taker_expected_amount,
+        old_expires_at,
+        new_expires_at,
+        top_up_amount,
+        withdraw_amount,
+        unique_seed: escrow_state.unique_seed,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn quote(ctx: Context<Quote>) -> Result<u64> {
+    let escrow_state = &ctx.accounts.escrow_state;
+    let now = Clock::get()?.unix_timestamp;
+    require!(now <= escrow_state.expires_at, ErrorCode::EscrowExpired);
+    let ask = current_ask(escrow_state, now)?;
+    Ok(ask
+        .checked_sub(escrow_state.filled_amount)
+        .ok_or(ErrorCode::Overflow)?)
+}
+
+/// Accounts for the `initialize` instruction
+#[derive(Accounts)]
+#[instruction(
+    initializer_amount: u64,
+    taker_expected_amount: u64,
+     duration_in_seconds:i64,
+    unique_seed: [u8; 8],
+    allowed_taker: Option<Pubkey>,
+    taker_pays_transfer_fee: bool,
+    deposit_asset: AssetKind,
+)] 
+pub struct Initialize<'info> {
+    /// The user creating the escrow (Seller). Must sign the transaction.
+    #[account(mut)]
+    pub initializer: Signer<'info>,
+
+    /// The token account belonging to the initializer that holds Token A (the token being offered).
+    /// Initializer's tokens will be transferred from here; omitted for a native SOL leg.
+    #[account(
+        mut, 
+        constraint = initializer_deposit_token_account.amount >= initializer_amount @ErrorCode::InsufficientFunds,
+        token::mint = initializer_deposit_token_mint,
+        token::authority = initializer,
+        token::token_program = deposit_token_program
+    )]
+    pub initializer_deposit_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The Mint account for Token A (the token being offered). Used for cross-checking.
+    #[account(mint::token_program = deposit_token_program)]
+    pub initializer_deposit_token_mint: InterfaceAccount<'info, Mint>,
+
+    /// The Mint account for Token B (the token the seller expects in return). Used for cross-checking.
+    /// Must differ from Token A, as both legs keep their own `MintStats`.
+    #[account(
+        mint::token_program = receive_token_program,
+        constraint = taker_expected_token_mint.key() != initializer_deposit_token_mint.key() @ErrorCode::SameMint
+    )]
+    pub taker_expected_token_mint: InterfaceAccount<'info, Mint>,
+
+    /// The Initializer's account where they will receive the Token B if the trade is completed.
+    /// This account must already exist; omitted for a native SOL leg.
+    #[account(
+        mut, 
+        token::mint = taker_expected_token_mint,
+        token::authority = initializer,
+        token::token_program = receive_token_program
+    )]
+    pub initializer_receive_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+            mut,
+            seeds = [b"global-stats"], 
+            bump = global_stats.bump
+        )]
+    pub global_stats: Account<'info, GlobalStats>,
+
+    /// Per-mint stats for Token A, created on first use
+    #[account(
+        init_if_needed,
+        seeds = [MINT_STATS_SEED, initializer_deposit_token_mint.key().as_ref()],
+        bump,
+        payer = initializer,
+        space = MINT_STATS_ACCOUNT_SPACE,
+    )]
+    pub deposit_mint_stats: Account<'info, MintStats>,
+
+    /// Per-mint stats for Token B, created on first use
+    #[account(
+        init_if_needed,
+        seeds = [MINT_STATS_SEED, taker_expected_token_mint.key().as_ref()],
+        bump,
+        payer = initializer,
+        space = MINT_STATS_ACCOUNT_SPACE,
+    )]
+    pub receive_mint_stats: Account<'info, MintStats>,
+
+    /// Protocol config, checked for the pause flag
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    /// The Escrow State PDA account. Stores the details of the trade.
+    #[account(
+        init,
+        seeds = [ESCROW_PDA_SEED, initializer.key().as_ref(), unique_seed.as_ref()],
+        bump,
+        payer = initializer,
+        space = ESCROW_ACCOUNT_SPACE,
+    )]
+    pub escrow_state: Account<'info, EscrowState>,
+
+    /// The PDA-owned token account (Vault) that will hold the Initializer's deposited tokens (Token A).
+    #[account(
+        init,
+        token::mint = initializer_deposit_token_mint,
+        token::authority = escrow_state,
+        token::token_program = deposit_token_program,
+        payer = initializer,
+        seeds = [b"vault", escrow_state.key().as_ref()],
+        bump
+    )]
+    pub vault_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Token program owning Token A
+    pub deposit_token_program: Interface<'info, TokenInterface>,
+
+    /// Token program owning Token B
+    pub receive_token_program: Interface<'info, TokenInterface>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Accounts for the `exchange` instruction
+#[derive(Accounts)]
+pub struct Exchange<'info> {
+    /// Buyer (taker) — must sign, and match `allowed_taker` for private escrows
+    #[account(
+        mut,
+        constraint = escrow_state.allowed_taker.is_none_or(|allowed| allowed == taker.key()) @ErrorCode::UnauthorizedTaker
+    )]
+    pub taker: Signer<'info>,
+
+    /// Taker's account holding Token B (the one they're offering); omitted for a native SOL leg
+    #[account(
+        mut,
+        constraint = taker_deposit_token_account.mint == escrow_state.taker_expected_token_mint @ErrorCode::InvalidMint,
+        token::token_program = receive_token_program
+    )]
+    pub taker_deposit_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Taker's account receiving Token A; omitted for a native SOL leg
+    #[account(
+        mut,
+        constraint = taker_receive_token_account.mint == escrow_state.initializer_deposit_token_mint @ErrorCode::InvalidMint,
+        token::token_program = deposit_token_program
+    )]
+    pub taker_receive_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Initializer's receiving account for Token B; omitted for a native SOL leg
+    #[account(
+        mut,
+        constraint = initializer_receive_token_account.key() == escrow_state.initializer_receive_token_account @ErrorCode::InvalidAccount,
+        token::token_program = receive_token_program
+    )]
+    pub initializer_receive_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Initializer's Token A account, receives rounding dust on the final fill; omitted for a
+    /// native SOL leg
+    #[account(
+        mut,
+        address = escrow_state.initializer_deposit_token_account @ErrorCode::InvalidAccount,
+        token::token_program = deposit_token_program
+    )]
+    pub initializer_deposit_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// PDA holding the escrow state (closed to the taker on the final fill)
+    #[account(
+        mut,
+        seeds = [ESCROW_PDA_SEED, escrow_state.initializer_key.as_ref(), escrow_state.unique_seed.as_ref()],
+        bump = escrow_state.bump,
+        has_one = initializer_key,
+    )]
+    pub escrow_state: Account<'info, EscrowState>,
+
+    /// PDA vault that holds Token A
+    #[account(
+        mut,
+        token::authority = escrow_state,
+        token::token_program = deposit_token_program
+    )]
+    pub vault_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+            mut,
+            seeds = [b"global-stats"],
+            bump = global_stats.bump
+        )]
+    pub global_stats: Account<'info, GlobalStats>,
+    /// Per-mint stats for Token A
+    #[account(
+        mut,
+        seeds = [MINT_STATS_SEED, escrow_state.initializer_deposit_token_mint.as_ref()],
+        bump = deposit_mint_stats.bump
+    )]
+    pub deposit_mint_stats: Account<'info, MintStats>,
+    /// Per-mint stats for Token B
+    #[account(
+        mut,
+        seeds = [MINT_STATS_SEED, escrow_state.taker_expected_token_mint.as_ref()],
+        bump = receive_mint_stats.bump
+    )]
+    pub receive_mint_stats: Account<'info, MintStats>,
+    /// Mint for Token A (initializer’s deposited token)
+    #[account(
+        mint::token_program = deposit_token_program
+    )]
+    pub initializer_deposit_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+            mint::token_program = receive_token_program
+        )]
+    /// Mint for Token B (taker’s offered token)
+    pub taker_expected_mint: InterfaceAccount<'info, Mint>,
+
+    /// Checked against the escrow (has_one); receives native SOL payments and native vault dust
+    /// CHECK: Used only for matching initializer
+    #[account(mut)]
+    pub initializer_key: AccountInfo<'info>,
+
+    /// Token program owning Token A
+    #[account(address = escrow_state.deposit_token_program @ErrorCode::InvalidTokenProgram)]
+    pub deposit_token_program: Interface<'info, TokenInterface>,
+
+    /// Token program owning Token B
+    #[account(address = escrow_state.receive_token_program @ErrorCode::InvalidTokenProgram)]
+    pub receive_token_program: Interface<'info, TokenInterface>,
+
+    /// Protocol fee settings
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Treasury's Token A account, required when the initializer side is charged
+    #[account(
+        mut,
+        token::mint = initializer_deposit_mint,
+        token::authority = config.treasury,
+        token::token_program = deposit_token_program
+    )]
+    pub treasury_deposit_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Treasury's Token B account, required when the taker side is charged
+    #[account(
+        mut,
+        token::mint = taker_expected_mint,
+        token::authority = config.treasury,
+        token::token_program = receive_token_program
+    )]
+    pub treasury_receive_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Moves native SOL legs
+    pub system_program: Program<'info, System>,
+
+    /// Per-wallet fill tracker, required for allowlisted fills with a `wallet_cap`
+    #[account(
+        init_if_needed,
+        seeds = [ALLOWLIST_FILL_SEED, escrow_state.key().as_ref(), taker.key().as_ref()],
+        bump,
+        payer = taker,
+        space = ALLOWLIST_FILL_ACCOUNT_SPACE,
+    )]
+    pub allowlist_fill: Option<Account<'info, AllowlistFill>>,
+
+    /// Temporary wSOL account the release is unwrapped through, required for a native SOL
+    /// deposit and closed to the taker within the instruction
+    #[account(
+        init,
+        seeds = [UNWRAP_SEED, escrow_state.key().as_ref()],
+        bump,
+        payer = taker,
+        token::mint = initializer_deposit_mint,
+        token::authority = escrow_state,
+        token::token_program = deposit_token_program,
+    )]
+    pub unwrap_account: Option<InterfaceAccount<'info, TokenAccount>>,
+}
+
+/// Accounts for the `exchange_many` instruction. The escrows come in remaining accounts.
+#[derive(Accounts)]
+pub struct ExchangeMany<'info> {
+    /// Buyer (taker); pays for every fill and collects the closed accounts' rent
+    #[account(mut)]
+    pub taker: Signer<'info>,
+
+    /// Taker's account holding Token B
+    #[account(
+        mut,
+        token::mint = taker_expected_mint,
+        token::authority = taker,
+        token::token_program = receive_token_program
+    )]
+    pub taker_deposit_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Taker's account receiving Token A
+    #[account(
+        mut,
+        token::mint = initializer_deposit_mint,
+        token::authority = taker,
+        token::token_program = deposit_token_program
+    )]
+    pub taker_receive_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Mint for Token A, shared by every escrow in the batch
+    #[account(mint::token_program = deposit_token_program)]
+    pub initializer_deposit_mint: InterfaceAccount<'info, Mint>,
+
+    /// Mint for Token B, shared by every escrow in the batch
+    #[account(mint::token_program = receive_token_program)]
+    pub taker_expected_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"global-stats"],
+        bump = global_stats.bump
+    )]
+    pub global_stats: Account<'info, GlobalStats>,
+
+    /// Per-mint stats for Token A
+    #[account(
+        mut,
+        seeds = [MINT_STATS_SEED, initializer_deposit_mint.key().as_ref()],
+        bump = deposit_mint_stats.bump
+    )]
+    pub deposit_mint_stats: Account<'info, MintStats>,
+
+    /// Per-mint stats for Token B
+    #[account(
+        mut,
+        seeds = [MINT_STATS_SEED, taker_expected_mint.key().as_ref()],
+        bump = receive_mint_stats.bump
+    )]
+    pub receive_mint_stats: Account<'info, MintStats>,
+
+    /// Token program owning Token A, checked against each escrow
+    pub deposit_token_program: Interface<'info, TokenInterface>,
+
+    /// Token program owning Token B, checked against each escrow
+    pub receive_token_program: Interface<'info, TokenInterface>,
+
+    /// Protocol fee settings and pause flag
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Treasury's Token A account, required when the initializer side is charged
+    #[account(
+        mut,
+        token::mint = initializer_deposit_mint,
+        token::authority = config.treasury,
+        token::token_program = deposit_token_program
+    )]
+    pub treasury_deposit_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Treasury's Token B account, required when the taker side is charged
+    #[account(
+        mut,
+        token::mint = taker_expected_mint,
+        token::authority = config.treasury,
+        token::token_program = receive_token_program
+    )]
+    pub treasury_receive_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+}
+
+/// Accounts for the `cancel` instruction
+#[derive(Accounts)]
+pub struct Cancel<'info> {
+    /// The user who initialized the escrow (Seller)
+    #[account(mut)]
+    pub initializer: Signer<'info>,
+
+    /// The Initializer's token account to refund Token A into; omitted for a native SOL leg
+    #[account(
+        mut, 
+        constraint = initializer_deposit_token_account.owner == initializer.key() @ErrorCode::InvalidOwner,
+        token::token_program = deposit_token_program
+    )]
+    pub initializer_deposit_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// PDA-owned vault holding Token A
+    #[account(
+        mut,
+        token::authority = escrow_state,
+        token::token_program = deposit_token_program
+    )]
+    pub vault_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Mint of the deposited token (Token A)
+    pub initializer_deposit_mint: InterfaceAccount<'info, Mint>,
+
+    /// Escrow state PDA
+    #[account(
+        mut,
+        seeds = [ESCROW_PDA_SEED, initializer.key().as_ref(), escrow_state.unique_seed.as_ref()],
+        bump = escrow_state.bump,
+        close = initializer,
+    )]
+    pub escrow_state: Account<'info, EscrowState>,
+ #[account(
+            mut,
+            seeds = [b"global-stats"], 
+            bump = global_stats.bump
+        )]
+    pub global_stats: Account<'info, GlobalStats>,
+    /// Per-mint stats for Token A
+    #[account(
+        mut,
+        seeds = [MINT_STATS_SEED, escrow_state.initializer_deposit_token_mint.as_ref()],
+        bump = deposit_mint_stats.bump
+    )]
+    pub deposit_mint_stats: Account<'info, MintStats>,
+    /// Token program owning Token A
+    #[account(address = escrow_state.deposit_token_program @ErrorCode::InvalidTokenProgram)]
+    pub deposit_token_program: Interface<'info, TokenInterface>,
+}
+
+/// Accounts for the `refund_expired` instruction
+#[derive(Accounts)]
+pub struct RefundExpired<'info> {
+    /// Anyone may crank an expired escrow; only pays the transaction fee
+    pub cranker: Signer<'info>,
+
+    /// The Initializer (Seller), receives the rent of the closed accounts
+    /// CHECK: Matched against `escrow_state.initializer_key`
+    #[account(
+        mut,
+        address = escrow_state.initializer_key @ErrorCode::InvalidOwner
+    )]
+    pub initializer: AccountInfo<'info>,
+
+    /// The Initializer's token account recorded at creation, refunded with Token A; omitted
+    /// for a native SOL leg
+    #[account(
+        mut,
+        address = escrow_state.initializer_deposit_token_account @ErrorCode::InvalidAccount,
+        token::token_program = deposit_token_program
+    )]
+    pub initializer_deposit_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// PDA-owned vault holding Token A
+    #[account(
+        mut,
+        seeds = [b"vault", escrow_state.key().as_ref()],
+        bump,
+        token::authority = escrow_state,
+        token::token_program = deposit_token_program
+    )]
+    pub vault_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Mint of the deposited token (Token A)
+    #[account(
+        address = escrow_state.initializer_deposit_token_mint @ErrorCode::InvalidMint
+    )]
+    pub initializer_deposit_mint: InterfaceAccount<'info, Mint>,
+
+    /// Escrow state PDA
+    #[account(
+        mut,
+        seeds = [ESCROW_PDA_SEED, escrow_state.initializer_key.as_ref(), escrow_state.unique_seed.as_ref()],
+        bump = escrow_state.bump,
+        close = initializer,
+    )]
+    pub escrow_state: Account<'info, EscrowState>,
+    #[account(
+        mut,
+        seeds = [b"global-stats"],
+        bump = global_stats.bump
+    )]
+    pub global_stats: Account<'info, GlobalStats>,
+    /// Per-mint stats for Token A
+    #[account(
+        mut,
+        seeds = [MINT_STATS_SEED, escrow_state.initializer_deposit_token_mint.as_ref()],
+        bump = deposit_mint_stats.bump
+    )]
+    pub deposit_mint_stats: Account<'info, MintStats>,
+    /// Token program owning Token A
+    #[account(address = escrow_state.deposit_token_program @ErrorCode::InvalidTokenProgram)]
+    pub deposit_token_program: Interface<'info, TokenInterface>,
+
+    // The accounts below are only required to unwind an accepted two-sided escrow
+
+    /// CHECK: Matched against the taker recorded by `accept`; receives the taker vault rent
+    #[account(mut)]
+    pub taker: Option<AccountInfo<'info>>,
+
+    /// Taker's account recorded by `accept`, refunded with Token B
+    #[account(mut)]
+    pub taker_deposit_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// PDA-owned vault holding the taker's Token B
+    #[account(
+        mut,
+        seeds = [TAKER_VAULT_SEED, escrow_state.key().as_ref()],
+        bump,
+    )]
+    pub taker_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address = escrow_state.taker_expected_token_mint @ErrorCode::InvalidMint)]
+    pub taker_expected_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    #[account(address = escrow_state.receive_token_program @ErrorCode::InvalidTokenProgram)]
+    pub receive_token_program: Option<Interface<'info, TokenInterface>>,
+}
+
+/// Accounts for the `amend_escrow` instruction
+#[derive(Accounts)]
+pub struct AmendEscrow<'info> {
+    /// The user who initialized the escrow (Seller)
+    #[account(mut)]
+    pub initializer: Signer<'info>,
+
+    /// The Initializer's Token A account, source of top-ups and target of withdrawals;
+    /// omitted for a native SOL leg
+    #[account(
+        mut,
+        address = escrow_state.initializer_deposit_token_account @ErrorCode::InvalidAccount,
+        token::token_program = deposit_token_program
+    )]
+    pub initializer_deposit_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// PDA-owned vault holding Token A
+    #[account(
+        mut,
+        seeds = [b"vault", escrow_state.key().as_ref()],
+        bump,
+        token::authority = escrow_state,
+        token::token_program = deposit_token_program
+    )]
+    pub vault_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Mint of the deposited token (Token A)
+    #[account(
+        address = escrow_state.initializer_deposit_token_mint @ErrorCode::InvalidMint,
+        mint::token_program = deposit_token_program
+    )]
+    pub initializer_deposit_mint: InterfaceAccount<'info, Mint>,
+
+    /// Escrow state PDA
+    #[account(
+        mut,
+        seeds = [ESCROW_PDA_SEED, initializer.key().as_ref(), escrow_state.unique_seed.as_ref()],
+        bump = escrow_state.bump,
+    )]
+    pub escrow_state: Account<'info, EscrowState>,
+    #[account(
+        mut,
+        seeds = [b"global-stats"],
+        bump = global_stats.bump
+    )]
+    pub global_stats: Account<'info, GlobalStats>,
+    /// Per-mint stats for Token A
+    #[account(
+        mut,
+        seeds = [MINT_STATS_SEED, escrow_state.initializer_deposit_token_mint.as_ref()],
+        bump = deposit_mint_stats.bump
+    )]
+    pub deposit_mint_stats: Account<'info, MintStats>,
+    /// Token program owning Token A
+    #[account(address = escrow_state.deposit_token_program @ErrorCode::InvalidTokenProgram)]
+    pub deposit_token_program: Interface<'info, TokenInterface>,
+
+    /// Protocol config, for the duration bounds
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Temporary wSOL account a native SOL withdrawal is unwrapped through, closed within
+    /// the instruction
+    #[account(
+        init,
+        seeds = [UNWRAP_SEED, escrow_state.key().as_ref()],
+        bump,
+        payer = initializer,
+        token::mint = initializer_deposit_mint,
+        token::authority = escrow_state,
+        token::token_program = deposit_token_program,
+    )]
+    pub unwrap_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Wraps native SOL top-ups and funds the unwrap account
+    pub system_program: Program<'info, System>,
+}
+
+/// Accounts for the `quote` instruction
+#[derive(Accounts)]
+pub struct Quote<'info> {
+    #[account(
+        seeds = [ESCROW_PDA_SEED, escrow_state.initializer_key.as_ref(), escrow_state.unique_seed.as_ref()],
+        bump = escrow_state.bump,
+    )]
+    pub escrow_state: Account<'info, EscrowState>,
+}
@@ -0,0 +1,406 @@
+use crate::*;
+
+pub(crate) fn create_vesting_escrow<'info>(
+    ctx: Context<'_, '_, '_, 'info, CreateVestingEscrow<'info>>,
+    amount: u64,
+    unique_seed: [u8; 8],
+    start_time: i64,
+    cliff_time: i64,
+    end_time: i64,
+    revocable: bool,
+) -> Result<()> {
+    require!(!ctx.accounts.config.paused, ErrorCode::ProgramPaused);
+    check_token_program_id(ctx.accounts.token_program.key)?;
+    require!(amount > 0, ErrorCode::InvalidAmount);
+    require_keys_neq!(
+        ctx.accounts.beneficiary.key(),
+        ctx.accounts.initializer.key(),
+        ErrorCode::InvalidAccount
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        start_time < end_time
+            && start_time <= cliff_time
+            && cliff_time <= end_time
+            && end_time > now,
+        ErrorCode::InvalidVestingSchedule
+    );
+
+    // --- CPI: Initializer funds the vault ---
+    let cpi_accounts = TransferChecked {
+        from: ctx.accounts.initializer_token_account.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        to: ctx.accounts.vault_account.to_account_info(),
+        authority: ctx.accounts.initializer.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_context = CpiContext::new(cpi_program, cpi_accounts)
+        .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+    let total_amount = deposit_to_vault(cpi_context, amount, ctx.accounts.mint.decimals)?;
+    require!(total_amount > 0, ErrorCode::InvalidAmount);
+
+    let vesting_escrow = &mut ctx.accounts.vesting_escrow;
+    vesting_escrow.initializer = ctx.accounts.initializer.key();
+    vesting_escrow.beneficiary = ctx.accounts.beneficiary.key();
+    vesting_escrow.mint = ctx.accounts.mint.key();
+    vesting_escrow.token_program = ctx.accounts.token_program.key();
+    vesting_escrow.total_amount = total_amount;
+    vesting_escrow.claimed_amount = 0;
+    vesting_escrow.start_time = start_time;
+    vesting_escrow.cliff_time = cliff_time;
+    vesting_escrow.end_time = end_time;
+    vesting_escrow.revocable = revocable;
+    vesting_escrow.unique_seed = unique_seed;
+    vesting_escrow.bump = ctx.bumps.vesting_escrow;
+
+    let mint_stats = &mut ctx.accounts.mint_stats;
+    mint_stats.init_if_needed(ctx.accounts.mint.key(), ctx.bumps.mint_stats);
+    mint_stats.record_lock(total_amount)?;
+
+    emit!(VestingEscrowCreated {
+        initializer: ctx.accounts.initializer.key(),
+        beneficiary: ctx.accounts.beneficiary.key(),
+        mint: ctx.accounts.mint.key(),
+        total_amount,
+        start_time,
+        cliff_time,
+        end_time,
+        revocable,
+        unique_seed,
+        timestamp: now,
+    });
+    Ok(())
+}
+
+pub(crate) fn claim_vested<'info>(ctx: Context<'_, '_, '_, 'info, ClaimVested<'info>>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let vesting_escrow = &ctx.accounts.vesting_escrow;
+    let amount = vested_amount(vesting_escrow, now)?
+        .checked_sub(vesting_escrow.claimed_amount)
+        .ok_or(ErrorCode::Overflow)?;
+    require!(amount > 0, ErrorCode::NothingToClaim);
+
+    let authority_seeds = &[
+        ESCROW_PDA_SEED,
+        vesting_escrow.initializer.as_ref(),
+        vesting_escrow.unique_seed.as_ref(),
+        &[vesting_escrow.bump],
+    ];
+    let signer_seeds = &[&authority_seeds[..]];
+
+    // --- CPI 1: Vault pays the vested amount to the beneficiary ---
+    let cpi_accounts_transfer = TransferChecked {
+        from: ctx.accounts.vault_account.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        to: ctx.accounts.beneficiary_token_account.to_account_info(),
+        authority: ctx.accounts.vesting_escrow.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_context_transfer =
+        CpiContext::new_with_signer(cpi_program, cpi_accounts_transfer, signer_seeds)
+            .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+    transfer_checked_with_hook(cpi_context_transfer, amount, ctx.accounts.mint.decimals)?;
+
+    let total_claimed = ctx
+        .accounts
+        .vesting_escrow
+        .claimed_amount
+        .checked_add(amount)
+        .ok_or(ErrorCode::Overflow)?;
+    let settled = total_claimed == ctx.accounts.vesting_escrow.total_amount;
+
+    // --- CPI 2: Close the vault once fully claimed, rent goes back to the initializer ---
+    if settled {
+        let cpi_accounts_close = CloseAccount {
+            account: ctx.accounts.vault_account.to_account_info(),
+            destination: ctx.accounts.initializer.to_account_info(),
+            authority: ctx.accounts.vesting_escrow.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_context_close =
+            CpiContext::new_with_signer(cpi_program, cpi_accounts_close, signer_seeds);
+
+        token_interface::close_account(cpi_context_close)?;
+    }
+
+    ctx.accounts.vesting_escrow.claimed_amount = total_claimed;
+    ctx.accounts.mint_stats.record_release(amount)?;
+    if settled {
+        ctx.accounts
+            .vesting_escrow
+            .close(ctx.accounts.initializer.to_account_info())?;
+    }
+
+    let vesting_escrow = &ctx.accounts.vesting_escrow;
+    emit!(VestedClaimed {
+        initializer: vesting_escrow.initializer,
+        beneficiary: vesting_escrow.beneficiary,
+        amount,
+        total_claimed,
+        total_amount: vesting_escrow.total_amount,
+        settled,
+        unique_seed: vesting_escrow.unique_seed,
+        timestamp: now,
+    });
+    Ok(())
+}
+
+pub(crate) fn revoke_vesting<'info>(ctx: Context<'_, '_, '_, 'info, RevokeVesting<'info>>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let vesting_escrow = &ctx.accounts.vesting_escrow;
+    require!(vesting_escrow.revocable, ErrorCode::NotRevocable);
+
+    let vested = vested_amount(vesting_escrow, now)?;
+    let vested_unclaimed = vested
+        .checked_sub(vesting_escrow.claimed_amount)
+        .ok_or(ErrorCode::Overflow)?;
+    let unvested = vesting_escrow
+        .total_amount
+        .checked_sub(vested)
+        .ok_or(ErrorCode::Overflow)?;
+
+    let authority_seeds = &[
+        ESCROW_PDA_SEED,
+        vesting_escrow.initializer.as_ref(),
+        vesting_escrow.unique_seed.as_ref(),
+        &[vesting_escrow.bump],
+    ];
+    let signer_seeds = &[&authority_seeds[..]];
+
+    // --- CPI 1: Beneficiary keeps what has already vested ---
+    if vested_unclaimed > 0 {
+        let cpi_accounts_transfer = TransferChecked {
+            from: ctx.accounts.vault_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.beneficiary_token_account.to_account_info(),
+            authority: ctx.accounts.vesting_escrow.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_context_transfer =
+            CpiContext::new_with_signer(cpi_program, cpi_accounts_transfer, signer_seeds)
+                .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+        transfer_checked_with_hook(cpi_context_transfer, vested_unclaimed, ctx.accounts.mint.decimals)?;
+    }
+
+    // --- CPI 2: Unvested remainder goes back to the initializer ---
+    if unvested > 0 {
+        let cpi_accounts_transfer = TransferChecked {
+            from: ctx.accounts.vault_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.initializer_token_account.to_account_info(),
+            authority: ctx.accounts.vesting_escrow.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_context_transfer =
+            CpiContext::new_with_signer(cpi_program, cpi_accounts_transfer, signer_seeds)
+                .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+        transfer_checked_with_hook(cpi_context_transfer, unvested, ctx.accounts.mint.decimals)?;
+    }
+
+    // --- CPI 3: Close the vault, rent goes back to the initializer ---
+    let cpi_accounts_close = CloseAccount {
+        account: ctx.accounts.vault_account.to_account_info(),
+        destination: ctx.accounts.initializer.to_account_info(),
+        authority: ctx.accounts.vesting_escrow.to_account_info(),
+    };
+    let cpi_program = ctx.accounts.token_program.to_account_info();
+    let cpi_context_close =
+        CpiContext::new_with_signer(cpi_program, cpi_accounts_close, signer_seeds);
+
+    token_interface::close_account(cpi_context_close)?;
+    {
+        let mint_stats = &mut ctx.accounts.mint_stats;
+        mint_stats.record_release(vested_unclaimed)?;
+        mint_stats.record_refund(unvested)?;
+    }
+
+    let vesting_escrow = &ctx.accounts.vesting_escrow;
+    emit!(VestingRevoked {
+        initializer: vesting_escrow.initializer,
+        beneficiary: vesting_escrow.beneficiary,
+        vested_amount: vested_unclaimed,
+        unvested_amount: unvested,
+        unique_seed: vesting_escrow.unique_seed,
+        timestamp: now,
+    });
+    Ok(())
+}
+
+/// Accounts for the `create_vesting_escrow` instruction
+#[derive(Accounts)]
+#[instruction(amount: u64, unique_seed: [u8; 8])]
+pub struct CreateVestingEscrow<'info> {
+    /// The initializer locking the tokens. Must sign the transaction.
+    #[account(mut)]
+    pub initializer: Signer<'info>,
+
+    /// CHECK: Any wallet; claims tokens as they vest
+    pub beneficiary: AccountInfo<'info>,
+
+    /// The initializer's token account funding the vault
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = initializer,
+        token::token_program = token_program
+    )]
+    pub initializer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// The vesting escrow PDA, derived like the swap escrow
+    #[account(
+        init,
+        seeds = [ESCROW_PDA_SEED, initializer.key().as_ref(), unique_seed.as_ref()],
+        bump,
+        payer = initializer,
+        space = VESTING_ESCROW_ACCOUNT_SPACE,
+    )]
+    pub vesting_escrow: Account<'info, VestingEscrow>,
+
+    /// PDA-owned vault, same seeds as the swap escrow vault
+    #[account(
+        init,
+        token::mint = mint,
+        token::authority = vesting_escrow,
+        token::token_program = token_program,
+        payer = initializer,
+        seeds = [b"vault", vesting_escrow.key().as_ref()],
+        bump
+    )]
+    pub vault_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Per-mint stats, created on first use
+    #[account(
+        init_if_needed,
+        seeds = [MINT_STATS_SEED, mint.key().as_ref()],
+        bump,
+        payer = initializer,
+        space = MINT_STATS_ACCOUNT_SPACE,
+    )]
+    pub mint_stats: Account<'info, MintStats>,
+
+    /// Protocol config, checked for the pause flag
+    #[account(
+        seeds = [CONFIG_SEED],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Accounts for the `claim_vested` instruction
+#[derive(Accounts)]
+pub struct ClaimVested<'info> {
+    /// The beneficiary. Must sign the transaction.
+    pub beneficiary: Signer<'info>,
+
+    /// CHECK: Matched against `vesting_escrow.initializer`; receives the rent on the final claim
+    #[account(mut)]
+    pub initializer: AccountInfo<'info>,
+
+    /// The beneficiary's token account receiving the vested tokens
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = beneficiary,
+        token::token_program = token_program
+    )]
+    pub beneficiary_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_PDA_SEED, vesting_escrow.initializer.as_ref(), vesting_escrow.unique_seed.as_ref()],
+        bump = vesting_escrow.bump,
+        has_one = initializer @ErrorCode::InvalidOwner,
+        has_one = beneficiary @ErrorCode::Unauthorized,
+        has_one = mint @ErrorCode::InvalidMint,
+    )]
+    pub vesting_escrow: Account<'info, VestingEscrow>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vesting_escrow.key().as_ref()],
+        bump,
+    )]
+    pub vault_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [MINT_STATS_SEED, mint.key().as_ref()],
+        bump = mint_stats.bump
+    )]
+    pub mint_stats: Account<'info, MintStats>,
+
+    #[account(address = vesting_escrow.token_program @ErrorCode::InvalidTokenProgram)]
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Accounts for the `revoke_vesting` instruction
+#[derive(Accounts)]
+pub struct RevokeVesting<'info> {
+    /// The initializer. Must sign the transaction and receives the rent.
+    #[account(mut)]
+    pub initializer: Signer<'info>,
+
+    /// CHECK: Matched against `vesting_escrow.beneficiary`
+    pub beneficiary: AccountInfo<'info>,
+
+    /// The initializer's token account receiving the unvested remainder
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = initializer,
+        token::token_program = token_program
+    )]
+    pub initializer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// The beneficiary's token account receiving vested but unclaimed tokens
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = beneficiary,
+        token::token_program = token_program
+    )]
+    pub beneficiary_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [ESCROW_PDA_SEED, initializer.key().as_ref(), vesting_escrow.unique_seed.as_ref()],
+        bump = vesting_escrow.bump,
+        has_one = initializer @ErrorCode::Unauthorized,
+        has_one = beneficiary @ErrorCode::InvalidAccount,
+        has_one = mint @ErrorCode::InvalidMint,
+        close = initializer,
+    )]
+    pub vesting_escrow: Account<'info, VestingEscrow>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vesting_escrow.key().as_ref()],
+        bump,
+    )]
+    pub vault_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [MINT_STATS_SEED, mint.key().as_ref()],
+        bump = mint_stats.bump
+    )]
+    pub mint_stats: Account<'info, MintStats>,
+
+    #[account(address = vesting_escrow.token_program @ErrorCode::InvalidTokenProgram)]
+    pub token_program: Interface<'info, TokenInterface>,
+}
@@ -1,605 +1,490 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token_interface::{self, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked}; 
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{self, CloseAccount, InitializeAccount3, Mint, SyncNative, TokenAccount, TokenInterface, TransferChecked}; 
+use spl_token::native_mint::ID as NATIVE_MINT;
 use spl_token::ID as TOKEN_PROGRAM_ID;
 use spl_token_2022::ID as TOKEN_2022_PROGRAM_ID;
+use spl_token_2022::extension::{
+    transfer_fee::TransferFeeConfig, BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+};
+use spl_token_2022::state::{Account as Token2022Account, Mint as Token2022Mint};
+use spl_token_group_interface::state::{TokenGroup, TokenGroupMember};
+use solana_sdk_ids::ed25519_program;
+use solana_sha256_hasher::hashv;
+use solana_instructions_sysvar::{
+    load_current_index_checked, load_instruction_at_checked, ID as INSTRUCTIONS_SYSVAR_ID,
+};
 
 declare_id!("7LbBHo3GD4ZJDiGAuK3uZKEzhvKXFhuJ4UFiZP1T7tJ7");
 
 const ESCROW_PDA_SEED: &[u8] = b"escrow";
+const CONFIG_SEED: &[u8] = b"config";
+const MINT_STATS_SEED: &[u8] = b"mint-stats";
+const SERVICE_ESCROW_SEED: &[u8] = b"service";
+const MILESTONE_ESCROW_SEED: &[u8] = b"milestone";
+const AUCTION_SEED: &[u8] = b"auction";
+const BID_SEED: &[u8] = b"bid";
+const COUNTER_OFFER_SEED: &[u8] = b"counter-offer";
+const TAKER_VAULT_SEED: &[u8] = b"taker-vault";
+const BASKET_ESCROW_SEED: &[u8] = b"basket";
+const ALLOWLIST_FILL_SEED: &[u8] = b"allowlist-fill";
+const ORDER_DELEGATE_SEED: &[u8] = b"order-delegate";
+const ORDER_NONCE_SEED: &[u8] = b"order-nonce";
+const UNWRAP_SEED: &[u8] = b"unwrap";
+
+// Prefix of every signed order message, so an order signature is never valid for anything else
+const SIGNED_ORDER_DOMAIN: &[u8] = b"escrow-signed-order";
+
+// Upper bound on milestones per escrow, which fixes the account size
+const MAX_MILESTONES: usize = 10;
+
+// Upper bound on offered and on requested legs per basket escrow, which fixes the account size
+const MAX_BASKET_LEGS: usize = 5;
+
+// Hard upper bound for the protocol fee, whatever the admin configures
+const MAX_FEE_BPS: u16 = 500; // 5%
+const BPS_DENOMINATOR: u64 = 10_000;
+
+// Duration bounds a new `Config` starts with; the admin can change them with `set_duration_bounds`
+const DEFAULT_MIN_ESCROW_DURATION_SECONDS: i64 = 60; // 1 minute
+const DEFAULT_MAX_ESCROW_DURATION_SECONDS: i64 = 365 * 24 * 60 * 60; // 1 year
+
+pub mod error;
+pub mod events;
+mod helpers;
+pub mod instructions;
+pub mod state;
+
+pub use error::ErrorCode;
+pub use events::*;
+use helpers::*;
+pub use helpers::{allowlist_leaf, verify_allowlist_proof};
+pub use instructions::*;
+pub use state::*;
 
-#[event]
-pub struct InitializeEvent {
-    pub initializer_key: String,
-    pub initializer_deposit_token_account: String,
-    pub initializer_deposit_token_mint: String,
-    pub taker_expected_token_mint: String,
-    pub initializer_amount:String,
-    pub taker_expected_amount: String,
-    pub initializer_receive_token_account: String,
-    pub unique_seed: [u8; 8],
-    pub expires_at: i64,
-    pub bump: u8,
-    pub escrow_pda: String,
-    pub vault_account: String,
-}
+#[program]
+pub mod escrow {
+    use super::*;
 
-#[event]
-pub struct ExchangeExecuted {
-    pub initializer: Pubkey,
-    pub taker: Pubkey,
-    pub initializer_deposit_token_mint: Pubkey,
-    pub taker_expected_token_mint: Pubkey,
-    pub initializer_amount: u64,
-    pub taker_expected_amount: u64,
-    pub unique_seed: [u8; 8],
-    pub timestamp: i64,
-}
+    /// Creates the program-wide `GlobalStats` PDA with every counter at zero.
+    pub fn initialize_global_stats(ctx: Context<InitializeGlobalStats>) -> Result<()> {
+        instructions::admin::initialize_global_stats(ctx)
+    }
 
-#[event]
-pub struct EscrowCanceled {
-    pub initializer: Pubkey,
-    pub initializer_deposit_token_mint: Pubkey,
-    pub canceled_amount: u64,
-    pub unique_seed: [u8; 8],
-    pub timestamp: i64,
-}
+    /// Creates the protocol fee `Config` PDA; the signer becomes its admin.
+    pub fn initialize_config(
+        ctx: Context<InitializeConfig>,
+        treasury: Pubkey,
+        fee_bps: u16,
+        charge_taker_side: bool,
+        charge_initializer_side: bool,
+    ) -> Result<()> {
+        instructions::admin::initialize_config(ctx, treasury, fee_bps, charge_taker_side, charge_initializer_side)
+    }
 
-// Helper function to check if the provided Pubkey is a valid token program ID
-fn check_token_program_id(program_id: &Pubkey) -> Result<()> {
-    if program_id.eq(&TOKEN_PROGRAM_ID) || program_id.eq(&TOKEN_2022_PROGRAM_ID) {
-        Ok(())
-    } else {
-        Err(ErrorCode::InvalidTokenProgram.into())
+    /// Admin-only: changes the protocol fee settings, capped at `MAX_FEE_BPS`.
+    pub fn update_config(
+        ctx: Context<UpdateConfig>,
+        treasury: Pubkey,
+        fee_bps: u16,
+        charge_taker_side: bool,
+        charge_initializer_side: bool,
+    ) -> Result<()> {
+        instructions::admin::update_config(ctx, treasury, fee_bps, charge_taker_side, charge_initializer_side)
+    }
+
+    /// Admin-only: pauses or resumes `initialize` and `exchange`.
+    /// `cancel` and `refund_expired` stay open so users can always exit.
+    pub fn set_paused(ctx: Context<UpdateConfig>, paused: bool) -> Result<()> {
+        instructions::admin::set_paused(ctx, paused)
+    }
+
+    /// Admin-only: sets the range of durations accepted for new escrows and deadlines.
+    pub fn set_duration_bounds(
+        ctx: Context<UpdateConfig>,
+        min_escrow_duration_seconds: i64,
+        max_escrow_duration_seconds: i64,
+    ) -> Result<()> {
+        instructions::admin::set_duration_bounds(ctx, min_escrow_duration_seconds, max_escrow_duration_seconds)
+    }
+
+    /// Admin-only: first step of an authority rotation. Proposing again replaces
+    /// the pending admin; nothing changes until they call `accept_authority`.
+    pub fn propose_authority(ctx: Context<UpdateConfig>, proposed_admin: Pubkey) -> Result<()> {
+        instructions::admin::propose_authority(ctx, proposed_admin)
+    }
+
+    /// Second step of an authority rotation, signed by the proposed admin.
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        instructions::admin::accept_authority(ctx)
+    }
+
+    /// Grows `Config` and `GlobalStats` accounts created before fields were added to them.
+    /// Gated on the upgrade authority, as the legacy `Config` cannot be read as one.
+    /// Escrows opened before partial fills are migrated too when passed in
+    /// `remaining_accounts` as (escrow, vault, Token B mint) triples.
+    pub fn migrate_accounts<'info>(ctx: Context<'_, '_, 'info, 'info, MigrateAccounts<'info>>) -> Result<()> {
+        instructions::admin::migrate_accounts(ctx)
     }
-}
 
-#[program]
-pub mod escrow {
-    use super::*;
     /// Initializes the escrow state account and transfers Token A from the seller
     /// to the PDA-owned temporary token account (vault).
-    pub fn initialize_global_stats(ctx: Context<InitializeGlobalStats>) -> Result<()> {
-    let stats = &mut ctx.accounts.global_stats;
-    stats.total_escrows_created = 0;
-    stats.total_escrows_completed = 0;
-    stats.total_escrows_canceled = 0;
-    stats.total_value_locked = 0;
-    stats.total_value_released = 0;
-    stats.bump = ctx.bumps.global_stats;
-    Ok(())
-}
-    pub fn initialize(
-        ctx: Context<Initialize>,
+    #[allow(clippy::too_many_arguments)]
+    pub fn initialize<'info>(
+        ctx: Context<'_, '_, '_, 'info, Initialize<'info>>,
         initializer_amount: u64,
         taker_expected_amount: u64,
         duration_in_seconds: i64,
-        unique_seed: [u8; 8], // ← FIX: Added missing argument
+        unique_seed: [u8; 8],
+        allowed_taker: Option<Pubkey>,
+        taker_pays_transfer_fee: bool,
+        deposit_asset: AssetKind,
+        receive_asset: AssetKind,
+        dutch_auction: Option<DutchAuctionTerms>,
+        collateral: Option<CollateralTerms>,
+        nft: Option<NftTerms>,
+        allowlist_root: Option<[u8; 32]>,
     ) -> Result<()> {
-        check_token_program_id(&ctx.accounts.token_program.key)?;
-        // 1. Set the Escrow State data
-        let escrow_account = &mut ctx.accounts.escrow_state;
-        escrow_account.initializer_key = *ctx.accounts.initializer.key;
-       let clock = Clock::get()?.unix_timestamp;
-
-        let expires_at = clock
-            .checked_add(duration_in_seconds) 
-            .ok_or(ErrorCode::Overflow)?;
-        escrow_account.expires_at = expires_at; 
-        escrow_account.initializer_deposit_token_mint = ctx
-            .accounts
-            .initializer_deposit_token_mint
-            .key();  // ← FIXED: Call key()
-        escrow_account.initializer_deposit_token_account = ctx.accounts.initializer_deposit_token_account.key();
-        escrow_account.taker_expected_token_mint =
-            ctx.accounts.taker_expected_token_mint.key();  // ← FIXED: Call key()
-        escrow_account.initializer_amount = initializer_amount;
-        escrow_account.taker_expected_amount = taker_expected_amount;
-        escrow_account.initializer_receive_token_account = ctx
-            .accounts
-            .initializer_receive_token_account
-            .key();  // ← FIXED: Call key()
-        escrow_account.unique_seed = unique_seed;  // ← FIXED: Save unique seed
-        escrow_account.bump = ctx.bumps.escrow_state;
-
-        let cpi_accounts = TransferChecked {
-            from: ctx.accounts.initializer_deposit_token_account.to_account_info(),
-            mint: ctx.accounts.initializer_deposit_token_mint.to_account_info(),
-            to: ctx.accounts.vault_account.to_account_info(),
-            authority: ctx.accounts.initializer.to_account_info(),
-        };
-        let cpi_program = ctx.accounts.token_program.to_account_info();
-        let cpi_context = CpiContext::new(cpi_program, cpi_accounts);
-
-        token_interface::transfer_checked(
-            cpi_context,
-            initializer_amount,
-            ctx.accounts.initializer_deposit_token_mint.decimals,
-        )?;
-        let global_stats = &mut ctx.accounts.global_stats;
-            global_stats.total_escrows_created = global_stats.total_escrows_created.checked_add(1).unwrap();
-            global_stats.total_value_locked = global_stats
-                .total_value_locked
-                .checked_add(initializer_amount)
-                .unwrap();
-
-    // Optional: Track unique active users
-    // global_stats.last_active_user = ctx.accounts.initializer.key();
-       emit!(InitializeEvent {
-        initializer_key: ctx.accounts.initializer.key().to_string(),
-        initializer_deposit_token_account: ctx.accounts.initializer_deposit_token_account.key().to_string(),
-        initializer_deposit_token_mint: ctx.accounts.initializer_deposit_token_mint.key().to_string(),
-        taker_expected_token_mint: ctx.accounts.taker_expected_token_mint.key().to_string(),
-        initializer_amount: initializer_amount.to_string(),
-        taker_expected_amount: taker_expected_amount.to_string(),
-        initializer_receive_token_account: ctx.accounts.initializer_receive_token_account.key().to_string(),
-        unique_seed,
-        expires_at,
-        bump: ctx.bumps.escrow_state,
-        escrow_pda: ctx.accounts.escrow_state.key().to_string(),
-        vault_account: ctx.accounts.vault_account.key().to_string(),
-    });
-
-        Ok(())
+        instructions::swap::initialize(ctx, initializer_amount, taker_expected_amount, duration_in_seconds, unique_seed, allowed_taker, taker_pays_transfer_fee, deposit_asset, receive_asset, dutch_auction, collateral, nft, allowlist_root)
     }
 
     /// Allows the Taker (buyer) to exchange their Token B for the Initializer's Token A.
-    pub fn exchange(ctx: Context<Exchange>) -> Result<()> {
-        check_token_program_id(&ctx.accounts.token_program.key())?;
-
-        let escrow_state = &ctx.accounts.escrow_state;
-
-        // --- CPI 1: Taker sends Token B to Initializer ---
-        {
-            let cpi_accounts_taker_transfer = TransferChecked {
-                from: ctx.accounts.taker_deposit_token_account.to_account_info(),
-                mint: ctx.accounts.taker_expected_mint.to_account_info(),
-                to: ctx.accounts.initializer_receive_token_account.to_account_info(),
-                authority: ctx.accounts.taker.to_account_info(),
-            };
-
-            let cpi_program = ctx.accounts.token_program.to_account_info();
-            let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts_taker_transfer);
-
-            token_interface::transfer_checked(
-                cpi_ctx,
-                escrow_state.taker_expected_amount,
-                ctx.accounts.taker_expected_mint.decimals,
-            )?;
-        }
-
-        // --- CPI 2: Escrow PDA sends Token A to Taker ---
-        {
-            let authority_seeds = &[
-                ESCROW_PDA_SEED,
-                escrow_state.initializer_key.as_ref(),
-                escrow_state.unique_seed.as_ref(),
-                &[escrow_state.bump],
-            ];
-            let signer_seeds = &[&authority_seeds[..]];
-
-            let cpi_accounts_initializer_transfer = TransferChecked {
-                from: ctx.accounts.vault_account.to_account_info(),
-                mint: ctx.accounts.initializer_deposit_mint.to_account_info(),
-                to: ctx.accounts.taker_receive_token_account.to_account_info(),
-                authority: ctx.accounts.escrow_state.to_account_info(),
-            };
-
-            let cpi_program = ctx.accounts.token_program.to_account_info();
-            let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts_initializer_transfer, signer_seeds);
-
-            token_interface::transfer_checked(
-                cpi_ctx,
-                escrow_state.initializer_amount,
-                ctx.accounts.initializer_deposit_mint.decimals,
-            )?;
-        }
-
-        // --- CPI 3: Close Vault ---
-        {
-            let authority_seeds = &[
-                ESCROW_PDA_SEED,
-                escrow_state.initializer_key.as_ref(),
-                escrow_state.unique_seed.as_ref(),
-                &[escrow_state.bump],
-            ];
-            let signer_seeds = &[&authority_seeds[..]];
-
-            let cpi_accounts_close = CloseAccount {
-                account: ctx.accounts.vault_account.to_account_info(),
-                destination: ctx.accounts.taker.to_account_info(),
-                authority: ctx.accounts.escrow_state.to_account_info(),
-            };
-
-            let cpi_program = ctx.accounts.token_program.to_account_info();
-            let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts_close, signer_seeds);
-
-            token_interface::close_account(cpi_ctx)?;
-        }
-        {
-            let global_stats = &mut ctx.accounts.global_stats;
-
-            // Increment total completed escrows
-            global_stats.total_escrows_completed = global_stats
-                .total_escrows_completed
-                .checked_add(1)
-                .unwrap();
-
-            // Add to total value released
-            global_stats.total_value_released = global_stats
-                .total_value_released
-                .checked_add(escrow_state.initializer_amount)
-                .unwrap();
-
-            // Subtract from TVL (since escrow funds are released)
-            if global_stats.total_value_locked >= escrow_state.initializer_amount {
-                global_stats.total_value_locked -= escrow_state.initializer_amount;
-            } else {
-                global_stats.total_value_locked = 0;
-            }
-
-                // Optionally track the most recent successful trade pair
-        }
-        // --- Emit Event ---
-        emit!(ExchangeExecuted {
-            initializer: escrow_state.initializer_key,
-            taker: ctx.accounts.taker.key(),
-            initializer_deposit_token_mint: escrow_state.initializer_deposit_token_mint,
-            taker_expected_token_mint: escrow_state.taker_expected_token_mint,
-            initializer_amount: escrow_state.initializer_amount,
-            taker_expected_amount: escrow_state.taker_expected_amount,
-            unique_seed: escrow_state.unique_seed,
-            timestamp: Clock::get()?.unix_timestamp,
-        });
-
-        Ok(())
+    /// `fill_amount` is the amount of Token B paid; a proportional slice of Token A is
+    /// released, and the escrow stays open until `taker_expected_amount` has been filled.
+    /// Mints with a TransferHook take their extra accounts from `remaining_accounts`.
+    /// Slippage guard: the fill aborts unless the taker receives at least
+    /// `expected_initializer_amount` of Token A, pays at most `max_taker_amount` of Token B,
+    /// and the escrow trades the mints the taker expects.
+    /// Native SOL legs move lamports: a native Token B is paid straight to the Initializer's
+    /// wallet, and a native Token A is unwrapped into the taker's wallet.
+    /// Dutch auctions are priced from `Clock` (see `quote`) and must be filled in one go.
+    /// Allowlisted escrows need `allowlist_proof` for the taker's leaf; a leaf with a
+    /// `wallet_cap` limits the Token B that wallet pays across fills, tracked in
+    /// `allowlist_fill`. Both are ignored on escrows without an allowlist.
+    #[allow(clippy::too_many_arguments)]
+    pub fn exchange<'info>(
+        ctx: Context<'_, '_, '_, 'info, Exchange<'info>>,
+        fill_amount: u64,
+        expected_initializer_amount: u64,
+        max_taker_amount: u64,
+        expected_initializer_mint: Pubkey,
+        expected_taker_mint: Pubkey,
+        allowlist_proof: Vec<[u8; 32]>,
+        wallet_cap: Option<u64>,
+    ) -> Result<()> {
+        instructions::swap::exchange(ctx, fill_amount, expected_initializer_amount, max_taker_amount, expected_initializer_mint, expected_taker_mint, allowlist_proof, wallet_cap)
     }
 
+    /// Fills several escrows on the same pair in one instruction, all or nothing. Remaining
+    /// accounts start with one (escrow_state, vault, initializer_receive_token_account)
+    /// triple per entry of `fill_amounts`; the initializers' Token A accounts (for rounding
+    /// dust on final fills) and transfer hook accounts follow, looked up by key. Each escrow
+    /// is checked as in `exchange`, and the Token B paid across the batch, fees included,
+    /// is capped by `max_total_taker_amount`. Native SOL and allowlisted escrows are not
+    /// batchable.
+    pub fn exchange_many<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ExchangeMany<'info>>,
+        fill_amounts: Vec<u64>,
+        expected_total_initializer_amount: u64,
+        max_total_taker_amount: u64,
+    ) -> Result<()> {
+        instructions::swap::exchange_many(ctx, fill_amounts, expected_total_initializer_amount, max_total_taker_amount)
+    }
 
     /// Allows the Initializer (Seller) to cancel the escrow and retrieve their Token A.
-            pub fn cancel(ctx: Context<Cancel>) -> Result<()> {
-            check_token_program_id(&ctx.accounts.token_program.key())?;
-
-            let escrow_state = &ctx.accounts.escrow_state;
-
-            // --- CPI 1: Return Token A from the vault to the Initializer ---
-            let authority_seeds = &[
-                ESCROW_PDA_SEED,
-                escrow_state.initializer_key.as_ref(),
-                escrow_state.unique_seed.as_ref(),
-                &[escrow_state.bump],
-            ];
-            let signer_seeds = &[&authority_seeds[..]];
-
-            let cpi_accounts_transfer = TransferChecked {
-                from: ctx.accounts.vault_account.to_account_info(),
-                mint: ctx.accounts.initializer_deposit_mint.to_account_info(),
-                to: ctx.accounts.initializer_deposit_token_account.to_account_info(),
-                authority: ctx.accounts.escrow_state.to_account_info(),
-            };
-            let cpi_program = ctx.accounts.token_program.to_account_info();
-            let cpi_context_transfer =
-                CpiContext::new_with_signer(cpi_program, cpi_accounts_transfer, signer_seeds);
-
-            token_interface::transfer_checked(
-                cpi_context_transfer,
-                escrow_state.initializer_amount,
-                ctx.accounts.initializer_deposit_mint.decimals,
-            )?;
-
-            // --- CPI 2: Close the PDA-owned Vault Account ---
-            let cpi_accounts_close = CloseAccount {
-                account: ctx.accounts.vault_account.to_account_info(),
-                destination: ctx.accounts.initializer.to_account_info(), // Refund rent to Initializer
-                authority: ctx.accounts.escrow_state.to_account_info(),  // PDA signs the close
-            };
-            let cpi_program = ctx.accounts.token_program.to_account_info();
-            let cpi_context_close =
-                CpiContext::new_with_signer(cpi_program, cpi_accounts_close, signer_seeds);
-
-            token_interface::close_account(cpi_context_close)?;
-            {
-                let global_stats = &mut ctx.accounts.global_stats;
-
-                // Use checked arithmetic with manual error handling
-                global_stats.total_escrows_canceled = global_stats
-                    .total_escrows_canceled
-                    .checked_add(1)
-                    .ok_or_else(|| error!(ErrorCode::NumericalOverflow))?;
-
-                global_stats.total_value_released = global_stats
-                    .total_value_released
-                    .checked_add(escrow_state.initializer_amount)
-                    .ok_or_else(|| error!(ErrorCode::NumericalOverflow))?;
-            }
-
-            // --- Emit Event ---
-            emit!(EscrowCanceled {
-                initializer: escrow_state.initializer_key,
-                initializer_deposit_token_mint: escrow_state.initializer_deposit_token_mint,
-                canceled_amount: escrow_state.initializer_amount,
-                unique_seed: escrow_state.unique_seed,
-                timestamp: Clock::get()?.unix_timestamp,
-            });
-
-            Ok(())
-        }
+    pub fn cancel<'info>(ctx: Context<'_, '_, '_, 'info, Cancel<'info>>) -> Result<()> {
+        instructions::swap::cancel(ctx)
+    }
 
-}
+    /// Permissionless crank: once the escrow has expired, anyone can return Token A
+    /// to the Initializer. Rent goes back to the Initializer, not the caller.
+    /// An accepted two-sided escrow that never became settleable is unwound: the taker
+    /// gets their Token B and the taker vault rent back.
+    pub fn refund_expired<'info>(ctx: Context<'_, '_, '_, 'info, RefundExpired<'info>>) -> Result<()> {
+        instructions::swap::refund_expired(ctx)
+    }
 
-// ----------------------------------------------------------------
-// STATE
-// ----------------------------------------------------------------
+    /// Allows the Initializer (Seller) to reprice, extend/shorten, top up or partially
+    /// withdraw an open escrow. Amending rebases the escrow on the Token A left in the
+    /// vault: `new_taker_expected_amount` is the ask for that remainder (by default the
+    /// unfilled part of the current ask is kept).
+    /// Native SOL top-ups are wrapped into the vault; withdrawals from a native vault land
+    /// as wSOL in the initializer's deposit account, which fills and refunds still reference.
+    pub fn amend_escrow<'info>(
+        ctx: Context<'_, '_, '_, 'info, AmendEscrow<'info>>,
+        new_taker_expected_amount: Option<u64>,
+        new_expires_at: Option<i64>,
+        top_up_amount: u64,
+        withdraw_amount: u64,
+    ) -> Result<()> {
+        instructions::swap::amend_escrow(ctx, new_taker_expected_amount, new_expires_at, top_up_amount, withdraw_amount)
+    }
 
-/// Defines the data stored in the Escrow State PDA account.
-#[account]
-pub struct EscrowState {
-    // The Pubkey of the user who initiated the escrow (Seller)
-    pub initializer_key: Pubkey,
-    pub initializer_deposit_token_account: Pubkey, // <-- ADD THIS
+    /// Read-only: returns (as return data) the Token B a taker would pay right now to fill
+    /// the rest of the escrow, before protocol and transfer fees. For Dutch auctions this
+    /// is the live price; for fixed-price escrows it is simply what is left to fill.
+    pub fn quote(ctx: Context<Quote>) -> Result<u64> {
+        instructions::swap::quote(ctx)
+    }
 
-    // The Mint of the token the initializer is offering (Token A)
-    pub initializer_deposit_token_mint: Pubkey,
+    /// Creates a one-sided service escrow: the buyer locks `amount` of a token for a seller
+    /// who delivers off-chain, and names an `arbiter` who alone can settle a dispute.
+    /// The buyer has `review_period_seconds` to release or dispute; after that anyone may
+    /// release to the seller. An arbiter who does not rule within `dispute_timeout_seconds`
+    /// of a dispute loses the right to, and the buyer can then be refunded.
+    pub fn create_service_escrow<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateServiceEscrow<'info>>,
+        amount: u64,
+        unique_seed: [u8; 8],
+        review_period_seconds: i64,
+        dispute_timeout_seconds: i64,
+    ) -> Result<()> {
+        instructions::service::create_service_escrow(ctx, amount, unique_seed, review_period_seconds, dispute_timeout_seconds)
+    }
 
-    // The Mint of the token the initializer expects to receive (Token B)
-    pub taker_expected_token_mint: Pubkey,
+    /// Pays the whole vault to the seller. The buyer may release at any time while the
+    /// escrow is funded and undisputed; once the review period is over, anyone may.
+    pub fn release<'info>(ctx: Context<'_, '_, '_, 'info, Release<'info>>) -> Result<()> {
+        instructions::service::release(ctx)
+    }
 
-    // The amount of Token A that the initializer deposited
-    pub initializer_amount: u64,
+    /// Either party can freeze a funded escrow for the arbiter, until the review period ends.
+    pub fn raise_dispute(ctx: Context<RaiseDispute>) -> Result<()> {
+        instructions::service::raise_dispute(ctx)
+    }
 
-    // The amount of Token B that the taker is expected to deposit
-    pub taker_expected_amount: u64,
+    /// Settles a dispute by splitting the vault: `seller_amount` to the seller and the rest
+    /// back to the buyer. Only the arbiter may rule until `dispute_deadline`; after it,
+    /// anyone may close the dispute, which refunds the buyer in full.
+    pub fn resolve_dispute<'info>(
+        ctx: Context<'_, '_, '_, 'info, ResolveDispute<'info>>,
+        seller_amount: u64,
+    ) -> Result<()> {
+        instructions::service::resolve_dispute(ctx, seller_amount)
+    }
 
-    // The token account where the initializer expects to receive Token B
-    pub initializer_receive_token_account: Pubkey,
+    /// Creates an escrow whose deposit is split into milestones, each with an amount and
+    /// an optional deadline. The payer approves milestones one at a time; a pending
+    /// milestone past its deadline can be refunded to the payer by anyone.
+    pub fn create_milestone_escrow<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateMilestoneEscrow<'info>>,
+        unique_seed: [u8; 8],
+        milestones: Vec<MilestoneTerms>,
+    ) -> Result<()> {
+        instructions::milestone::create_milestone_escrow(ctx, unique_seed, milestones)
+    }
 
-    // The unique 8-byte seed used to derive this PDA
-    pub unique_seed: [u8; 8], // ← FIXED: Added unique seed to state
-    pub expires_at: i64,
+    /// Payer-only: pays milestone `index` to the payee. Approving the last open
+    /// milestone closes the vault and the escrow, returning rent (and any dust) to the payer.
+    pub fn approve_milestone<'info>(
+        ctx: Context<'_, '_, '_, 'info, SettleMilestone<'info>>,
+        index: u8,
+    ) -> Result<()> {
+        instructions::milestone::approve_milestone(ctx, index)
+    }
 
-    // The canonical bump seed for the EscrowState PDA
-    pub bump: u8,
-}
+    /// Permissionless crank: refunds pending milestone `index` to the payer once its
+    /// deadline has passed. Milestones without a deadline can only be approved.
+    pub fn refund_milestone<'info>(
+        ctx: Context<'_, '_, '_, 'info, SettleMilestone<'info>>,
+        index: u8,
+    ) -> Result<()> {
+        instructions::milestone::refund_milestone(ctx, index)
+    }
 
-// Space calculation: 
-// 8 (discriminator) + 32*4 (Pubkeys) + 8*2 (u64 amounts) + 8 (unique_seed) + 1 (bump) = 161 bytes.
-const ESCROW_ACCOUNT_SPACE: usize = 201; 
-
-// ----------------------------------------------------------------
-// ACCOUNT STRUCTS
-// ----------------------------------------------------------------
-#[account]
-pub struct GlobalStats {
-    pub total_escrows_created: u64,
-    pub total_escrows_completed: u64,
-    pub total_escrows_canceled: u64,
-    pub total_value_locked: u64,
-    pub total_value_released: u64,
-    pub bump: u8,
-}
-#[derive(Accounts)]
-pub struct InitializeGlobalStats<'info> {
-    #[account(mut)]
-    pub admin: Signer<'info>,  
-    #[account(
-        init,
-        seeds = [b"global-stats"],
-        bump,
-        payer = admin,
-        space = 8 + 8*5 + 1, 
-    )]
-    pub global_stats: Account<'info, GlobalStats>,
-
-    pub system_program: Program<'info, System>,
-}
+    /// Locks `amount` of a token for a beneficiary who can claim it linearly between
+    /// `start_time` and `end_time`, with nothing claimable before `cliff_time`. When
+    /// `revocable` is set, the initializer can take back the unvested remainder.
+    pub fn create_vesting_escrow<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateVestingEscrow<'info>>,
+        amount: u64,
+        unique_seed: [u8; 8],
+        start_time: i64,
+        cliff_time: i64,
+        end_time: i64,
+        revocable: bool,
+    ) -> Result<()> {
+        instructions::vesting::create_vesting_escrow(ctx, amount, unique_seed, start_time, cliff_time, end_time, revocable)
+    }
 
-/// Accounts for the `initialize` instruction
-#[derive(Accounts)]
-#[instruction(
-    initializer_amount: u64, // ← FIXED: Corrected to match function args
-    taker_expected_amount: u64,
-     duration_in_seconds:i64,
-    unique_seed: [u8; 8] // ← FIXED: Added unique seed arg
-)] 
-pub struct Initialize<'info> {
-    /// The user creating the escrow (Seller). Must sign the transaction.
-    #[account(mut)]
-    pub initializer: Signer<'info>,
-
-    /// The token account belonging to the initializer that holds Token A (the token being offered).
-    /// Initializer's tokens will be transferred from here.
-    #[account(
-        mut, 
-        constraint = initializer_deposit_token_account.amount >= initializer_amount @ErrorCode::InsufficientFunds,
-        token::token_program = token_program
-    )]
-    pub initializer_deposit_token_account: InterfaceAccount<'info, TokenAccount>,  // ← FIXED: InterfaceAccount for dynamic
-
-    /// The Mint account for Token A (the token being offered). Used for cross-checking.
-    pub initializer_deposit_token_mint: InterfaceAccount<'info, Mint>,  // ← FIXED: InterfaceAccount
-
-    /// The Mint account for Token B (the token the seller expects in return). Used for cross-checking.
-    pub taker_expected_token_mint: InterfaceAccount<'info, Mint>,  // ← FIXED: InterfaceAccount
-
-    /// The Initializer's account where they will receive the Token B if the trade is completed.
-    /// This account must already exist.
-    #[account(
-        mut, 
-        constraint = initializer_receive_token_account.owner == initializer.key() @ErrorCode::InvalidOwner,
-        token::token_program = token_program
-    )]
-    pub initializer_receive_token_account: InterfaceAccount<'info, TokenAccount>,  // ← FIXED: InterfaceAccount
-    #[account(
-            mut,
-            seeds = [b"global-stats"], 
-            bump = global_stats.bump
-        )]
-    pub global_stats: Account<'info, GlobalStats>, // ← new addition
-    /// The Escrow State PDA account. Stores the details of the trade.
-    #[account(
-        init,
-        seeds = [ESCROW_PDA_SEED, initializer.key().as_ref(), unique_seed.as_ref()],  // ← FIXED: All seeds
-        bump,
-        payer = initializer,
-        space = ESCROW_ACCOUNT_SPACE,
-    )]
-    pub escrow_state: Account<'info, EscrowState>,
-
-    /// The PDA-owned token account (Vault) that will hold the Initializer's deposited tokens (Token A).
-    #[account(
-        init,
-        token::mint = initializer_deposit_token_mint,
-        token::authority = escrow_state,
-        token::token_program = token_program, 
-        payer = initializer,
-        seeds = [b"vault", escrow_state.key().as_ref()],  // ← FIXED: PDA for vault
-        bump
-    )]
-    pub vault_account: InterfaceAccount<'info, TokenAccount>,  // ← FIXED: InterfaceAccount
-
-    pub system_program: Program<'info, System>,
-
-    pub token_program: Interface<'info, TokenInterface>,  // ← FIXED: Interface for dynamic
-
-    pub rent: Sysvar<'info, Rent>,
-}
+    /// Beneficiary-only: pays out everything vested so far and not yet claimed. The claim
+    /// that empties the vault closes it and the escrow, returning rent to the initializer.
+    pub fn claim_vested<'info>(ctx: Context<'_, '_, '_, 'info, ClaimVested<'info>>) -> Result<()> {
+        instructions::vesting::claim_vested(ctx)
+    }
 
-/// Accounts for the `exchange` instruction
-#[derive(Accounts)]
-pub struct Exchange<'info> {
-    /// Buyer (taker) — must sign
-    #[account(mut)]
-    pub taker: Signer<'info>,
-
-    /// Taker's account holding Token B (the one they're offering)
-    #[account(
-        mut,
-        constraint = taker_deposit_token_account.mint == escrow_state.taker_expected_token_mint @ErrorCode::InvalidMint,
-        token::token_program = token_program
-    )]
-    pub taker_deposit_token_account: InterfaceAccount<'info, TokenAccount>,
-
-    /// Taker's account receiving Token A
-    #[account(
-        mut,
-        constraint = taker_receive_token_account.mint == escrow_state.initializer_deposit_token_mint @ErrorCode::InvalidMint,
-        token::token_program = token_program
-    )]
-    pub taker_receive_token_account: InterfaceAccount<'info, TokenAccount>,
-
-    /// Initializer's receiving account for Token B
-    #[account(
-        mut,
-        constraint = initializer_receive_token_account.key() == escrow_state.initializer_receive_token_account @ErrorCode::InvalidAccount,
-        token::token_program = token_program
-    )]
-    pub initializer_receive_token_account: InterfaceAccount<'info, TokenAccount>,
-
-    /// PDA holding the escrow state
-    #[account(
-        mut,
-        seeds = [ESCROW_PDA_SEED, escrow_state.initializer_key.as_ref(), escrow_state.unique_seed.as_ref()],
-        bump = escrow_state.bump,
-        close = taker,
-        has_one = initializer_key,
-    )]
-    pub escrow_state: Account<'info, EscrowState>,
-
-    /// PDA vault that holds Token A
-    #[account(
-        mut,
-        token::authority = escrow_state,
-        token::token_program = token_program
-    )]
-    pub vault_account: InterfaceAccount<'info, TokenAccount>,
-    #[account(
-            mut,
-            seeds = [b"global-stats"],
-            bump = global_stats.bump
-        )]
-    pub global_stats: Account<'info, GlobalStats>,
-    /// Mint for Token A (initializer’s deposited token)
-    #[account(
-        token::token_program = token_program
-    )]
-    pub initializer_deposit_mint: InterfaceAccount<'info, Mint>,
-    #[account(
-            token::token_program = token_program
-        )]
-    /// Mint for Token B (taker’s offered token)
-    pub taker_expected_mint: InterfaceAccount<'info, Mint>,
-
-    /// Just for validation (has_one)
-    /// CHECK: Used only for matching initializer
-    pub initializer_key: AccountInfo<'info>,
-
-    pub token_program: Interface<'info, TokenInterface>,
-}
+    /// Initializer-only, for revocable escrows: pays the beneficiary whatever has vested
+    /// but not been claimed, refunds the unvested remainder and closes the escrow.
+    pub fn revoke_vesting<'info>(ctx: Context<'_, '_, '_, 'info, RevokeVesting<'info>>) -> Result<()> {
+        instructions::vesting::revoke_vesting(ctx)
+    }
 
+    /// Puts `amount` of Token A up for an English auction paid in `bid_mint`. Bids must
+    /// meet `reserve_price` and beat the leader by at least `min_increment`; after
+    /// `duration_in_seconds` anyone can settle the auction.
+    pub fn create_auction<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateAuction<'info>>,
+        amount: u64,
+        unique_seed: [u8; 8],
+        reserve_price: u64,
+        min_increment: u64,
+        duration_in_seconds: i64,
+    ) -> Result<()> {
+        instructions::auction::create_auction(ctx, amount, unique_seed, reserve_price, min_increment, duration_in_seconds)
+    }
 
-/// Accounts for the `cancel` instruction
-#[derive(Accounts)]
-pub struct Cancel<'info> {
-    /// The user who initialized the escrow (Seller)
-    #[account(mut)]
-    pub initializer: Signer<'info>,
-
-    /// The Initializer's token account to refund Token A into
-    #[account(
-        mut, 
-        constraint = initializer_deposit_token_account.owner == initializer.key() @ErrorCode::InvalidOwner,
-        token::token_program = token_program
-    )]
-    pub initializer_deposit_token_account: InterfaceAccount<'info, TokenAccount>,
-
-    /// PDA-owned vault holding Token A
-    #[account(
-        mut,
-        token::authority = escrow_state,
-        token::token_program = token_program
-    )]
-    pub vault_account: InterfaceAccount<'info, TokenAccount>,
-
-    /// Mint of the deposited token (Token A)
-    pub initializer_deposit_mint: InterfaceAccount<'info, Mint>,
-
-    /// Escrow state PDA
-    #[account(
-        mut,
-        seeds = [ESCROW_PDA_SEED, initializer.key().as_ref(), escrow_state.unique_seed.as_ref()],
-        bump = escrow_state.bump,
-        close = initializer,
-    )]
-    pub escrow_state: Account<'info, EscrowState>,
- #[account(
-            mut,
-            seeds = [b"global-stats"], 
-            bump = global_stats.bump
-        )]
-    pub global_stats: Account<'info, GlobalStats>,
-    pub token_program: Interface<'info, TokenInterface>,
-}
+    /// Raises the caller's bid to `amount` (a total, not an increment) by escrowing the
+    /// difference in their own bid vault. The previous leader is not paid back here; their
+    /// bid becomes withdrawable through `withdraw_bid`.
+    pub fn place_bid<'info>(
+        ctx: Context<'_, '_, '_, 'info, PlaceBid<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::auction::place_bid(ctx, amount)
+    }
 
+    /// Returns an outbid (or losing) bid to its bidder. Anyone may crank it, so the keeper
+    /// can refund outbid bidders automatically; the leading bid stays locked until settlement.
+    /// The last withdrawal after settlement closes the auction, returning rent to the seller.
+    pub fn withdraw_bid<'info>(ctx: Context<'_, '_, '_, 'info, WithdrawBid<'info>>) -> Result<()> {
+        instructions::auction::withdraw_bid(ctx)
+    }
+
+    /// Permissionless once `expires_at` has passed: swaps the leading bid for the vault
+    /// content, or returns the deposit to the seller if nobody bid. The winner's accounts
+    /// are only needed when there is a winner.
+    pub fn settle_auction<'info>(ctx: Context<'_, '_, '_, 'info, SettleAuction<'info>>) -> Result<()> {
+        instructions::auction::settle_auction(ctx)
+    }
 
-// ----------------------------------------------------------------
-// ERRORS
-// ----------------------------------------------------------------
-
-#[error_code]
-pub enum ErrorCode {
-    #[msg("Arithmetic overflow occurred during calculation")]
-    Overflow,
-    #[msg("Insufficient funds in the initializer's deposit token account.")]
-    InsufficientFunds,
-    #[msg("The provided account does not belong to the correct owner.")]
-    InvalidOwner,
-    #[msg("The token mint in the provided account does not match the expected mint.")]
-    InvalidMint,
-    #[msg("The provided account key does not match the expected account in the escrow state.")]
-    InvalidAccount,
-    #[msg("The taker did not send the exact amount of expected tokens.")]
-    InvalidExchangeAmount,
-    #[msg("The token program provided is neither the Standard SPL Token Program nor the Token-2022 Program.")]
-    InvalidTokenProgram,
-    #[msg("Numerical overflow occurred.")]
-    NumericalOverflow,
-}
\ No newline at end of file
+    /// Lets a taker propose a different price for everything left in an escrow: they
+    /// escrow `taker_amount` of Token B against the `initializer_amount` of Token A they
+    /// expect to receive. The counter-offer lives for `duration_in_seconds`, independently
+    /// of the escrow, and does not touch the escrow's own terms.
+    pub fn make_counter_offer<'info>(
+        ctx: Context<'_, '_, '_, 'info, MakeCounterOffer<'info>>,
+        taker_amount: u64,
+        initializer_amount: u64,
+        duration_in_seconds: i64,
+    ) -> Result<()> {
+        instructions::counter_offer::make_counter_offer(ctx, taker_amount, initializer_amount, duration_in_seconds)
+    }
+
+    /// Initializer-only: settles the escrow at the counter-offer's terms, swapping the
+    /// escrowed Token B for everything left in the vault. Protocol fees apply as in
+    /// `exchange`. Fails if the escrow changed since the offer was made, and aborts unless
+    /// the counter-offer holds at least `min_taker_amount`.
+    pub fn accept_counter_offer<'info>(
+        ctx: Context<'_, '_, '_, 'info, AcceptCounterOffer<'info>>,
+        min_taker_amount: u64,
+    ) -> Result<()> {
+        instructions::counter_offer::accept_counter_offer(ctx, min_taker_amount)
+    }
+
+    /// Returns a counter-offer's Token B to the taker and closes it. The taker may withdraw
+    /// at any time; once the counter-offer has expired anyone may crank it. Works whether
+    /// or not the escrow still exists.
+    pub fn withdraw_counter_offer<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawCounterOffer<'info>>,
+    ) -> Result<()> {
+        instructions::counter_offer::withdraw_counter_offer(ctx)
+    }
+
+    /// Taker side of a two-sided escrow: locks the full Token B price in a taker vault
+    /// next to the initializer's Token A. Nothing is swapped yet; the escrow settles
+    /// once both parties confirm or at its settlement time, and otherwise unwinds at
+    /// expiry through `refund_expired`. The slippage guards mirror `exchange`.
+    pub fn accept<'info>(
+        ctx: Context<'_, '_, '_, 'info, Accept<'info>>,
+        expected_initializer_amount: u64,
+        max_taker_amount: u64,
+    ) -> Result<()> {
+        instructions::collateral::accept(ctx, expected_initializer_amount, max_taker_amount)
+    }
+
+    /// Initializer or taker of an accepted two-sided escrow: confirms that their side of
+    /// the deal is done. Once both have confirmed, anyone can `settle` right away.
+    pub fn confirm_settlement(ctx: Context<ConfirmSettlement>) -> Result<()> {
+        instructions::collateral::confirm_settlement(ctx)
+    }
+
+    /// Permissionless crank for an accepted two-sided escrow that both parties confirmed or
+    /// whose settlement time has come: swaps the two vaults with protocol fees taken from
+    /// each side as in `accept_counter_offer`, then closes both vaults and the escrow.
+    pub fn settle<'info>(ctx: Context<'_, '_, '_, 'info, Settle<'info>>) -> Result<()> {
+        instructions::collateral::settle(ctx)
+    }
+
+    /// Creates a basket escrow: up to `MAX_BASKET_LEGS` offered tokens, each locked in its
+    /// own `[b"vault", basket, mint]` vault, against up to `MAX_BASKET_LEGS` requested
+    /// tokens, all swapped at once by `exchange_basket`. Legs are passed as remaining
+    /// accounts, offered legs first as `[initializer_token_account, mint, vault,
+    /// token_program]`, then requested legs as `[mint, initializer_receive_token_account,
+    /// token_program]`. Transfer-hook accounts, if any, follow the legs.
+    pub fn create_basket_escrow<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateBasketEscrow<'info>>,
+        unique_seed: [u8; 8],
+        offered_amounts: Vec<u64>,
+        requested_amounts: Vec<u64>,
+        duration_in_seconds: i64,
+        allowed_taker: Option<Pubkey>,
+    ) -> Result<()> {
+        instructions::basket::create_basket_escrow(ctx, unique_seed, offered_amounts, requested_amounts, duration_in_seconds, allowed_taker)
+    }
+
+    /// Takes a whole basket escrow atomically: the taker pays every requested leg to the
+    /// initializer and receives every offered leg, with protocol fees charged per leg as in
+    /// `exchange`. Legs are passed as remaining accounts in their stored order, requested
+    /// legs first as `[taker_token_account, mint, initializer_receive_token_account,
+    /// token_program, treasury_token_account]`, then offered legs as `[vault, mint,
+    /// taker_receive_token_account, token_program, treasury_token_account]`. The treasury
+    /// account is only used when that side is charged; pass the program ID otherwise.
+    /// Slippage is guarded per leg: `max_taker_amounts` caps what the taker pays on each
+    /// requested leg and `expected_initializer_amounts` floors what it receives on each
+    /// offered leg, both in stored leg order.
+    pub fn exchange_basket<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExchangeBasket<'info>>,
+        expected_initializer_amounts: Vec<u64>,
+        max_taker_amounts: Vec<u64>,
+    ) -> Result<()> {
+        instructions::basket::exchange_basket(ctx, expected_initializer_amounts, max_taker_amounts)
+    }
+
+    /// Returns every offered leg of a basket escrow to the initializer and closes it. The
+    /// initializer may cancel at any time; once the basket has expired anyone may crank it.
+    /// Offered legs are passed as remaining accounts in their stored order, as `[vault,
+    /// mint, initializer_token_account, token_program]`.
+    pub fn cancel_basket<'info>(ctx: Context<'_, '_, '_, 'info, CancelBasket<'info>>) -> Result<()> {
+        instructions::basket::cancel_basket(ctx)
+    }
+
+    /// Fills a collection bid with any NFT of the requested Token-2022 group: the taker's
+    /// member NFT goes to the initializer's associated token account, and the whole Token A
+    /// deposit, minus the initializer-side protocol fee, goes to the taker. Aborts unless
+    /// the taker receives at least `expected_initializer_amount`.
+    pub fn fill_collection_bid<'info>(
+        ctx: Context<'_, '_, '_, 'info, FillCollectionBid<'info>>,
+        expected_initializer_amount: u64,
+    ) -> Result<()> {
+        instructions::collection_bid::fill_collection_bid(ctx, expected_initializer_amount)
+    }
+
+    /// Fills an order the maker signed off-chain instead of creating an escrow, so listing
+    /// costs the maker no rent. The transaction must carry an Ed25519 program instruction
+    /// for `signed_order_message(order)` right before this one, and the maker must have
+    /// approved the `[ORDER_DELEGATE_SEED, maker]` PDA as delegate for `maker_amount` on
+    /// their Token A account. Orders fill in full, once; the nonce account records it.
+    pub fn fill_signed_order<'info>(
+        ctx: Context<'_, '_, '_, 'info, FillSignedOrder<'info>>,
+        order: SignedOrder,
+        expected_maker_amount: u64,
+    ) -> Result<()> {
+        instructions::signed_order::fill_signed_order(ctx, order, expected_maker_amount)
+    }
+
+    /// Spends a signed order's nonce without filling it. Revoking the delegate approval
+    /// also stops fills, but for every order on that token account at once.
+    pub fn cancel_signed_order(ctx: Context<CancelSignedOrder>, nonce: u64) -> Result<()> {
+        instructions::signed_order::cancel_signed_order(ctx, nonce)
+    }
+}
@@ -0,0 +1,495 @@
+use crate::*;
+
+/// Defines the data stored in the Escrow State PDA account.
+#[account]
+pub struct EscrowState {
+    // The Pubkey of the user who initiated the escrow (Seller)
+    pub initializer_key: Pubkey,
+    pub initializer_deposit_token_account: Pubkey,
+
+    // The Mint of the token the initializer is offering (Token A)
+    pub initializer_deposit_token_mint: Pubkey,
+
+    // The Mint of the token the initializer expects to receive (Token B)
+    pub taker_expected_token_mint: Pubkey,
+
+    // The amount of Token A that the vault received (net of any Token-2022 transfer fee)
+    pub initializer_amount: u64,
+
+    // The amount of Token B that the taker is expected to deposit
+    pub taker_expected_amount: u64,
+
+    // The token account where the initializer expects to receive Token B
+    pub initializer_receive_token_account: Pubkey,
+
+    // The unique 8-byte seed used to derive this PDA
+    pub unique_seed: [u8; 8],
+    pub expires_at: i64,
+
+    // The canonical bump seed for the EscrowState PDA
+    pub bump: u8,
+
+    // The amount of Token A still held in the vault
+    pub remaining_amount: u64,
+
+    // The amount of Token B paid by takers so far
+    pub filled_amount: u64,
+
+    // If set, only this taker may fill the escrow (private / OTC escrow)
+    pub allowed_taker: Option<Pubkey>,
+
+    // The token program owning Token A (SPL Token or Token-2022)
+    pub deposit_token_program: Pubkey,
+
+    // The token program owning Token B (SPL Token or Token-2022)
+    pub receive_token_program: Pubkey,
+
+    // If set, the taker covers Token B transfer fees so the initializer nets the full amount
+    pub taker_pays_transfer_fee: bool,
+
+    // Whether Token A / Token B are SPL tokens or native SOL (wrapped inside the instruction)
+    pub deposit_asset: AssetKind,
+    pub receive_asset: AssetKind,
+
+    // If set, `taker_expected_amount` is the auction's start price and the ask decays to the floor
+    pub dutch_auction: Option<DutchAuction>,
+
+    // `Accepted` once a taker has locked Token B in a two-sided escrow
+    pub status: EscrowStatus,
+
+    // If set, the taker deposits Token B up front and the swap happens at settlement
+    pub collateral: Option<Collateral>,
+
+    // If set, `taker_expected_token_mint` is a Token-2022 group and any member NFT fills the escrow
+    pub collection_bid: bool,
+
+    // If set, only wallets proving membership in this Merkle tree may fill the escrow
+    pub allowlist_root: Option<[u8; 32]>,
+}
+
+/// Lifecycle of an escrow. Only two-sided escrows ever leave `Open`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EscrowStatus {
+    Open,
+    Accepted,
+}
+
+/// Settlement state of a two-sided escrow. The taker fields are set by `accept`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Collateral {
+    // If set, anyone may settle from this time on without both confirmations
+    pub settle_at: Option<i64>,
+    pub taker: Pubkey,
+    pub taker_deposit_token_account: Pubkey,
+    // Token B held in the taker vault (net of any Token-2022 transfer fee)
+    pub taker_amount: u64,
+    pub initializer_confirmed: bool,
+    pub taker_confirmed: bool,
+}
+
+/// NFT mode options given to `initialize`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct NftTerms {
+    // Token A must be an NFT: supply 1, 0 decimals, no mint authority
+    pub offered_nft: bool,
+    // Token B is a Token-2022 group mint; filled by `fill_collection_bid` with any member NFT
+    pub requested_collection: bool,
+}
+
+/// Two-sided terms given to `initialize`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct CollateralTerms {
+    pub settle_at: Option<i64>,
+}
+
+/// Dutch-auction schedule of an escrow; the ask reaches `floor_price` at `expires_at`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct DutchAuction {
+    pub floor_price: u64,
+    pub start_time: i64,
+    // 0 for a continuous decay, otherwise the price only drops every `step_seconds`
+    pub step_seconds: i64,
+}
+
+/// Dutch-auction terms given to `initialize`; the auction starts when the escrow is created.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct DutchAuctionTerms {
+    pub floor_price: u64,
+    pub step_seconds: i64,
+}
+
+/// Kind of asset on one leg of an escrow. `NativeSol` legs use the native mint, but the
+/// program wraps and unwraps SOL itself so users never handle wSOL.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AssetKind {
+    Token,
+    NativeSol,
+}
+
+impl AssetKind {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            AssetKind::Token => "token",
+            AssetKind::NativeSol => "nativeSol",
+        }
+    }
+}
+
+// Space calculation: 
+// 8 (discriminator) + 32*7 (Pubkeys) + 8*4 (u64 amounts) + 8 (unique_seed) + 8 (expires_at) + 1 (bump)
+// + 33 (allowed_taker) + 1 (taker_pays_transfer_fee) + 1*2 (asset kinds)
+// + 25 (dutch_auction: 1 + 8 floor_price + 8 start_time + 8 step_seconds) + 1 (status)
+// + 84 (collateral: 1 + 9 settle_at + 32*2 + 8 taker_amount + 1*2 confirmations)
+// + 1 (collection_bid) + 33 (allowlist_root) = 461 bytes.
+pub(crate) const ESCROW_ACCOUNT_SPACE: usize = 461; 
+// The baseline layout, which ended at `bump`: 8 (discriminator) + 32*5 (Pubkeys)
+// + 8*2 (u64 amounts) + 8 (unique_seed) + 8 (expires_at) + 1 (bump)
+pub(crate) const LEGACY_ESCROW_ACCOUNT_SPACES: [usize; 1] = [201];
+
+/// One-sided escrow for off-chain services, settled by the buyer or an arbiter.
+#[account]
+pub struct ServiceEscrow {
+    pub buyer: Pubkey,
+    pub seller: Pubkey,
+    // The only party that can split the vault while a dispute is open
+    pub arbiter: Pubkey,
+    pub mint: Pubkey,
+    pub token_program: Pubkey,
+
+    // The amount held in the vault (net of any Token-2022 transfer fee)
+    pub amount: u64,
+    pub unique_seed: [u8; 8],
+    pub status: ServiceStatus,
+    pub created_at: i64,
+
+    // Until then the buyer may release or either party may dispute; afterwards anyone may release
+    pub review_deadline: i64,
+
+    // How long the arbiter has to rule once a dispute is raised
+    pub dispute_timeout_seconds: i64,
+
+    // Set by `raise_dispute`; past it, the dispute can be closed with a full refund
+    pub dispute_deadline: i64,
+    pub bump: u8,
+}
+
+/// Funded -> (release) closed, or Funded -> Disputed -> (resolve_dispute) closed.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ServiceStatus {
+    Funded,
+    Disputed,
+}
+
+// 8 (discriminator) + 32*5 (Pubkeys) + 8 (amount) + 8 (unique_seed) + 1 (status)
+// + 8*4 (timestamps and timeout) + 1 (bump) = 218 bytes.
+pub(crate) const SERVICE_ESCROW_ACCOUNT_SPACE: usize = 218;
+
+/// Escrow paid out in stages: each milestone is approved by the payer or, past its
+/// deadline, refunded to them.
+#[account]
+pub struct MilestoneEscrow {
+    pub payer: Pubkey,
+    pub payee: Pubkey,
+    pub mint: Pubkey,
+    pub token_program: Pubkey,
+    pub unique_seed: [u8; 8],
+    pub created_at: i64,
+
+    // At most `MAX_MILESTONES`, in the order they were given at creation
+    pub milestones: Vec<Milestone>,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Milestone {
+    pub amount: u64,
+    // Past this, a pending milestone can be refunded; `None` means approval only
+    pub deadline: Option<i64>,
+    pub status: MilestoneStatus,
+}
+
+/// Milestone terms given to `create_milestone_escrow`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct MilestoneTerms {
+    pub amount: u64,
+    pub deadline: Option<i64>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MilestoneStatus {
+    Pending,
+    Approved,
+    Refunded,
+}
+
+// 8 (discriminator) + 32*4 (Pubkeys) + 8 (unique_seed) + 8 (created_at)
+// + 4 + 18*MAX_MILESTONES (Vec<Milestone>: 8 amount + 9 deadline + 1 status) + 1 (bump)
+pub(crate) const MILESTONE_ESCROW_ACCOUNT_SPACE: usize = 8 + 32 * 4 + 8 + 8 + 4 + 18 * MAX_MILESTONES + 1;
+
+/// Linear vesting escrow. Shares the swap escrow's PDA seeds, so a `unique_seed` is
+/// used by either a swap or a vesting escrow, never both.
+#[account]
+pub struct VestingEscrow {
+    pub initializer: Pubkey,
+    pub beneficiary: Pubkey,
+    pub mint: Pubkey,
+    pub token_program: Pubkey,
+
+    // The amount held in the vault at creation (net of any Token-2022 transfer fee)
+    pub total_amount: u64,
+    pub claimed_amount: u64,
+    pub start_time: i64,
+
+    // Nothing is claimable before this, after which the linear schedule applies from `start_time`
+    pub cliff_time: i64,
+    pub end_time: i64,
+
+    // Whether the initializer may take back the unvested remainder
+    pub revocable: bool,
+    pub unique_seed: [u8; 8],
+    pub bump: u8,
+}
+
+// 8 (discriminator) + 32*4 (Pubkeys) + 8*2 (amounts) + 8*3 (timestamps) + 1 (revocable)
+// + 8 (unique_seed) + 1 (bump) = 186 bytes.
+pub(crate) const VESTING_ESCROW_ACCOUNT_SPACE: usize = 186;
+
+/// English auction over a vault of Token A, paid in `bid_mint`. Each bidder escrows their
+/// bid in a vault of their own (`Bid`), so outbid bidders can always get their funds back.
+#[account]
+pub struct Auction {
+    pub seller: Pubkey,
+    pub deposit_mint: Pubkey,
+    pub bid_mint: Pubkey,
+    pub deposit_token_program: Pubkey,
+    pub bid_token_program: Pubkey,
+
+    // The amount of Token A held in the vault (net of any Token-2022 transfer fee)
+    pub amount: u64,
+
+    // The first bid must reach this; later bids must beat the leader by `min_increment`
+    pub reserve_price: u64,
+    pub min_increment: u64,
+    pub highest_bid: u64,
+    pub highest_bidder: Option<Pubkey>,
+    pub expires_at: i64,
+
+    // Bid accounts still open; the auction closes once it is settled and this reaches zero
+    pub open_bids: u32,
+    pub settled: bool,
+    pub unique_seed: [u8; 8],
+    pub bump: u8,
+}
+
+// 8 (discriminator) + 32*5 (Pubkeys) + 8*4 (amounts) + 33 (highest_bidder) + 8 (expires_at)
+// + 4 (open_bids) + 1 (settled) + 8 (unique_seed) + 1 (bump) = 255 bytes.
+pub(crate) const AUCTION_ACCOUNT_SPACE: usize = 255;
+
+/// A bidder's escrowed bid on an auction; its vault is `[b"vault", bid]`.
+#[account]
+pub struct Bid {
+    pub auction: Pubkey,
+    pub bidder: Pubkey,
+    pub amount: u64,
+    pub bump: u8,
+}
+
+// 8 (discriminator) + 32*2 (Pubkeys) + 8 (amount) + 1 (bump) = 81 bytes.
+pub(crate) const BID_ACCOUNT_SPACE: usize = 81;
+
+/// A taker's standing counter-offer on an escrow, with its Token B held in
+/// `[b"vault", counter_offer]`. One per (escrow, taker).
+#[account]
+pub struct CounterOffer {
+    pub escrow_state: Pubkey,
+    pub taker: Pubkey,
+
+    // Token B held in the counter-offer vault (net of any Token-2022 transfer fee)
+    pub taker_amount: u64,
+
+    // The escrow's remaining Token A when the offer was made; it must be unchanged to accept
+    pub initializer_amount: u64,
+    pub expires_at: i64,
+    pub bump: u8,
+}
+
+// 8 (discriminator) + 32*2 (Pubkeys) + 8*2 (amounts) + 8 (expires_at) + 1 (bump) = 97 bytes.
+pub(crate) const COUNTER_OFFER_ACCOUNT_SPACE: usize = 97;
+
+/// Token B an allowlisted wallet has paid into an escrow, checked against its per-wallet
+/// cap. One per (escrow, taker), created on the wallet's first capped fill.
+#[account]
+pub struct AllowlistFill {
+    pub escrow_state: Pubkey,
+    pub taker: Pubkey,
+    pub filled_amount: u64,
+    pub bump: u8,
+}
+
+// 8 (discriminator) + 32*2 (Pubkeys) + 8 (filled_amount) + 1 (bump) = 81 bytes.
+pub(crate) const ALLOWLIST_FILL_ACCOUNT_SPACE: usize = 81;
+
+/// Off-chain order a maker signs instead of creating an escrow (see `fill_signed_order`)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct SignedOrder {
+    pub maker: Pubkey,
+    // Token A, moved out of the maker's account by the order delegate PDA
+    pub maker_mint: Pubkey,
+    pub maker_amount: u64,
+    // Token B, paid by the taker
+    pub taker_mint: Pubkey,
+    pub taker_amount: u64,
+    pub allowed_taker: Option<Pubkey>,
+    pub expires_at: i64,
+    // Chosen by the maker; each (maker, nonce) fills or is canceled at most once
+    pub nonce: u64,
+}
+
+/// Marks a signed order's nonce as spent, by a fill or a cancellation
+#[account]
+pub struct OrderNonce {
+    pub maker: Pubkey,
+    pub nonce: u64,
+    pub filled: bool,
+    pub canceled: bool,
+    pub bump: u8,
+}
+
+// 8 (discriminator) + 32 (maker) + 8 (nonce) + 1*2 (flags) + 1 (bump) = 51 bytes.
+pub(crate) const ORDER_NONCE_ACCOUNT_SPACE: usize = 51;
+
+/// Escrow swapping a basket of offered tokens for a basket of requested tokens. Each
+/// offered leg's tokens sit in `[b"vault", basket_escrow, mint]`. Baskets only count
+/// towards the global escrow totals, not the per-mint stats.
+#[account]
+pub struct BasketEscrow {
+    pub initializer: Pubkey,
+    pub unique_seed: [u8; 8],
+    pub expires_at: i64,
+
+    // If set, only this taker may take the basket
+    pub allowed_taker: Option<Pubkey>,
+    pub offered: Vec<BasketLeg>,
+    pub requested: Vec<BasketLeg>,
+    pub bump: u8,
+}
+
+/// One token of a basket. For offered legs `amount` is what the vault holds and
+/// `token_account` the initializer's refund account; for requested legs they are the
+/// price and the initializer's receiving account.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BasketLeg {
+    pub mint: Pubkey,
+    pub token_program: Pubkey,
+    pub amount: u64,
+    pub token_account: Pubkey,
+}
+
+// 8 (discriminator) + 32 (initializer) + 8 (unique_seed) + 8 (expires_at) + 33 (allowed_taker)
+// + 2 * (4 + 104 * MAX_BASKET_LEGS) (legs: 32*3 Pubkeys + 8 amount) + 1 (bump) = 1138 bytes.
+pub(crate) const BASKET_ESCROW_ACCOUNT_SPACE: usize = 8 + 32 + 8 + 8 + 33 + 2 * (4 + 104 * MAX_BASKET_LEGS) + 1;
+
+/// Program-wide counters. The value fields add up raw amounts across mints; `MintStats`
+/// holds the per-mint totals.
+#[account]
+pub struct GlobalStats {
+    pub total_escrows_created: u64,
+    pub total_escrows_completed: u64,
+    pub total_escrows_canceled: u64,
+    pub total_value_locked: u64,
+    pub total_value_released: u64,
+    pub total_taker_side_fees: u64,
+    pub total_initializer_side_fees: u64,
+    pub bump: u8,
+}
+// 8 (discriminator) + 8*7 (counters) + 1 (bump)
+pub(crate) const GLOBAL_STATS_ACCOUNT_SPACE: usize = 65;
+// Before the fee totals were added: 8 (discriminator) + 8*5 (counters) + 1 (bump)
+pub(crate) const LEGACY_GLOBAL_STATS_ACCOUNT_SPACES: [usize; 1] = [49];
+
+/// Per-mint counters, so amounts are only ever summed within a single mint.
+#[account]
+pub struct MintStats {
+    pub mint: Pubkey,
+    // Currently held in escrow vaults
+    pub value_locked: u64,
+    // Paid out of vaults to takers (and the treasury)
+    pub value_released: u64,
+    // Returned to initializers by cancel, refund_expired or an amend withdrawal
+    pub value_refunded: u64,
+    // Fills in which this mint was either leg
+    pub fill_count: u64,
+    // Amount of this mint traded in fills, on either leg
+    pub volume: u64,
+    // Protocol fees collected in this mint
+    pub fees_collected: u64,
+    pub bump: u8,
+}
+// 8 (discriminator) + 32 (mint) + 8*6 (counters) + 1 (bump)
+pub(crate) const MINT_STATS_ACCOUNT_SPACE: usize = 89;
+
+// Every instruction that moves a mint's tokens in or out of escrow goes through these, so the
+// counters always mean the same thing. `value_locked` saturates rather than failing, so a vault
+// funded before its mint had stats can still be paid out.
+impl MintStats {
+    // The first escrow for a mint creates its stats account
+    pub(crate) fn init_if_needed(&mut self, mint: Pubkey, bump: u8) {
+        if self.mint == Pubkey::default() {
+            self.mint = mint;
+            self.bump = bump;
+        }
+    }
+
+    pub(crate) fn record_lock(&mut self, amount: u64) -> Result<()> {
+        self.value_locked = self.value_locked.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+        Ok(())
+    }
+
+    // Paid out of a vault to a taker, a seller or the treasury
+    pub(crate) fn record_release(&mut self, amount: u64) -> Result<()> {
+        self.value_locked = self.value_locked.saturating_sub(amount);
+        self.value_released = self.value_released.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+        Ok(())
+    }
+
+    // Returned to whoever funded the vault
+    pub(crate) fn record_refund(&mut self, amount: u64) -> Result<()> {
+        self.value_locked = self.value_locked.saturating_sub(amount);
+        self.value_refunded = self.value_refunded.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+        Ok(())
+    }
+
+    // `fills` fills that traded `volume` of this mint and collected `fees` in it
+    pub(crate) fn record_fills(&mut self, fills: u64, volume: u64, fees: u64) -> Result<()> {
+        self.fill_count = self.fill_count.checked_add(fills).ok_or(ErrorCode::Overflow)?;
+        self.volume = self.volume.checked_add(volume).ok_or(ErrorCode::Overflow)?;
+        self.fees_collected = self.fees_collected.checked_add(fees).ok_or(ErrorCode::Overflow)?;
+        Ok(())
+    }
+}
+
+/// Protocol fee settings, paid out to token accounts owned by `treasury`.
+#[account]
+pub struct Config {
+    pub admin: Pubkey,
+    pub treasury: Pubkey,
+    // Fee in basis points, applied to every charged leg
+    pub fee_bps: u16,
+    // Skim the fee from the Token B paid by the taker
+    pub charge_taker_side: bool,
+    // Skim the fee from the Token A released to the taker
+    pub charge_initializer_side: bool,
+    // Blocks `initialize` and `exchange`; exits stay open
+    pub paused: bool,
+    // Admin proposed by `propose_authority`, awaiting `accept_authority`
+    pub pending_admin: Option<Pubkey>,
+    // Range of durations `check_duration` accepts, set with `set_duration_bounds`
+    pub min_escrow_duration_seconds: i64,
+    pub max_escrow_duration_seconds: i64,
+    pub bump: u8,
+}
+// 8 (discriminator) + 32*2 (Pubkeys) + 2 (fee_bps) + 1*3 (flags) + 33 (Option<Pubkey>)
+// + 8*2 (duration bounds) + 1 (bump)
+pub(crate) const CONFIG_ACCOUNT_SPACE: usize = 127;
+// Before `paused` and `pending_admin`, and before the duration bounds were added
+pub(crate) const LEGACY_CONFIG_ACCOUNT_SPACES: [usize; 2] = [77, 111];
@@ -212,7 +212,7 @@ const page = () => {
                                                                     </button>
                                                                     : <>
                                                                         <button className='text-violet-400 text-lg hover:text-violet-500 flex gap-2 p-2 items-center w-full cursor-pointer'
-                                                                            onClick={() => exchangeEscrow.mutateAsync({ uniqueSeed: escrow.account.uniqueSeed.toString(), initializerKey: escrow.account.initializerKey, escrowPDA: escrow.publicKey.toString(), depositTokenMint: escrow.tokenA.metadata.mintAddress, receiveTokenMint: escrow.tokenB.metadata.mintAddress }).then(() => toast.success("Successfully Exchanged Tokens"))}
+                                                                            onClick={() => exchangeEscrow.mutateAsync({ uniqueSeed: escrow.account.uniqueSeed.toString(), initializerKey: escrow.account.initializerKey, escrowPDA: escrow.publicKey.toString(), depositTokenMint: escrow.tokenA.metadata.mintAddress, receiveTokenMint: escrow.tokenB.metadata.mintAddress, initializerAmount: escrow.account.initializerAmount.toString(), takerExpectedAmount: escrow.account.takerExpectedAmount.toString() }).then(() => toast.success("Successfully Exchanged Tokens"))}
                                                                         >
                                                                             {(pendingId == escrow.account.uniqueSeed.toString() && isMutating) ? <Loader /> :
                                                                                 <>
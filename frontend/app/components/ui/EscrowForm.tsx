@@ -99,6 +99,7 @@ export const EscrowFormModal: React.FC<EscrowFormModalProps> = ({ isOpen, onClos
                                         <InputGroup label="Token B Mint Address" name="takerExpectedMint" value={formData.takerExpectedMint} onChange={handleChange} placeholder="Base58 Mint Address (Token B)" disabled={isMutating || !!data} />
                                         <InputGroup label="Deposit Amount (Token A)" name="initializerAmount" type="number" value={formData.initializerAmount} onChange={handleChange} placeholder="e.g., 10000" disabled={isMutating} />
                                         <InputGroup label="Expected Amount (Token B)" name="takerExpectedAmount" type="number" value={formData.takerExpectedAmount} onChange={handleChange} placeholder="e.g., 10" disabled={isMutating} />
+                                        <InputGroup label="Designated Taker (optional)" name="allowedTaker" value={formData.allowedTaker ?? ''} onChange={handleChange} placeholder="Base58 wallet address, leave empty for a public deal" disabled={isMutating} />
                                         <div className="flex gap-3 items-end">
                                             <InputGroup
                                                 type="number"
@@ -4,7 +4,7 @@ import {
     SystemProgram,
     SYSVAR_RENT_PUBKEY,
 } from '@solana/web3.js';
-import { TOKEN_2022_PROGRAM_ID } from '@solana/spl-token';
+import { getAssociatedTokenAddressSync, NATIVE_MINT } from '@solana/spl-token';
 import { useProgram } from './useProgram';
 import { ensureATA, fetchTokenMetadata, generateUniqueSeed, getMintProgramId } from '@/app/utils/token';
 import { GlobalStats } from '@/app/types/query';
@@ -13,7 +13,7 @@ import { Escrow } from '../types/query';
 const API_BASE = "http://localhost:3000"
 
 export const useEscrowActions = () => {
-    const { program, PROGRAM_ID, sendTransaction, publicKey, anchorWallet, getEscrowStatePDA, getVaultPDA, getGlobalStatsPDA, connection } = useProgram()
+    const { program, PROGRAM_ID, sendTransaction, publicKey, anchorWallet, getEscrowStatePDA, getVaultPDA, getGlobalStatsPDA, getConfigPDA, getMintStatsPDA, connection } = useProgram()
 
     async function fetchAllEscrows(
     ): Promise<Escrow[]> {
@@ -89,39 +89,46 @@ export const useEscrowActions = () => {
         takerExpectedAmount: number,
         initializerDepositMint: PublicKey,
         takerExpectedMint: PublicKey,
-        durationInSeconds: number
+        durationInSeconds: number,
+        allowedTaker: PublicKey | null = null,
+        takerPaysTransferFee: boolean = false
     ) => {
         if (!program || !publicKey) {
             throw new Error("Wallet not connected or program not loaded.");
         }
         const initializerKey = anchorWallet?.publicKey!;
 
+        // Each mint may live under a different token program (legacy SPL or Token-2022)
         const depositTokenProgramId = await getMintProgramId(initializerDepositMint);
         const receiveTokenProgramId = await getMintProgramId(takerExpectedMint);
 
-        if (!depositTokenProgramId.equals(receiveTokenProgramId)) {
-            throw new Error(
-                "Mints must use the same token program (both legacy SPL or both Token-2022)."
-            );
-        }
-
-        const tokenProgramToUse = depositTokenProgramId;
         const programId = program.programId; // Get the ID of your Anchor program
 
         const uniqueSeed = generateUniqueSeed();
         const escrowStatePDA = getEscrowStatePDA(initializerKey, uniqueSeed);
         const globalStatsPDA = getGlobalStatsPDA(programId); // NEW: Global stats PDA
+        const configPDA = getConfigPDA(programId);
         const vaultAccountPDA = getVaultPDA(escrowStatePDA);
 
-        // Ensure ATAs exist
-        const initializerDepositTokenAccount = await ensureATA(initializerDepositMint, initializerKey, sendTransaction);
-        const initializerReceiveTokenAccount = await ensureATA(takerExpectedMint, initializerKey, sendTransaction);
-
         // Convert amounts to Anchor's internal BN (BigNumber) format
         const initializerAmountBN = new anchor.BN(initializerAmount);
         const takerExpectedAmountBN = new anchor.BN(takerExpectedAmount);
         const durationInSecondsBN = new anchor.BN(durationInSeconds)
 
+        // Picking the native mint means native SOL: the program wraps and unwraps it
+        const nativeDeposit = initializerDepositMint.equals(NATIVE_MINT);
+        const nativeReceive = takerExpectedMint.equals(NATIVE_MINT);
+        const depositAsset = nativeDeposit ? { nativeSol: {} } : { token: {} };
+        const receiveAsset = nativeReceive ? { nativeSol: {} } : { token: {} };
+
+        // Ensure ATAs exist; native SOL legs move lamports, so their token accounts are omitted
+        const initializerDepositTokenAccount = nativeDeposit
+            ? null
+            : await ensureATA(initializerDepositMint, initializerKey, sendTransaction);
+        const initializerReceiveTokenAccount = nativeReceive
+            ? null
+            : await ensureATA(takerExpectedMint, initializerKey, sendTransaction);
+
         try {
             const txSignature = await program.methods
                 .initialize(
@@ -129,6 +136,14 @@ export const useEscrowActions = () => {
                     takerExpectedAmountBN,
                     durationInSecondsBN,
                     uniqueSeed.toJSON().data,
+                    allowedTaker,
+                    takerPaysTransferFee,
+                    depositAsset,
+                    receiveAsset,
+                    null, // Fixed price, no Dutch auction
+                    null, // One-sided: takers swap immediately
+                    null, // No NFT mode checks
+                    null, // Open to any taker, no allowlist
                 )
                 .accounts({
                     initializer: initializerKey,
@@ -138,9 +153,13 @@ export const useEscrowActions = () => {
                     initializerReceiveTokenAccount: initializerReceiveTokenAccount,
                     escrowState: escrowStatePDA,
                     globalStats: globalStatsPDA,
+                    depositMintStats: getMintStatsPDA(initializerDepositMint),
+                    receiveMintStats: getMintStatsPDA(takerExpectedMint),
+                    config: configPDA,
                     vaultAccount: vaultAccountPDA,
                     systemProgram: SystemProgram.programId,
-                    tokenProgram: tokenProgramToUse,
+                    depositTokenProgram: depositTokenProgramId,
+                    receiveTokenProgram: receiveTokenProgramId,
                     rent: SYSVAR_RENT_PUBKEY,
                 })
                 .rpc({
@@ -222,12 +241,16 @@ export const useEscrowActions = () => {
                 .cancel()
                 .accounts({
                     initializer: initializerKey,
-                    initializerDepositTokenAccount: initializerDepositTokenAccountKey,
+                    // A native SOL escrow records the default key and refunds straight to the wallet
+                    initializerDepositTokenAccount: initializerDepositTokenAccountKey.equals(PublicKey.default)
+                        ? null
+                        : initializerDepositTokenAccountKey,
                     vaultAccount: vaultAccountPDA,
                     globalStats: globalStatsPDA,
+                    depositMintStats: getMintStatsPDA(mintAddress),
                     escrowState: escrowPDA,
                     initializerDepositMint: mintAddress,
-                    tokenProgram: tokenProgramId, // Use the SPL Token Program ID
+                    depositTokenProgram: tokenProgramId, // Token program owning Token A
                 })
                 // 3. Send the transaction
                 .rpc();
@@ -246,46 +269,100 @@ export const useEscrowActions = () => {
         initializerKey: PublicKey,
         depositTokenMint: PublicKey,
         receiveTokenMint: PublicKey,
+        initializerAmount: anchor.BN,
+        takerExpectedAmount: anchor.BN,
     ): Promise<{ escrow_pda: string; initializerKey: string; }> {
 
         const takerKey = anchorWallet?.publicKey!;
         const vaultAccountPDA = getVaultPDA(escrowPDA);
         const globalStatsPDA = getGlobalStatsPDA(PROGRAM_ID);
 
-        const takerDepositTokenAccount = await ensureATA(
-            receiveTokenMint,
-            takerKey,
-            sendTransaction,
-        );
+        const escrowState = await (program!.account as any).escrowState.fetch(escrowPDA);
+        const nativeDeposit = "nativeSol" in escrowState.depositAsset;
+        const nativeReceive = "nativeSol" in escrowState.receiveAsset;
+
+        // Native SOL legs move lamports, so their token accounts are omitted
+        const takerDepositTokenAccount = nativeReceive
+            ? null
+            : await ensureATA(receiveTokenMint, takerKey, sendTransaction);
+
+        const takerReceiveTokenAccount = nativeDeposit
+            ? null
+            : await ensureATA(depositTokenMint, takerKey, sendTransaction);
 
-        const takerReceiveTokenAccount = await ensureATA(
-            depositTokenMint,
-            takerKey,
-            sendTransaction,
-        );
+        const initializerReceiveTokenAccount = nativeReceive
+            ? null
+            : await ensureATA(receiveTokenMint, initializerKey, sendTransaction);
 
-        const initializerReceiveTokenAccount = await ensureATA(
-            receiveTokenMint,
-            initializerKey,
-            sendTransaction,
-        );
+        // A native SOL release is unwrapped through a temporary account owned by the escrow
+        const unwrapAccount = nativeDeposit
+            ? PublicKey.findProgramAddressSync([Buffer.from("unwrap"), escrowPDA.toBuffer()], PROGRAM_ID)[0]
+            : null;
+
+        // Fill whatever Token B amount is still open on the escrow
+        const fillAmount = escrowState.takerExpectedAmount.sub(escrowState.filledAmount);
+
+        // Protocol fee treasury accounts, only needed for the charged sides
+        const configPDA = getConfigPDA(PROGRAM_ID);
+        const config = await (program!.account as any).config.fetch(configPDA);
+
+        // Slippage guard: price the fill at the terms the taker was shown
+        let expectedInitializerAmount = fillAmount.mul(initializerAmount).div(takerExpectedAmount);
+        if (config.chargeInitializerSide) {
+            expectedInitializerAmount = expectedInitializerAmount.sub(expectedInitializerAmount.muln(config.feeBps).divn(10_000));
+        }
+        const maxTakerAmount = escrowState.takerPaysTransferFee
+            ? fillAmount.muln(101).divn(100) // leave room for the Token-2022 fee gross-up
+            : fillAmount;
+        const treasuryDepositTokenAccount = config.chargeInitializerSide
+            ? getAssociatedTokenAddressSync(depositTokenMint, config.treasury, true, escrowState.depositTokenProgram)
+            : null;
+        const treasuryReceiveTokenAccount = config.chargeTakerSide
+            ? getAssociatedTokenAddressSync(receiveTokenMint, config.treasury, true, escrowState.receiveTokenProgram)
+            : null;
+
+        // Allowlisted escrows need the taker's Merkle proof (and cap, if any) from the backend
+        let allowlistProof: number[][] = [];
+        let walletCap: anchor.BN | null = null;
+        let allowlistFill: PublicKey | null = null;
+        if (escrowState.allowlistRoot) {
+            const root = anchor.utils.bytes.bs58.encode(Uint8Array.from(escrowState.allowlistRoot));
+            const { data } = await axios.get(`${API_BASE}/api/allowlists/${root}/proofs/${takerKey.toBase58()}`);
+            allowlistProof = data.proof.map((node: string) => Array.from(anchor.utils.bytes.bs58.decode(node)));
+            if (data.cap !== null) {
+                walletCap = new anchor.BN(data.cap);
+                [allowlistFill] = PublicKey.findProgramAddressSync(
+                    [Buffer.from("allowlist-fill"), escrowPDA.toBuffer(), takerKey.toBuffer()],
+                    PROGRAM_ID,
+                );
+            }
+        }
 
         try {
             const tx = await program!.methods
-                .exchange()
+                .exchange(fillAmount, expectedInitializerAmount, maxTakerAmount, depositTokenMint, receiveTokenMint, allowlistProof, walletCap)
                 .accounts({
                     taker: takerKey,
                     takerDepositTokenAccount,
                     takerReceiveTokenAccount,
                     initializerReceiveTokenAccount,
+                    initializerDepositTokenAccount: nativeDeposit ? null : escrowState.initializerDepositTokenAccount,
                     escrowState: escrowPDA,
                     vaultAccount: vaultAccountPDA,
                     globalStats: globalStatsPDA,
+                    depositMintStats: getMintStatsPDA(depositTokenMint),
+                    receiveMintStats: getMintStatsPDA(receiveTokenMint),
                     initializerDepositMint: depositTokenMint,
                     takerExpectedMint: receiveTokenMint,
                     initializerKey: initializerKey,
-                    tokenProgram: TOKEN_2022_PROGRAM_ID,
+                    depositTokenProgram: escrowState.depositTokenProgram,
+                    receiveTokenProgram: escrowState.receiveTokenProgram,
+                    config: configPDA,
+                    treasuryDepositTokenAccount,
+                    treasuryReceiveTokenAccount,
                     systemProgram: SystemProgram.programId,
+                    allowlistFill,
+                    unwrapAccount,
                     rent: SYSVAR_RENT_PUBKEY,
                 })
                 .rpc();
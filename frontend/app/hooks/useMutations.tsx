@@ -1,6 +1,7 @@
 import { useMutation, useQueryClient } from "@tanstack/react-query";
 import Cookies from "js-cookie"
 import { PublicKey } from "@solana/web3.js";
+import * as anchor from "@coral-xyz/anchor";
 import { useEscrowActions } from "./useEscrowActions";
 import axios from "axios";
 import { MutationHookProps } from "../types/props";
@@ -51,7 +52,8 @@ export const useMutations = ({ setPendingId }: MutationHookProps = {}) => {
                 parseFloat(params.takerExpectedAmount),
                 new PublicKey(params.initializerDepositMint),
                 new PublicKey(params.takerExpectedMint),
-                convertTimeToSeconds(Number(params.durationValue), params.durationUnit)!
+                convertTimeToSeconds(Number(params.durationValue), params.durationUnit)!,
+                params.allowedTaker ? new PublicKey(params.allowedTaker) : null
             );
         },
         onSuccess: ({ account, publicKey }) => {
@@ -74,6 +76,8 @@ export const useMutations = ({ setPendingId }: MutationHookProps = {}) => {
                 new PublicKey(params.initializerKey),
                 new PublicKey(params.depositTokenMint),
                 new PublicKey(params.receiveTokenMint),
+                new anchor.BN(params.initializerAmount),
+                new anchor.BN(params.takerExpectedAmount),
             )
         },
         onSuccess: (data: ExchangeQuery) => {
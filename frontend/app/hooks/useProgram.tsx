@@ -61,10 +61,29 @@ export const useProgram = () => {
         );
         return pda;
     };
+
+    const getConfigPDA = (programId: PublicKey) => {
+        const [pda, _] = PublicKey.findProgramAddressSync(
+            [Buffer.from("config")],
+            programId
+        );
+        return pda;
+    };
+
+    // Per-mint stats account, seeds = [b"mint-stats", mint]
+    const getMintStatsPDA = (mint: PublicKey) => {
+        const [pda, _] = PublicKey.findProgramAddressSync(
+            [Buffer.from("mint-stats"), mint.toBuffer()],
+            PROGRAM_ID
+        );
+        return pda;
+    };
     return {
         getVaultPDA,
         getEscrowStatePDA,
         getGlobalStatsPDA,
+        getConfigPDA,
+        getMintStatsPDA,
         sendTransaction,
         disconnect,
         program,
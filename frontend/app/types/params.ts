@@ -6,6 +6,8 @@ export interface ExchangeParams {
     initializerKey: string;
     depositTokenMint: string,
     receiveTokenMint: string,
+    initializerAmount: string,
+    takerExpectedAmount: string,
 }
 
 export interface UpdateParams {
@@ -80,6 +80,12 @@ export interface Accounts {
     uniqueSeed: number[]; // Anchor decodes [u8; 8] as number[]
     expiresAt: anchor.BN;        // ← ADD THIS: i64 → BN (signed)
     bump: number;
+    remainingAmount?: anchor.BN; // Token A still in the vault
+    filledAmount?: anchor.BN;    // Token B paid by takers so far
+    allowedTaker?: string | null; // Designated taker for private escrows
+    depositAsset?: string;        // "token" or "nativeSol"
+    receiveAsset?: string;        // "token" or "nativeSol"
+    allowlistRoot?: number[] | null; // Merkle root of the wallets allowed to fill
 }
 
 export interface Escrow {
@@ -5,4 +5,5 @@ export interface EscrowFormState {
     takerExpectedMint: string;
     durationValue: string;
     durationUnit: 'days' | 'hours' | 'mins' | 'sec';
+    allowedTaker?: string;
 }
\ No newline at end of file